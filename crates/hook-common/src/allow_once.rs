@@ -0,0 +1,150 @@
+//! "Allow once" escape hatch for blocking hooks.
+//!
+//! A blocked command runs once when the user sets `<HOOK>_ALLOW_ONCE` (e.g.
+//! `RISKY_COMMAND_GUARD_ALLOW_ONCE`) to its [`hash`], which the block message
+//! prints. Only the user's environment counts: re-submitting the command, or
+//! setting the variable inside the command itself, never gets past a block.
+//! Approvals are consumed by use, per session, in the state directory (see
+//! [`crate::state`]).
+
+use crate::state;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct Entries {
+    /// Hashes already let through via the environment variable.
+    used_env: Vec<String>,
+}
+
+pub struct AllowOnce {
+    path: PathBuf,
+    env_hash: Option<String>,
+    entries: Entries,
+}
+
+/// Stable short hash of a command (64-bit FNV-1a, hex).
+pub fn hash(command: &str) -> String {
+    let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+    for b in command.bytes() {
+        h ^= u64::from(b);
+        h = h.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{:016x}", h)
+}
+
+/// `risky-command-guard` → `RISKY_COMMAND_GUARD_ALLOW_ONCE`.
+pub fn env_var(hook: &str) -> String {
+    format!("{}_ALLOW_ONCE", hook.to_ascii_uppercase().replace('-', "_"))
+}
+
+/// The line a block message ends with: how the user lets `command` (or the
+/// path of a blocked write) through once.
+pub fn hint(hook: &str, command: &str) -> String {
+    format!(
+        "Only if the user explicitly confirms this is intended: ask them to set {}={} in the \
+         environment Claude Code runs in, then re-submit the call unchanged to run it once.",
+        env_var(hook),
+        hash(command)
+    )
+}
+
+impl AllowOnce {
+    /// Session state for `hook`, plus any approval from `<HOOK>_ALLOW_ONCE`.
+    pub fn load(hook: &str, session_id: &str) -> Self {
+        let env_hash = env::var(env_var(hook)).ok().filter(|h| !h.trim().is_empty());
+        AllowOnce::at(state::session_path(hook, session_id, "allow-once.json"), env_hash)
+    }
+
+    pub fn at(path: PathBuf, env_hash: Option<String>) -> Self {
+        let entries = fs::read_to_string(&path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        AllowOnce {
+            path,
+            env_hash: env_hash.map(|h| h.trim().to_string()),
+            entries,
+        }
+    }
+
+    /// Whether `command` may run once despite a block. Consumes the approval.
+    pub fn take(&mut self, command: &str) -> bool {
+        let h = hash(command);
+        if self.env_hash.as_deref() != Some(h.as_str()) || self.entries.used_env.contains(&h) {
+            return false;
+        }
+        self.entries.used_env.push(h);
+        true
+    }
+
+    pub fn save(&self) {
+        if let Ok(json) = serde_json::to_string(&self.entries) {
+            let _ = fs::write(&self.path, json);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch(name: &str) -> PathBuf {
+        let p = env::temp_dir().join(format!("hook-common-allow-once-{}.json", name));
+        let _ = fs::remove_file(&p);
+        p
+    }
+
+    #[test]
+    fn resubmission_alone_is_never_allowed() {
+        let path = scratch("resubmit");
+        let cmd = "mysql -psecret app";
+        for _ in 0..2 {
+            let mut allow = AllowOnce::at(path.clone(), None);
+            assert!(!allow.take(cmd));
+            allow.save();
+        }
+    }
+
+    #[test]
+    fn env_hash_allows_once() {
+        let path = scratch("env");
+        let cmd = "PGPASSWORD=x psql";
+        let mut allow = AllowOnce::at(path.clone(), Some(hash(cmd)));
+        assert!(allow.take(cmd));
+        allow.save();
+
+        let mut allow = AllowOnce::at(path, Some(hash(cmd)));
+        assert!(!allow.take(cmd));
+    }
+
+    #[test]
+    fn env_hash_of_another_command_is_not_allowed() {
+        let mut allow = AllowOnce::at(scratch("different"), Some(hash("mysql -psecret app")));
+        assert!(!allow.take("mysql -psecret other"));
+    }
+
+    #[test]
+    fn hint_names_env_var_and_hash() {
+        let hint = hint("risky-command-guard", "mysql -psecret");
+        assert!(hint.contains(&format!("RISKY_COMMAND_GUARD_ALLOW_ONCE={}", hash("mysql -psecret"))));
+    }
+
+    #[test]
+    fn env_var_name_follows_hook_name() {
+        assert_eq!(env_var("risky-command-guard"), "RISKY_COMMAND_GUARD_ALLOW_ONCE");
+    }
+
+    #[test]
+    fn hash_is_stable() {
+        assert_eq!(hash(""), "cbf29ce484222325");
+        assert_ne!(hash("a"), hash("b"));
+    }
+}
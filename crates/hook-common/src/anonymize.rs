@@ -0,0 +1,139 @@
+//! Optional anonymization of file paths in what hooks log and report.
+//!
+//! With `HOOK_ANONYMIZE_PATHS=1`, the dead-letter log, false-positive reports
+//! and decision-sink reports have their absolute paths rewritten before they
+//! are written: a path inside the project (`$CLAUDE_PROJECT_DIR`, else the
+//! current directory) becomes relative to it, and any other absolute path
+//! becomes `<path:…>`, a short hash of the path. The hash is stable, so
+//! entries about the same file still correlate. What the hook shows Claude is
+//! unchanged.
+//!
+//! Both `/unix/paths` and Windows `C:\paths` (also JSON-escaped, `C:\\paths`)
+//! are recognized; URLs are left alone.
+
+use std::env;
+
+/// [`anonymize_paths`] against the project root when `HOOK_ANONYMIZE_PATHS=1`;
+/// `text` as is otherwise.
+pub fn logged(text: &str) -> String {
+    if env::var("HOOK_ANONYMIZE_PATHS").as_deref() != Ok("1") {
+        return text.to_string();
+    }
+    let root = env::var("CLAUDE_PROJECT_DIR")
+        .ok()
+        .filter(|r| !r.is_empty())
+        .or_else(|| env::current_dir().ok().map(|d| d.to_string_lossy().into_owned()))
+        .unwrap_or_default();
+    anonymize_paths(text, &root)
+}
+
+/// Rewrite every absolute path in `text`: relative to `root` when under it,
+/// `<path:hash>` otherwise.
+pub fn anonymize_paths(text: &str, root: &str) -> String {
+    let root = normalize(root).trim_end_matches('/').to_string();
+    let b = text.as_bytes();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < b.len() {
+        let boundary = i == 0 || is_boundary(b[i - 1]);
+        let len = if boundary { absolute_path_len(&text[i..]) } else { 0 };
+        if len == 0 {
+            let c = text[i..].chars().next().unwrap_or_default();
+            out.push(c);
+            i += c.len_utf8();
+            continue;
+        }
+        out.push_str(&replace(&text[i..i + len], &root));
+        i += len;
+    }
+    out
+}
+
+fn replace(path: &str, root: &str) -> String {
+    let normalized = normalize(path);
+    if !root.is_empty() {
+        if normalized == root {
+            return ".".to_string();
+        }
+        if let Some(rest) = normalized.strip_prefix(root).and_then(|r| r.strip_prefix('/')) {
+            return rest.to_string();
+        }
+    }
+    format!("<path:{:08x}>", fnv1a(&normalized) as u32)
+}
+
+/// Forward slashes only, so `C:\a`, `C:\\a` (JSON) and `C:/a` compare equal.
+fn normalize(path: &str) -> String {
+    path.replace("\\\\", "/").replace('\\', "/")
+}
+
+/// Length of the absolute path at the start of `text`, 0 if there is none.
+fn absolute_path_len(text: &str) -> usize {
+    let b = text.as_bytes();
+    let start = match b {
+        [b'/', c, ..] if is_path_byte(*c) && *c != b'/' => 1,
+        [d, b':', b'\\', b'\\', ..] if d.is_ascii_alphabetic() => 4,
+        [d, b':', b'\\' | b'/', ..] if d.is_ascii_alphabetic() => 3,
+        _ => return 0,
+    };
+    start + b[start..].iter().take_while(|c| is_path_byte(**c)).count()
+}
+
+fn is_path_byte(c: u8) -> bool {
+    !c.is_ascii_whitespace()
+        && !matches!(c, b'"' | b'\'' | b'`' | b',' | b';' | b')' | b']' | b'}' | b'<' | b'>')
+}
+
+/// What may precede a path. `:` is not among them, so `https://host/a` is
+/// left alone.
+fn is_boundary(c: u8) -> bool {
+    c.is_ascii_whitespace() || matches!(c, b'"' | b'\'' | b'`' | b'=' | b'(' | b'[' | b',')
+}
+
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |h, b| (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_under_root_become_relative() {
+        assert_eq!(
+            anonymize_paths("edited /home/ana/app/src/lib.rs (line 3)", "/home/ana/app/"),
+            "edited src/lib.rs (line 3)"
+        );
+        assert_eq!(
+            anonymize_paths(r#"{"cwd":"/home/ana/app","file_path":"/home/ana/app/a.rs"}"#, "/home/ana/app"),
+            r#"{"cwd":".","file_path":"a.rs"}"#
+        );
+        assert_eq!(
+            anonymize_paths(r#"{"file_path":"C:\\Users\\ana\\app\\src\\x.ts"}"#, r"C:\Users\ana\app"),
+            r#"{"file_path":"src/x.ts"}"#
+        );
+    }
+
+    #[test]
+    fn other_absolute_paths_are_hashed_consistently() {
+        let a = anonymize_paths("read /home/ana/.ssh/config", "/home/ana/app");
+        assert!(a.starts_with("read <path:") && !a.contains("ana"), "got: {}", a);
+        // The same file hashes the same in every entry and spelling, another
+        // file differently.
+        let token = a.trim_start_matches("read ");
+        assert_eq!(anonymize_paths("`/home/ana/.ssh/config`", "/x"), format!("`{}`", token));
+        assert_eq!(anonymize_paths(r"C:\a\b", ""), anonymize_paths("C:/a/b", ""));
+        assert_ne!(anonymize_paths("/etc/hosts", ""), anonymize_paths("/etc/passwd", ""));
+    }
+
+    #[test]
+    fn urls_and_relative_paths_are_left_alone() {
+        for text in ["see https://example.org/a/b", "src/lib.rs:12", "ratio 3/4", "a // comment", "dir/"] {
+            assert_eq!(anonymize_paths(text, "/home/ana/app"), text);
+        }
+    }
+}
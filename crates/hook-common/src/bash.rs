@@ -0,0 +1,417 @@
+//! Minimal Bash command-line parsing shared by the Bash hooks.
+//!
+//! Not a full shell parser: enough to split a command into simple commands
+//! and words, find the program each one runs, and read its arguments.
+
+use std::ops::Range;
+
+/// A shell word with its quote characters removed. Backslashes are kept
+/// verbatim so Windows paths stay recognizable.
+#[derive(Debug)]
+pub struct Word {
+    pub text: String,
+    /// Some part of the word was in quotes, so bash keeps its backslashes.
+    pub quoted: bool,
+}
+
+/// Split `command` into simple commands on unquoted `;`, `&`, `|`, `(`, `)`
+/// and newlines, returning the words of each. A backslash-newline is a line
+/// continuation and acts as whitespace.
+pub fn simple_commands(command: &str) -> Vec<Vec<Word>> {
+    let b = command.as_bytes();
+    let mut commands = Vec::new();
+    let mut words: Vec<Word> = Vec::new();
+    let mut cur: Vec<u8> = Vec::new();
+    let mut in_word = false;
+    let mut quoted = false;
+    let mut i = 0;
+
+    fn end_word(cur: &mut Vec<u8>, in_word: &mut bool, quoted: &mut bool, words: &mut Vec<Word>) {
+        if *in_word {
+            words.push(Word {
+                text: String::from_utf8_lossy(cur).into_owned(),
+                quoted: *quoted,
+            });
+        }
+        cur.clear();
+        *in_word = false;
+        *quoted = false;
+    }
+
+    while i < b.len() {
+        let c = b[i];
+        match c {
+            b'\\' if i + 1 < b.len() && b[i + 1] == b'\n' => {
+                end_word(&mut cur, &mut in_word, &mut quoted, &mut words);
+                i += 2;
+            }
+            // `C:\src\ dst` — a drive path's trailing backslash is a separator,
+            // not an escaped space (the same reading windows-bash-guard's rewrite uses).
+            b'\\'
+                if i + 1 < b.len()
+                    && matches!(b[i + 1], b' ' | b'\t')
+                    && is_backslash_drive_path(&String::from_utf8_lossy(&cur)) =>
+            {
+                cur.push(c);
+                i += 1;
+            }
+            b'\\' if i + 1 < b.len() => {
+                cur.extend_from_slice(&b[i..i + 2]);
+                in_word = true;
+                i += 2;
+            }
+            b'\'' | b'"' => {
+                let close = skip_quoted(b, i);
+                let inner_end = if close > i + 1 && b[close - 1] == c { close - 1 } else { close };
+                cur.extend_from_slice(&b[i + 1..inner_end]);
+                in_word = true;
+                quoted = true;
+                i = close;
+            }
+            b' ' | b'\t' | b'\r' => {
+                end_word(&mut cur, &mut in_word, &mut quoted, &mut words);
+                i += 1;
+            }
+            b';' | b'&' | b'|' | b'(' | b')' | b'\n' => {
+                end_word(&mut cur, &mut in_word, &mut quoted, &mut words);
+                if !words.is_empty() {
+                    commands.push(std::mem::take(&mut words));
+                }
+                i += 1;
+            }
+            _ => {
+                cur.push(c);
+                in_word = true;
+                i += 1;
+            }
+        }
+    }
+    end_word(&mut cur, &mut in_word, &mut quoted, &mut words);
+    if !words.is_empty() {
+        commands.push(words);
+    }
+    commands
+}
+
+/// Byte ranges of the simple commands in `command`, split where
+/// [`simple_commands`] splits (so on `&&`, `||`, `;` and `|` too) and trimmed
+/// of whitespace. For rewrites that must stay within one simple command of
+/// the raw text.
+pub fn command_spans(command: &str) -> Vec<Range<usize>> {
+    let b = command.as_bytes();
+    let mut spans = Vec::new();
+    let mut push = |start: usize, end: usize| {
+        let text = &command[start..end];
+        let start = start + (text.len() - text.trim_start().len());
+        let end = start + text.trim().len();
+        if start < end {
+            spans.push(start..end);
+        }
+    };
+    let mut start = 0;
+    let mut i = 0;
+    while i < b.len() {
+        match b[i] {
+            b'\\' => i += 2,
+            b'\'' | b'"' => i = skip_quoted(b, i),
+            b';' | b'&' | b'|' | b'(' | b')' | b'\n' => {
+                push(start, i);
+                i += 1;
+                start = i;
+            }
+            _ => i += 1,
+        }
+    }
+    push(start, b.len());
+    spans
+}
+
+/// Given `b[i]` is a quote, return the index just past the matching closing
+/// quote (or end of string if unterminated). In double quotes a backslash
+/// escapes the next character, so `\"` doesn't close them but `\\"` does.
+fn skip_quoted(b: &[u8], i: usize) -> usize {
+    let quote = b[i];
+    let mut j = i + 1;
+    while j < b.len() {
+        if b[j] == b'\\' && quote == b'"' {
+            j += 2;
+            continue;
+        }
+        if b[j] == quote {
+            return j + 1;
+        }
+        j += 1;
+    }
+    b.len()
+}
+
+/// The words of a simple command as written, quotes included, for checks
+/// that must see the quoting. Line continuations separate words like
+/// whitespace.
+pub fn raw_words(segment: &str) -> Vec<&str> {
+    let b = segment.as_bytes();
+    let mut words = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut i = 0;
+    while i < b.len() {
+        let continuation = b[i] == b'\\' && b.get(i + 1) == Some(&b'\n');
+        if continuation || b[i].is_ascii_whitespace() {
+            if let Some(s) = start.take() {
+                words.push(&segment[s..i]);
+            }
+            i += if continuation { 2 } else { 1 };
+            continue;
+        }
+        start.get_or_insert(i);
+        i = match b[i] {
+            b'\\' => (i + 2).min(b.len()),
+            q @ (b'\'' | b'"') => {
+                let mut j = i + 1;
+                while j < b.len() && b[j] != q {
+                    j += if q == b'"' && b[j] == b'\\' { 2 } else { 1 };
+                }
+                (j + 1).min(b.len())
+            }
+            _ => i + 1,
+        };
+    }
+    if let Some(s) = start {
+        words.push(&segment[s..]);
+    }
+    words
+}
+
+/// Options of `env` and `sudo` that consume the following word.
+const ENV_VALUE_OPTS: &[&str] = &["-u", "--unset", "-C", "--chdir", "-S", "--split-string"];
+const SUDO_VALUE_OPTS: &[&str] = &["-u", "--user", "-g", "--group", "-C", "-D", "-h", "-p", "-r", "-t", "-U", "-T"];
+
+/// The program the first simple command of `command` runs, past leading
+/// `NAME=val` assignments and `env`/`sudo` prefixes: `git` for
+/// `FOO=1 sudo env BAR=2 git status`. `None` for assignments only.
+pub fn command_word(command: &str) -> Option<&str> {
+    let span = command_spans(command).into_iter().next()?;
+    let words = raw_words(&command[span]);
+    let idx = program_position(&words)?;
+    Some(program_name(words[idx]))
+}
+
+/// Index of the word naming the program of a simple command, skipped past
+/// the same prefixes as [`command_word`].
+pub fn program_index(words: &[Word]) -> Option<usize> {
+    let texts: Vec<&str> = words.iter().map(|w| w.text.as_str()).collect();
+    program_position(&texts)
+}
+
+fn program_position(words: &[&str]) -> Option<usize> {
+    let mut i = 0;
+    while let Some(&w) = words.get(i) {
+        i += 1;
+        if is_assignment(w) {
+            continue;
+        }
+        let value_opts = match program_name(w) {
+            "env" => ENV_VALUE_OPTS,
+            "sudo" => SUDO_VALUE_OPTS,
+            _ => return Some(i - 1),
+        };
+        while let Some(&opt) = words.get(i) {
+            if opt == "--" {
+                i += 1;
+                break;
+            }
+            if !opt.starts_with('-') {
+                break;
+            }
+            i += if value_opts.contains(&opt) { 2 } else { 1 };
+        }
+    }
+    None
+}
+
+/// If the simple command runs one of `programs` (after any leading `NAME=val`
+/// assignments and `env`/`sudo` prefixes), return its argument words.
+pub fn program_args<'a>(words: &'a [Word], programs: &[&str]) -> Option<&'a [Word]> {
+    let idx = program_index(words)?;
+    let name = program_name(&words[idx].text);
+    if programs.contains(&name) {
+        Some(&words[idx + 1..])
+    } else {
+        None
+    }
+}
+
+/// Basename of a program word without a trailing `.exe`
+/// (`/usr/bin/rsync.exe` → `rsync`).
+pub fn program_name(word: &str) -> &str {
+    let base = word.rsplit(['/', '\\']).next().unwrap_or(word);
+    base.strip_suffix(".exe").unwrap_or(base)
+}
+
+/// `NAME=value` shell variable assignment.
+pub fn is_assignment(word: &str) -> bool {
+    match word.find('=') {
+        Some(eq) if eq > 0 => word[..eq]
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'_'),
+        _ => false,
+    }
+}
+
+/// An output redirection: `> file` truncates, `>> file` appends.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Redirect {
+    pub append: bool,
+    pub target: String,
+}
+
+/// File output redirections in a simple command's words: `>f`, `> f`, `2>f`,
+/// `>>f`, `>| f`. Descriptor duplications (`>&2`) and `/dev/*` targets are not
+/// files and are skipped. Quotes are already stripped, so a quoted `">"`
+/// argument reads as a redirection too.
+pub fn output_redirects(words: &[Word]) -> Vec<Redirect> {
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < words.len() {
+        let text = words[i].text.trim_start_matches(|c: char| c.is_ascii_digit());
+        i += 1;
+        let (append, rest) = if let Some(rest) = text.strip_prefix(">>") {
+            (true, rest)
+        } else if let Some(rest) = text.strip_prefix('>') {
+            (false, rest.strip_prefix('|').unwrap_or(rest))
+        } else {
+            continue;
+        };
+        let target = if rest.is_empty() {
+            let Some(next) = words.get(i) else {
+                continue;
+            };
+            i += 1;
+            next.text.as_str()
+        } else {
+            rest
+        };
+        if !target.is_empty() && !target.starts_with('&') && !target.starts_with("/dev/") {
+            out.push(Redirect {
+                append,
+                target: target.to_string(),
+            });
+        }
+    }
+    out
+}
+
+/// `X:\...` — a drive letter, colon, and at least one backslash separator.
+pub fn is_backslash_drive_path(text: &str) -> bool {
+    let b = text.as_bytes();
+    b.len() >= 3 && b[0].is_ascii_alphabetic() && b[1] == b':' && b[2] == b'\\'
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(command: &str) -> Vec<Vec<String>> {
+        simple_commands(command)
+            .into_iter()
+            .map(|words| words.into_iter().map(|w| w.text).collect())
+            .collect()
+    }
+
+    #[test]
+    fn splits_on_operators_and_strips_quotes() {
+        assert_eq!(
+            texts("cd 'my dir' && ls -la | grep \"x y\"; echo done"),
+            vec![vec!["cd", "my dir"], vec!["ls", "-la"], vec!["grep", "x y"], vec!["echo", "done"]]
+        );
+    }
+
+    #[test]
+    fn keeps_backslashes_in_drive_paths() {
+        assert_eq!(texts(r"rsync -a C:\src\ dst/"), vec![vec!["rsync", "-a", r"C:\src\", "dst/"]]);
+    }
+
+    #[test]
+    fn marks_quoted_words() {
+        let words = simple_commands(r#"cp 'C:\a b' C:\c x"y""#).remove(0);
+        let quoted: Vec<bool> = words.iter().map(|w| w.quoted).collect();
+        assert_eq!(quoted, vec![false, true, false, true]);
+    }
+
+    #[test]
+    fn escaped_backslash_before_closing_quote_ends_the_word() {
+        assert_eq!(
+            texts(r#"rsync -a "C:\src\\" dst/"#),
+            vec![vec!["rsync", "-a", r"C:\src\\", "dst/"]]
+        );
+        assert_eq!(texts(r#"echo "a\"b" c"#), vec![vec!["echo", r#"a\"b"#, "c"]]);
+        let cmd = r#"rsync -a "C:\src\\" dst/ && ls"#;
+        let spans: Vec<&str> = command_spans(cmd).into_iter().map(|r| &cmd[r]).collect();
+        assert_eq!(spans, vec![r#"rsync -a "C:\src\\" dst/"#, "ls"]);
+    }
+
+    #[test]
+    fn line_continuation_is_whitespace() {
+        assert_eq!(texts("ls \\\n  -la"), vec![vec!["ls", "-la"]]);
+    }
+
+    #[test]
+    fn command_spans_split_like_simple_commands() {
+        let cmd = r#"cd C:/ok && node -e "a(';')" /dev/stdin || echo 'x|y'; cat f | sort"#;
+        let spans: Vec<&str> = command_spans(cmd).into_iter().map(|r| &cmd[r]).collect();
+        assert_eq!(
+            spans,
+            vec!["cd C:/ok", r#"node -e "a(';')" /dev/stdin"#, "echo 'x|y'", "cat f", "sort"]
+        );
+        assert_eq!(spans.len(), simple_commands(cmd).len());
+        assert_eq!(command_spans(r"echo a\;b"), vec![0..9]);
+        assert!(command_spans(" ; ").is_empty());
+    }
+
+    #[test]
+    fn program_args_skips_assignments_and_matches_basename() {
+        let words = simple_commands("FOO=1 /usr/bin/rsync.exe -a x").remove(0);
+        let args = program_args(&words, &["rsync"]).unwrap();
+        assert_eq!(args.len(), 2);
+        assert!(program_args(&words, &["cp"]).is_none());
+    }
+
+    #[test]
+    fn command_word_skips_assignments_env_and_sudo() {
+        assert_eq!(command_word("FOO=1 sudo env BAR=2 git status"), Some("git"));
+        assert_eq!(command_word("sudo -u deploy -E /usr/bin/rsync.exe -a x y"), Some("rsync"));
+        assert_eq!(command_word("env -i -u HOME PATH=/bin node app.js && ls"), Some("node"));
+        assert_eq!(command_word("  FOO=\"a b\" cargo test"), Some("cargo"));
+        assert_eq!(command_word("FOO=1 BAR=2"), None);
+        assert_eq!(command_word(""), None);
+        let words = simple_commands("sudo cp a b").remove(0);
+        assert_eq!(program_index(&words), Some(1));
+        assert_eq!(program_args(&words, &["cp"]).map(<[Word]>::len), Some(2));
+    }
+
+    #[test]
+    fn raw_words_keep_quotes() {
+        assert_eq!(raw_words(r#"node -e 'a b' "c\"d" e\ f"#), vec!["node", "-e", "'a b'", r#""c\"d""#, r"e\ f"]);
+        assert_eq!(raw_words("ls \\\n  -la"), vec!["ls", "-la"]);
+    }
+
+    #[test]
+    fn finds_output_redirects() {
+        let words = simple_commands("sort a.txt >out.txt 2> err.log >> all.log >/dev/null").remove(0);
+        let r = output_redirects(&words);
+        let got: Vec<(bool, &str)> = r.iter().map(|r| (r.append, r.target.as_str())).collect();
+        assert_eq!(got, vec![(false, "out.txt"), (false, "err.log"), (true, "all.log")]);
+        assert!(output_redirects(&simple_commands("echo a >&2").remove(0)).is_empty());
+        assert!(output_redirects(&simple_commands("git diff --stat").remove(0)).is_empty());
+    }
+
+    #[test]
+    fn recognizes_assignments() {
+        assert!(is_assignment("PGPASSWORD=x"));
+        assert!(!is_assignment("--flag=x"));
+        assert!(!is_assignment("=x"));
+    }
+}
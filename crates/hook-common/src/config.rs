@@ -0,0 +1,275 @@
+//! Per-project hook configuration.
+//!
+//! Each hook reads an optional `.claude/<hook>.toml`. The file is looked up in
+//! `$CLAUDE_PROJECT_DIR` first, then in the current directory and each of its
+//! ancestors. A missing or unparseable file means defaults: configuration can
+//! tune a hook but never break it.
+//!
+//! Hook config structs use `#[serde(deny_unknown_fields)]`, so a typo'd key is
+//! an error like a wrong type. Either way the hook prints one line to stderr
+//! naming the file, line and problem, then runs with defaults.
+//!
+//! Hooks enforce in every project by default. With `HOOK_OPT_IN=1` they only
+//! enforce where a `.claude/hooks-enabled` marker is found, the same way as a
+//! config file, and exit 0 silently everywhere else (see [`enabled_here`]).
+
+use serde::de::DeserializeOwned;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Load the hook's config, falling back to `T::default()` (with a diagnostic
+/// on stderr when the file exists but is invalid).
+pub fn load<T: DeserializeOwned + Default>(hook: &str) -> T {
+    let Some(path) = locate(hook) else {
+        return T::default();
+    };
+    match try_load_from(&path) {
+        Ok(cfg) => cfg,
+        Err(problem) => {
+            eprintln!("{}: {}", hook, problem);
+            T::default()
+        }
+    }
+}
+
+/// Parse a config file, falling back to `T::default()` on any error.
+pub fn load_from<T: DeserializeOwned + Default>(path: &Path) -> T {
+    try_load_from(path).unwrap_or_default()
+}
+
+/// Parse a config file. A missing file is `T::default()`; an invalid one is
+/// a one-line description of the problem (`<path> line 2: unknown field …`).
+pub fn try_load_from<T: DeserializeOwned + Default>(path: &Path) -> Result<T, String> {
+    let Ok(text) = fs::read_to_string(path) else {
+        return Ok(T::default());
+    };
+    toml::from_str(&text).map_err(|e| {
+        let line = e
+            .span()
+            .map(|span| format!(" line {}", text[..span.start].matches('\n').count() + 1))
+            .unwrap_or_default();
+        format!(
+            "ignoring {}{}: {}; using defaults",
+            path.display(),
+            line,
+            e.message().trim_end()
+        )
+    })
+}
+
+/// Whether a written file is in scope for a hook's `include_globs` and
+/// `exclude_globs` settings: it must match an include glob (when any are set)
+/// and no exclude glob. See [`glob_match`] for the syntax.
+pub fn path_in_scope(path: &str, include_globs: &[String], exclude_globs: &[String]) -> bool {
+    (include_globs.is_empty() || include_globs.iter().any(|g| glob_match(g, path)))
+        && !exclude_globs.iter().any(|g| glob_match(g, path))
+}
+
+/// Match `path` against a glob: `*` and `?` stay within one path component,
+/// `**` spans any number of them (`src/**/*.rs` matches `src/a.rs`).
+/// Backslashes count as `/`. A relative glob matches at any directory
+/// boundary, so `src/**` covers `/repo/src/main.rs`.
+pub fn glob_match(glob: &str, path: &str) -> bool {
+    let glob = glob.replace('\\', "/");
+    let path = path.replace('\\', "/");
+    let glob = glob.trim_start_matches("./");
+    let (g, p) = (glob.as_bytes(), path.as_bytes());
+    if glob.starts_with('/') || glob.as_bytes().get(1) == Some(&b':') {
+        return match_from(g, p);
+    }
+    match_from(g, p)
+        || p.iter()
+            .enumerate()
+            .any(|(i, &c)| c == b'/' && match_from(g, &p[i + 1..]))
+}
+
+fn match_from(g: &[u8], p: &[u8]) -> bool {
+    match g {
+        [] => p.is_empty(),
+        [b'*', b'*', b'/', rest @ ..] => {
+            // Zero directories, or skip one component and try again.
+            match_from(rest, p)
+                || p.iter()
+                    .position(|&c| c == b'/')
+                    .is_some_and(|i| match_from(g, &p[i + 1..]))
+        }
+        [b'*', b'*', rest @ ..] => (0..=p.len()).any(|i| match_from(rest, &p[i..])),
+        [b'*', rest @ ..] => {
+            let component = p.iter().position(|&c| c == b'/').unwrap_or(p.len());
+            (0..=component).any(|i| match_from(rest, &p[i..]))
+        }
+        [b'?', rest @ ..] => p.first().is_some_and(|&c| c != b'/') && match_from(rest, &p[1..]),
+        [c, rest @ ..] => p.first() == Some(c) && match_from(rest, &p[1..]),
+    }
+}
+
+/// The config file [`load`] reads, if any: [`find`] from
+/// `$CLAUDE_PROJECT_DIR` and the current directory.
+pub fn locate(hook: &str) -> Option<PathBuf> {
+    let project_dir = env::var_os("CLAUDE_PROJECT_DIR").map(PathBuf::from);
+    let cwd = env::current_dir().ok();
+    find(hook, project_dir.as_deref(), cwd.as_deref())
+}
+
+/// Locate `.claude/<hook>.toml`: the project dir wins, otherwise walk up from
+/// `start`.
+pub fn find(hook: &str, project_dir: Option<&Path>, start: Option<&Path>) -> Option<PathBuf> {
+    find_file(&format!("{}.toml", hook), project_dir, start)
+}
+
+fn find_file(file: &str, project_dir: Option<&Path>, start: Option<&Path>) -> Option<PathBuf> {
+    if let Some(dir) = project_dir {
+        let p = dir.join(".claude").join(file);
+        if p.is_file() {
+            return Some(p);
+        }
+    }
+    start?
+        .ancestors()
+        .map(|dir| dir.join(".claude").join(file))
+        .find(|p| p.is_file())
+}
+
+/// Marker file in `.claude/` that opts a project in under `HOOK_OPT_IN=1`.
+pub const OPT_IN_MARKER: &str = "hooks-enabled";
+
+/// Whether hooks should enforce in this project: always, unless
+/// `HOOK_OPT_IN=1`, in which case only where [`OPT_IN_MARKER`] is found from
+/// `$CLAUDE_PROJECT_DIR` and the current directory.
+pub fn enabled_here() -> bool {
+    let opt_in = env::var("HOOK_OPT_IN").as_deref() == Ok("1");
+    let project_dir = env::var_os("CLAUDE_PROJECT_DIR").map(PathBuf::from);
+    let cwd = env::current_dir().ok();
+    enabled_in(opt_in, project_dir.as_deref(), cwd.as_deref())
+}
+
+/// [`enabled_here`] for explicit locations.
+pub fn enabled_in(opt_in: bool, project_dir: Option<&Path>, start: Option<&Path>) -> bool {
+    !opt_in || find_file(OPT_IN_MARKER, project_dir, start).is_some()
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Default, Deserialize, PartialEq)]
+    #[serde(default, deny_unknown_fields)]
+    struct Sample {
+        words: Vec<String>,
+        enabled: bool,
+    }
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("hook-common-config-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join(".claude")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn parses_config_file() {
+        let dir = scratch("parse");
+        let path = dir.join(".claude/sample.toml");
+        fs::write(&path, "words = [\"a\", \"b\"]\nenabled = true\n").unwrap();
+        let cfg: Sample = load_from(&path);
+        assert_eq!(cfg.words, vec!["a", "b"]);
+        assert!(cfg.enabled);
+    }
+
+    #[test]
+    fn missing_or_broken_file_is_default() {
+        let dir = scratch("broken");
+        let path = dir.join(".claude/sample.toml");
+        assert_eq!(load_from::<Sample>(&path), Sample::default());
+        fs::write(&path, "words = not toml").unwrap();
+        assert_eq!(load_from::<Sample>(&path), Sample::default());
+    }
+
+    #[test]
+    fn unknown_key_names_key_and_line() {
+        let dir = scratch("unknown");
+        let path = dir.join(".claude/sample.toml");
+        fs::write(&path, "enabled = true\nwordz = [\"a\"]\n").unwrap();
+        let err = try_load_from::<Sample>(&path).unwrap_err();
+        assert!(err.contains("sample.toml line 2"), "got: {}", err);
+        assert!(err.contains("unknown field `wordz`"), "got: {}", err);
+        assert!(err.ends_with("; using defaults"), "got: {}", err);
+        assert!(!err.contains('\n'), "got: {}", err);
+        assert_eq!(load_from::<Sample>(&path), Sample::default());
+    }
+
+    #[test]
+    fn wrong_type_is_reported() {
+        let dir = scratch("type");
+        let path = dir.join(".claude/sample.toml");
+        fs::write(&path, "enabled = \"yes\"\n").unwrap();
+        let err = try_load_from::<Sample>(&path).unwrap_err();
+        assert!(err.contains("line 1"), "got: {}", err);
+        assert!(err.contains("expected a boolean"), "got: {}", err);
+    }
+
+    #[test]
+    fn glob_syntax() {
+        assert!(glob_match("src/**", "/repo/src/a/b.rs"));
+        assert!(glob_match("src/**/*.rs", "/repo/src/a.rs"));
+        assert!(glob_match("src/**/*.rs", r"C:\repo\src\x\a.rs"));
+        assert!(!glob_match("src/*.rs", "/repo/src/x/a.rs"));
+        assert!(glob_match("*.md", "/repo/docs/README.md"));
+        assert!(glob_match("a?.rs", "ab.rs"));
+        assert!(!glob_match("/src/**", "/repo/src/a.rs"));
+        assert!(!glob_match("src/**", "/repo/mysrc/a.rs"));
+    }
+
+    #[test]
+    fn scope_applies_include_then_exclude() {
+        let include = vec!["src/**".to_string()];
+        let exclude = vec!["**/generated/**".to_string()];
+        assert!(path_in_scope("/repo/src/lib.rs", &include, &exclude));
+        assert!(!path_in_scope("/repo/docs/notes.md", &include, &exclude));
+        assert!(!path_in_scope("/repo/src/generated/api.rs", &include, &exclude));
+        assert!(path_in_scope("/repo/anything", &[], &[]));
+    }
+
+    #[test]
+    fn finds_config_in_ancestor() {
+        let dir = scratch("walk");
+        fs::write(dir.join(".claude/sample.toml"), "").unwrap();
+        let nested = dir.join("a/b");
+        fs::create_dir_all(&nested).unwrap();
+        assert_eq!(
+            find("sample", None, Some(&nested)),
+            Some(dir.join(".claude/sample.toml"))
+        );
+    }
+
+    #[test]
+    fn project_dir_wins_over_walk() {
+        let project = scratch("project");
+        let other = scratch("other");
+        fs::write(project.join(".claude/sample.toml"), "").unwrap();
+        fs::write(other.join(".claude/sample.toml"), "").unwrap();
+        assert_eq!(
+            find("sample", Some(&project), Some(&other)),
+            Some(project.join(".claude/sample.toml"))
+        );
+    }
+
+    #[test]
+    fn opt_in_requires_marker() {
+        let dir = scratch("opt-in");
+        let nested = dir.join("src/deep");
+        fs::create_dir_all(&nested).unwrap();
+        assert!(enabled_in(false, Some(&dir), Some(&nested)), "enforces everywhere by default");
+        assert!(!enabled_in(true, Some(&dir), Some(&nested)));
+
+        fs::write(dir.join(".claude").join(OPT_IN_MARKER), "").unwrap();
+        assert!(enabled_in(true, Some(&dir), None));
+        assert!(enabled_in(true, None, Some(&nested)), "marker found walking up");
+    }
+}
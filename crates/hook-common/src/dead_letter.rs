@@ -0,0 +1,152 @@
+//! Dead-letter log for hook panics.
+//!
+//! Hooks should never panic, but if one does it must not block the user or
+//! print a backtrace into the session. [`guard`] runs a hook's body, and on
+//! panic appends the panic message and the hook's stdin to
+//! `<state dir>/dead-letter.jsonl` (see [`crate::state`]) and exits 0. Both are
+//! passed through [`redact`] first, and [`anonymize::logged`] after it.
+
+use crate::anonymize;
+use crate::decision::Emission;
+use crate::redact::redact;
+use crate::state;
+use serde_json::json;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const FILE_NAME: &str = "dead-letter.jsonl";
+
+/// Inputs larger than this are truncated in the log entry.
+const MAX_INPUT_BYTES: usize = 64 * 1024;
+
+/// Last panic message and location, recorded by the panic hook installed in
+/// [`catch_in`] instead of the default stderr printout.
+static LAST_PANIC: Mutex<Option<String>> = Mutex::new(None);
+
+/// Run `body` (the hook's work after reading stdin). If it panics, record a
+/// dead-letter entry and exit 0.
+pub fn guard(hook: &str, input: &str, body: impl FnOnce()) {
+    if let Some(emission) = catch_in(&state::dir(), hook, input, body) {
+        emission.exit();
+    }
+}
+
+/// Run `body`, returning the emission to exit with if it panicked (always
+/// exit 0 and silent) after appending an entry to `dir`'s dead-letter file.
+pub fn catch_in(dir: &Path, hook: &str, input: &str, body: impl FnOnce()) -> Option<Emission> {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(|info| {
+        if let Ok(mut last) = LAST_PANIC.lock() {
+            *last = Some(info.to_string());
+        }
+    }));
+    let result = panic::catch_unwind(AssertUnwindSafe(body));
+    panic::set_hook(previous);
+
+    let payload = result.err()?;
+    let message = LAST_PANIC
+        .lock()
+        .ok()
+        .and_then(|mut last| last.take())
+        .or_else(|| payload.downcast_ref::<&str>().map(|s| s.to_string()))
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string());
+    record(dir, hook, &message, input);
+    Some(Emission::default())
+}
+
+fn record(dir: &Path, hook: &str, message: &str, input: &str) {
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let entry = json!({
+        "time": time,
+        "hook": hook,
+        "panic": anonymize::logged(&redact(message)),
+        "input": anonymize::logged(&redact(truncate(input, MAX_INPUT_BYTES))),
+    });
+    let Ok(mut file) = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(FILE_NAME))
+    else {
+        return;
+    };
+    let _ = writeln!(file, "{}", entry);
+}
+
+/// `text` cut to at most `max` bytes on a char boundary.
+pub(crate) fn truncate(text: &str, max: usize) -> &str {
+    if text.len() <= max {
+        return text;
+    }
+    let mut end = max;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+    use std::fs;
+
+    fn scratch_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("hook-common-dead-letter-{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn panic_writes_entry_and_exits_zero() {
+        let dir = scratch_dir("panic");
+        let out = catch_in(&dir, "test-hook", "{\"tool_name\":\"Bash\"}", || {
+            panic!("boom at {}", 42);
+        })
+        .expect("panic should be caught");
+        assert_eq!(out.code, 0);
+        assert!(out.stdout.is_empty());
+        assert!(out.stderr.is_empty());
+
+        let log = fs::read_to_string(dir.join(FILE_NAME)).unwrap();
+        let entry: Value = serde_json::from_str(log.trim()).unwrap();
+        assert_eq!(entry["hook"], "test-hook");
+        assert_eq!(entry["input"], "{\"tool_name\":\"Bash\"}");
+        assert!(entry["panic"].as_str().unwrap().contains("boom at 42"));
+    }
+
+    #[test]
+    fn entry_is_redacted() {
+        let dir = scratch_dir("redact");
+        let input = "{\"tool_input\":{\"command\":\"PGPASSWORD=hunter2 psql\"}}";
+        catch_in(&dir, "test-hook", input, || panic!("bad token ghp_0123456789abcdefghijAB"));
+        let log = fs::read_to_string(dir.join(FILE_NAME)).unwrap();
+        assert!(!log.contains("hunter2"), "got: {}", log);
+        assert!(!log.contains("ghp_0123"), "got: {}", log);
+    }
+
+    #[test]
+    fn normal_return_writes_nothing() {
+        let dir = scratch_dir("ok");
+        assert!(catch_in(&dir, "test-hook", "{}", || {}).is_none());
+        assert!(!dir.join(FILE_NAME).exists());
+    }
+
+    #[test]
+    fn truncates_on_char_boundary() {
+        assert_eq!(truncate("héllo", 2), "h");
+        assert_eq!(truncate("abc", 10), "abc");
+    }
+}
@@ -0,0 +1,142 @@
+//! `--doctor`: a read-only health check every hook binary answers.
+//!
+//! Reports whether the hook runs on this OS, whether its config file parses,
+//! whether the state directory (session state, dead letters) is writable, and
+//! how many patterns or checks are active — enough to confirm an install
+//! without feeding the hook a tool call. Always exits 0.
+
+use crate::config;
+use crate::state;
+use serde::de::DeserializeOwned;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// Whether the binary was started as `<hook> --doctor`.
+pub fn requested() -> bool {
+    env::args().nth(1).as_deref() == Some("--doctor")
+}
+
+/// Collects the report lines for one hook.
+pub struct Doctor {
+    hook: &'static str,
+    os: String,
+    config: String,
+    counts: Vec<String>,
+}
+
+impl Doctor {
+    pub fn new(hook: &'static str) -> Self {
+        Doctor {
+            hook,
+            os: format!("any (current: {})", env::consts::OS),
+            config: "not configurable".to_string(),
+            counts: Vec::new(),
+        }
+    }
+
+    /// The hook only acts on `os` (as in `std::env::consts::OS`).
+    pub fn os_gate(&mut self, os: &str) {
+        let current = env::consts::OS;
+        self.os = if current == os {
+            format!("{} only (current: {}, active)", os, current)
+        } else {
+            format!("{} only (current: {}, inactive: the hook exits without checking)", os, current)
+        };
+    }
+
+    /// Parse the hook's config the way [`config::load`] would, record the
+    /// outcome, and return the effective config for the counts.
+    pub fn config<T: DeserializeOwned + Default>(&mut self) -> T {
+        let Some(path) = config::locate(self.hook) else {
+            self.config = "none found, using defaults".to_string();
+            return T::default();
+        };
+        match config::try_load_from(&path) {
+            Ok(cfg) => {
+                self.config = format!("{} parses", path.display());
+                cfg
+            }
+            Err(problem) => {
+                self.config = problem;
+                T::default()
+            }
+        }
+    }
+
+    /// An active pattern or check count (`"patterns", 31`).
+    pub fn count(&mut self, what: &str, n: impl std::fmt::Display) {
+        self.counts.push(format!("{}: {}", what, n));
+    }
+
+    /// The report, checking the state directory last.
+    pub fn report(&self) -> String {
+        self.report_in(&state::dir())
+    }
+
+    fn report_in(&self, state_dir: &Path) -> String {
+        let mut out = format!("{} doctor\n", self.hook);
+        out.push_str(&format!("  os: {}\n", self.os));
+        out.push_str(&format!("  config: {}\n", self.config));
+        out.push_str(&format!("  state dir: {}\n", writable(state_dir)));
+        for c in &self.counts {
+            out.push_str(&format!("  {}\n", c));
+        }
+        out
+    }
+
+    /// Print the report and exit 0.
+    pub fn exit(self) -> ! {
+        print!("{}", self.report());
+        std::process::exit(0);
+    }
+}
+
+/// `<dir> writable`, or why not. Creates and removes a probe file.
+fn writable(dir: &Path) -> String {
+    let probe = dir.join(format!(".doctor-{}", std::process::id()));
+    match fs::write(&probe, b"") {
+        Ok(()) => {
+            let _ = fs::remove_file(&probe);
+            format!("{} writable", dir.display())
+        }
+        Err(e) => format!(
+            "{} NOT writable ({}); session state and dead letters are lost",
+            dir.display(),
+            e
+        ),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_each_section() {
+        let dir = env::temp_dir().join(format!("hook-common-doctor-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut d = Doctor::new("h");
+        d.os_gate("plan9");
+        d.count("patterns", 3);
+        let report = d.report_in(&dir);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(report.starts_with("h doctor\n"), "got: {}", report);
+        assert!(report.contains("os: plan9 only (current: "), "got: {}", report);
+        assert!(report.contains("inactive"), "got: {}", report);
+        assert!(report.contains("config: not configurable"), "got: {}", report);
+        assert!(report.contains(&format!("state dir: {} writable", dir.display())), "got: {}", report);
+        assert!(report.contains("  patterns: 3\n"), "got: {}", report);
+    }
+
+    #[test]
+    fn missing_state_dir_is_not_writable() {
+        let dir = env::temp_dir().join(format!("hook-common-doctor-missing-{}", std::process::id()));
+        assert!(writable(&dir).contains("NOT writable"));
+    }
+}
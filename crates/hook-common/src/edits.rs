@@ -0,0 +1,118 @@
+//! Normalized view of the file-writing tools (Write/Edit/MultiEdit).
+
+use serde_json::Value;
+
+/// What a single Write/Edit/MultiEdit call wrote. Each hunk pairs the text
+/// that was replaced (empty for Write) with the text that replaced it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WriteOp {
+    pub file_path: String,
+    pub hunks: Vec<(String, String)>,
+}
+
+impl WriteOp {
+    /// Parse a tool call's `tool_input`. `None` for tools that don't write
+    /// files or inputs missing the written text.
+    pub fn from_tool_input(tool_name: &str, input: &Value) -> Option<WriteOp> {
+        let file_path = input["file_path"].as_str().unwrap_or("").to_string();
+        let hunk = |v: &Value| {
+            (
+                v["old_string"].as_str().unwrap_or("").to_string(),
+                v["new_string"].as_str().unwrap_or("").to_string(),
+            )
+        };
+
+        let hunks = match tool_name {
+            "Write" => vec![(String::new(), input["content"].as_str()?.to_string())],
+            "Edit" => vec![hunk(input)],
+            "MultiEdit" => input["edits"].as_array()?.iter().map(hunk).collect(),
+            _ => return None,
+        };
+
+        Some(WriteOp { file_path, hunks })
+    }
+}
+
+/// A write reduced to the file and the text it put there, for checks that
+/// only look at what was written. `added_text` joins a MultiEdit's new
+/// strings with newlines.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EditOp {
+    pub path: String,
+    pub added_text: String,
+}
+
+impl From<&WriteOp> for EditOp {
+    fn from(w: &WriteOp) -> EditOp {
+        EditOp {
+            path: w.file_path.clone(),
+            added_text: w
+                .hunks
+                .iter()
+                .map(|(_, new)| new.as_str())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+}
+
+/// Test directories and the usual test file naming conventions.
+pub fn is_test_path(path: &str) -> bool {
+    let path = path.replace('\\', "/").to_lowercase();
+    let name = path.rsplit('/').next().unwrap_or("");
+    path.starts_with("tests/")
+        || path.starts_with("test/")
+        || ["/tests/", "/test/", "/__tests__/", "/spec/"].iter().any(|d| path.contains(d))
+        || name.starts_with("test_")
+        || [".test.", ".spec.", "_test.", "_spec."].iter().any(|s| name.contains(s))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_all_write_shapes() {
+        let w = WriteOp::from_tool_input("Write", &json!({"file_path": "a.rs", "content": "x"})).unwrap();
+        assert_eq!(w.file_path, "a.rs");
+        assert_eq!(w.hunks, vec![(String::new(), "x".to_string())]);
+
+        let e = WriteOp::from_tool_input(
+            "Edit",
+            &json!({"file_path": "a.rs", "old_string": "a", "new_string": "b"}),
+        )
+        .unwrap();
+        assert_eq!(e.hunks, vec![("a".to_string(), "b".to_string())]);
+
+        let m = WriteOp::from_tool_input(
+            "MultiEdit",
+            &json!({"file_path": "a.rs", "edits": [
+                {"old_string": "a", "new_string": "b"},
+                {"old_string": "c", "new_string": "d"}
+            ]}),
+        )
+        .unwrap();
+        assert_eq!(m.hunks.len(), 2);
+    }
+
+    #[test]
+    fn recognizes_test_paths() {
+        assert!(is_test_path("tests/cli.rs"));
+        assert!(is_test_path(r"C:\repo\src\__tests__\app.js"));
+        assert!(is_test_path("/repo/src/user.spec.ts"));
+        assert!(is_test_path("pkg/test_user.py"));
+        assert!(!is_test_path("/repo/src/contest.rs"));
+    }
+
+    #[test]
+    fn ignores_other_tools() {
+        assert!(WriteOp::from_tool_input("Bash", &json!({"command": "ls"})).is_none());
+        assert!(WriteOp::from_tool_input("Read", &json!({"file_path": "a.rs"})).is_none());
+        assert!(WriteOp::from_tool_input("Write", &json!({"file_path": "a.rs"})).is_none());
+    }
+}
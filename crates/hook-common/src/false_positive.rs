@@ -0,0 +1,117 @@
+//! Opt-in false-positive reports.
+//!
+//! When a hook fires on something it shouldn't have, re-run it with
+//! `HOOK_REPORT_FP=1` and the same input. [`report_if_requested`] then appends
+//! the input and the rules that fired to `<state dir>/false-positives.jsonl`
+//! (see [`crate::state`]) for later pattern tuning, with secrets masked by
+//! [`redact`] (and paths anonymized by [`anonymize::logged`]). The hook's
+//! decision is not affected.
+
+use crate::anonymize;
+use crate::dead_letter::truncate;
+use crate::decision::Finding;
+use crate::redact::redact;
+use crate::state;
+use serde_json::json;
+use std::env;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const FILE_NAME: &str = "false-positives.jsonl";
+
+/// Inputs larger than this are truncated in the report.
+const MAX_INPUT_BYTES: usize = 64 * 1024;
+
+/// Record `findings` as a false positive when `HOOK_REPORT_FP=1`.
+pub fn report_if_requested(hook: &str, input: &str, findings: &[Finding]) {
+    if env::var("HOOK_REPORT_FP").as_deref() == Ok("1") {
+        record_in(&state::dir(), hook, input, findings);
+    }
+}
+
+/// Append a report to `dir`. Returns whether one was written: there is nothing
+/// to report when no finding fired.
+pub fn record_in(dir: &Path, hook: &str, input: &str, findings: &[Finding]) -> bool {
+    if findings.is_empty() {
+        return false;
+    }
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let entry = json!({
+        "time": time,
+        "hook": hook,
+        "findings": findings
+            .iter()
+            .map(|f| {
+                let message = anonymize::logged(&redact(&f.message));
+                json!({"rule": f.rule, "severity": f.severity.as_str(), "message": message})
+            })
+            .collect::<Vec<_>>(),
+        "input": anonymize::logged(&redact(truncate(input, MAX_INPUT_BYTES))),
+    });
+    let Ok(mut file) = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(FILE_NAME))
+    else {
+        return false;
+    };
+    writeln!(file, "{}", entry).is_ok()
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+    use std::fs;
+
+    fn scratch_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("hook-common-fp-{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn writes_rules_and_input() {
+        let dir = scratch_dir("write");
+        let findings = vec![
+            Finding::block("for now", "\"for now\" → \"Works for now.\""),
+            Finding::info("note", "fyi"),
+        ];
+        assert!(record_in(&dir, "test-hook", "{\"session_id\":\"s\"}", &findings));
+
+        let log = fs::read_to_string(dir.join(FILE_NAME)).unwrap();
+        let entry: Value = serde_json::from_str(log.trim()).unwrap();
+        assert_eq!(entry["hook"], "test-hook");
+        assert_eq!(entry["input"], "{\"session_id\":\"s\"}");
+        assert_eq!(entry["findings"][0]["rule"], "for now");
+        assert_eq!(entry["findings"][0]["severity"], "block");
+        assert_eq!(entry["findings"][1]["rule"], "note");
+    }
+
+    #[test]
+    fn report_is_redacted() {
+        let dir = scratch_dir("redact");
+        let findings = vec![Finding::block("inline", "`mysql password=hunter2`")];
+        let input = "{\"tool_input\":{\"command\":\"mysql password=hunter2\"}}";
+        assert!(record_in(&dir, "test-hook", input, &findings));
+        let log = fs::read_to_string(dir.join(FILE_NAME)).unwrap();
+        assert!(!log.contains("hunter2"), "got: {}", log);
+    }
+
+    #[test]
+    fn nothing_fired_writes_nothing() {
+        let dir = scratch_dir("empty");
+        assert!(!record_in(&dir, "test-hook", "{}", &[]));
+        assert!(!dir.join(FILE_NAME).exists());
+    }
+}
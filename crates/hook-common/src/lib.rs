@@ -0,0 +1,21 @@
+//! Shared building blocks for the Rust hook binaries in this marketplace.
+//!
+//! Each hook stays a small, self-contained binary; this crate only holds the
+//! pieces that would otherwise be copy-pasted between them.
+
+pub mod allow_once;
+pub mod anonymize;
+pub mod bash;
+pub mod config;
+pub mod dead_letter;
+pub mod decision;
+pub mod doctor;
+pub mod edits;
+pub mod false_positive;
+pub mod pattern;
+pub mod quiet;
+pub mod redact;
+pub mod session_log;
+pub mod sink;
+pub mod state;
+pub mod stop;
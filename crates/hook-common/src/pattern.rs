@@ -0,0 +1,270 @@
+//! Per-pattern metadata and matching shared by the phrase detectors.
+//!
+//! `<hook> --pattern-test <group>` reads a text sample on stdin and lists
+//! every match of that hook's pattern group with its byte span, using the
+//! same matching as the hook itself. It shortens the loop when tuning a
+//! vocabulary: paste the text that should (or shouldn't) trigger and see why.
+//! The `pattern-test` tool does the same across every phrase detector (see
+//! [`hooks_report`]).
+
+use crate::decision::Finding;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::env;
+use std::io::{self, Read};
+use std::ops::Range;
+use std::process;
+
+/// Optional "learn more" links keyed by pattern, read from a hook's config:
+///
+/// ```toml
+/// [doc_urls]
+/// "for now" = "https://wiki.example.org/shortcuts#deferred-work"
+/// ```
+///
+/// Keys match the pattern text case-insensitively.
+#[derive(Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub struct DocUrls(HashMap<String, String>);
+
+impl DocUrls {
+    pub fn get(&self, pattern: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(pattern))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Attach the link configured for the finding's rule, if any.
+    pub fn attach(&self, finding: Finding) -> Finding {
+        let url = self.get(&finding.rule).map(str::to_string);
+        finding.with_doc_url(url)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Matching
+// ---------------------------------------------------------------------------
+
+/// Case-insensitive byte-level substring search (ASCII-folding only).
+/// Returns the byte offset of the first match in `haystack`.
+pub fn find_case_insensitive(haystack: &str, needle: &str) -> Option<usize> {
+    find_from(haystack.as_bytes(), needle.as_bytes(), 0, false)
+}
+
+fn find_from(h: &[u8], n: &[u8], from: usize, case_sensitive: bool) -> Option<usize> {
+    if n.is_empty() || h.len() < n.len() {
+        return None;
+    }
+    (from..=h.len() - n.len()).find(|&i| {
+        let window = &h[i..i + n.len()];
+        if case_sensitive {
+            window == n
+        } else {
+            window.eq_ignore_ascii_case(n)
+        }
+    })
+}
+
+/// A named list of plain-substring patterns, as one hook matches them.
+pub struct Group<'a> {
+    pub name: &'a str,
+    pub patterns: Vec<&'a str>,
+    /// Exact case; otherwise ASCII case-insensitive.
+    pub case_sensitive: bool,
+}
+
+impl Group<'_> {
+    /// Every occurrence of every pattern in `text`, ordered by position.
+    pub fn matches(&self, text: &str) -> Vec<(&str, Range<usize>)> {
+        let mut found = Vec::new();
+        for &pattern in &self.patterns {
+            let mut from = 0;
+            let (h, n) = (text.as_bytes(), pattern.as_bytes());
+            while let Some(start) = find_from(h, n, from, self.case_sensitive) {
+                found.push((pattern, start..start + pattern.len()));
+                from = start + 1;
+            }
+        }
+        found.sort_by_key(|(p, span)| (span.start, span.end, *p));
+        found
+    }
+}
+
+// ---------------------------------------------------------------------------
+// --pattern-test
+// ---------------------------------------------------------------------------
+
+/// The group name if the binary was started as `<hook> --pattern-test <group>`.
+pub fn test_requested() -> Option<String> {
+    let mut args = env::args().skip(1);
+    (args.next().as_deref() == Some("--pattern-test")).then(|| args.next().unwrap_or_default())
+}
+
+/// One line per match of group `name` in `text` (`start..end  pattern
+/// "matched text"`), then a count. Errs with the known group names.
+pub fn test_report(groups: &[Group], name: &str, text: &str) -> Result<String, String> {
+    let Some(group) = groups.iter().find(|g| g.name == name) else {
+        let names = groups.iter().map(|g| g.name).collect::<Vec<_>>().join(", ");
+        return Err(format!("unknown pattern group `{}` (groups: {})", name, names));
+    };
+    let matches = group.matches(text);
+    let mut out = String::new();
+    for (pattern, span) in &matches {
+        let matched = text.get(span.clone()).unwrap_or(pattern);
+        let span = format!("{}..{}", span.start, span.end);
+        out.push_str(&format!("{:<10} {:<24} \"{}\"\n", span, pattern, matched));
+    }
+    out.push_str(&match matches.len() {
+        0 => "no matches\n".to_string(),
+        1 => "1 match\n".to_string(),
+        n => format!("{} matches\n", n),
+    });
+    Ok(out)
+}
+
+/// [`test_report`] across hooks, for the `pattern-test` tool: `query` is
+/// `<hook>/<group>` for one hook's group, or a bare group name for that group
+/// in every hook that has it. Each report is headed by `<hook>/<group>:`.
+/// Errs with every known `<hook>/<group>`.
+pub fn hooks_report(hooks: &[(&str, Vec<Group>)], query: &str, text: &str) -> Result<String, String> {
+    let (hook, name) = match query.split_once('/') {
+        Some((hook, name)) => (Some(hook), name),
+        None => (None, query),
+    };
+    let mut out = String::new();
+    for (h, groups) in hooks.iter().filter(|(h, _)| hook.is_none_or(|hook| hook == *h)) {
+        if let Ok(report) = test_report(groups, name, text) {
+            out.push_str(&format!("{}/{}:\n{}", h, name, report));
+        }
+    }
+    if out.is_empty() {
+        let names: Vec<String> = hooks
+            .iter()
+            .flat_map(|(h, groups)| groups.iter().map(move |g| format!("{}/{}", h, g.name)))
+            .collect();
+        return Err(format!("unknown pattern group `{}` (groups: {})", query, names.join(", ")));
+    }
+    Ok(out)
+}
+
+/// Answer `--pattern-test <group>` if requested: read the sample from stdin,
+/// print the report and exit (2 for an unknown group). Returns otherwise.
+pub fn run_test_if_requested(hook: &str, groups: &[Group]) {
+    let Some(name) = test_requested() else {
+        return;
+    };
+    let mut text = String::new();
+    let _ = io::stdin().read_to_string(&mut text);
+    match test_report(groups, &name, &text) {
+        Ok(report) => {
+            print!("{}", report);
+            process::exit(0);
+        }
+        Err(problem) => {
+            eprintln!("{}: {}", hook, problem);
+            process::exit(2);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default, Deserialize)]
+    #[serde(default)]
+    struct Cfg {
+        doc_urls: DocUrls,
+    }
+
+    #[test]
+    fn looks_up_case_insensitively() {
+        let cfg: Cfg = toml::from_str("[doc_urls]\n\"For Now\" = \"https://x/for-now\"\n").unwrap();
+        assert_eq!(cfg.doc_urls.get("for now"), Some("https://x/for-now"));
+        assert_eq!(cfg.doc_urls.get("TODO"), None);
+    }
+
+    #[test]
+    fn attaches_link_by_rule() {
+        let cfg: Cfg = toml::from_str("[doc_urls]\nTODO = \"https://x/todo\"\n").unwrap();
+        let f = cfg.doc_urls.attach(Finding::block("TODO", "TODO comment"));
+        assert_eq!(f.doc_url.as_deref(), Some("https://x/todo"));
+        let f = cfg.doc_urls.attach(Finding::block("FIXME", "FIXME comment"));
+        assert_eq!(f.doc_url, None);
+    }
+
+    fn groups() -> Vec<Group<'static>> {
+        vec![
+            Group { name: "phrases", patterns: vec!["for now", "now"], case_sensitive: false },
+            Group { name: "markers", patterns: vec!["TODO"], case_sensitive: true },
+        ]
+    }
+
+    #[test]
+    fn finds_ascii_case_insensitively() {
+        assert_eq!(find_case_insensitive("Good For Now.", "for now"), Some(5));
+        assert_eq!(find_case_insensitive("é for now", "FOR NOW"), Some(3));
+        assert_eq!(find_case_insensitive("short", "longer needle"), None);
+    }
+
+    #[test]
+    fn group_lists_every_occurrence_with_spans() {
+        let g = &groups()[0];
+        let found = g.matches("For now, and for NOW again.");
+        assert_eq!(
+            found,
+            vec![("for now", 0..7), ("now", 4..7), ("for now", 13..20), ("now", 17..20)]
+        );
+        assert!(groups()[1].matches("todo: lowercase").is_empty());
+    }
+
+    #[test]
+    fn report_shows_spans_and_matched_text() {
+        let report = test_report(&groups(), "markers", "x // TODO: a\ny // TODO b").unwrap();
+        assert_eq!(
+            report,
+            "5..9       TODO                     \"TODO\"\n\
+             18..22     TODO                     \"TODO\"\n\
+             2 matches\n"
+        );
+        assert_eq!(test_report(&groups(), "phrases", "all done").unwrap(), "no matches\n");
+        let err = test_report(&groups(), "nope", "").unwrap_err();
+        assert_eq!(err, "unknown pattern group `nope` (groups: phrases, markers)");
+    }
+
+    #[test]
+    fn hooks_report_covers_every_hook_with_the_group() {
+        let hooks = vec![
+            ("first", groups()),
+            ("second", vec![Group { name: "phrases", patterns: vec!["again"], case_sensitive: false }]),
+        ];
+        let text = "For now, again.";
+        assert_eq!(
+            hooks_report(&hooks, "phrases", text).unwrap(),
+            "first/phrases:\n\
+             0..7       for now                  \"For now\"\n\
+             4..7       now                      \"now\"\n\
+             2 matches\n\
+             second/phrases:\n\
+             9..14      again                    \"again\"\n\
+             1 match\n"
+        );
+        assert_eq!(hooks_report(&hooks, "second/phrases", "none").unwrap(), "second/phrases:\nno matches\n");
+        assert_eq!(
+            hooks_report(&hooks, "second/markers", "").unwrap_err(),
+            "unknown pattern group `second/markers` \
+             (groups: first/phrases, first/markers, second/phrases)"
+        );
+    }
+
+    #[test]
+    fn missing_table_is_empty() {
+        let cfg: Cfg = toml::from_str("").unwrap();
+        assert_eq!(cfg.doc_urls.get("for now"), None);
+    }
+}
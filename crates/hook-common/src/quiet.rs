@@ -0,0 +1,137 @@
+//! Quiet hours: daily windows of local time in which a hook only advises.
+//!
+//! Hooks that opt in read `quiet_hours = ["18:00-09:00"]` from their config
+//! and pass [`is_quiet`] to [`Decision::with_quiet`](crate::decision::Decision::with_quiet),
+//! which turns blocks into warnings while it holds. `HOOK_QUIET=1` forces
+//! quiet mode and `HOOK_QUIET=0` forces enforcement, whatever the clock says.
+
+use serde::Deserialize;
+use std::env;
+use std::fmt;
+use time::OffsetDateTime;
+
+/// A daily `HH:MM-HH:MM` window. The start is inclusive and the end
+/// exclusive; a window whose end is before its start wraps past midnight.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Window {
+    /// Minutes since midnight.
+    start: u16,
+    end: u16,
+}
+
+impl Window {
+    pub fn contains(self, minute: u16) -> bool {
+        if self.start <= self.end {
+            (self.start..self.end).contains(&minute)
+        } else {
+            minute >= self.start || minute < self.end
+        }
+    }
+}
+
+impl TryFrom<String> for Window {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Window, String> {
+        let parse = |part: &str| {
+            let (h, m) = part.trim().split_once(':')?;
+            let (h, m): (u16, u16) = (h.parse().ok()?, m.parse().ok()?);
+            (h < 24 && m < 60).then_some(h * 60 + m)
+        };
+        s.split_once('-')
+            .and_then(|(start, end)| Some(Window { start: parse(start)?, end: parse(end)? }))
+            .ok_or_else(|| format!("invalid quiet hours `{}`, expected `HH:MM-HH:MM`", s))
+    }
+}
+
+impl fmt::Display for Window {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:02}:{:02}-{:02}:{:02}",
+            self.start / 60,
+            self.start % 60,
+            self.end / 60,
+            self.end % 60
+        )
+    }
+}
+
+/// Whether the hook should be quiet now: `HOOK_QUIET` when set, otherwise
+/// whether the local time (UTC if the local offset is unknown) falls in one
+/// of `windows`.
+pub fn is_quiet(windows: &[Window]) -> bool {
+    let now = OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
+    let minute = u16::from(now.hour()) * 60 + u16::from(now.minute());
+    is_quiet_at(windows, env_override(), minute)
+}
+
+/// [`is_quiet`] with the override and the clock (minutes since midnight)
+/// injected.
+pub fn is_quiet_at(windows: &[Window], forced: Option<bool>, minute: u16) -> bool {
+    forced.unwrap_or_else(|| windows.iter().any(|w| w.contains(minute)))
+}
+
+fn env_override() -> Option<bool> {
+    match env::var("HOOK_QUIET").ok()?.trim() {
+        "1" | "true" => Some(true),
+        "0" | "false" => Some(false),
+        _ => None,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(s: &str) -> Window {
+        Window::try_from(s.to_string()).unwrap()
+    }
+
+    const fn at(h: u16, m: u16) -> u16 {
+        h * 60 + m
+    }
+
+    #[test]
+    fn parses_and_rejects_windows() {
+        assert_eq!(window("09:00-17:30").to_string(), "09:00-17:30");
+        assert_eq!(window(" 9:05 - 17:00 ").to_string(), "09:05-17:00");
+        for bad in ["9-17", "25:00-26:00", "09:00", "09:60-10:00"] {
+            assert!(Window::try_from(bad.to_string()).is_err(), "accepted {}", bad);
+        }
+    }
+
+    #[test]
+    fn quiet_inside_window_only() {
+        let windows = [window("12:00-13:00"), window("18:00-09:00")];
+        assert!(is_quiet_at(&windows, None, at(12, 30)));
+        assert!(is_quiet_at(&windows, None, at(23, 0)));
+        assert!(is_quiet_at(&windows, None, at(8, 59)));
+        assert!(!is_quiet_at(&windows, None, at(9, 0)));
+        assert!(!is_quiet_at(&windows, None, at(13, 0)));
+        assert!(!is_quiet_at(&[], None, at(23, 0)));
+    }
+
+    #[test]
+    fn override_wins_over_clock() {
+        let windows = [window("18:00-09:00")];
+        assert!(is_quiet_at(&[], Some(true), at(10, 0)));
+        assert!(!is_quiet_at(&windows, Some(false), at(23, 0)));
+    }
+
+    #[test]
+    fn config_rejects_bad_window() {
+        #[derive(Debug, Default, Deserialize)]
+        struct Cfg {
+            #[allow(dead_code)]
+            quiet_hours: Vec<Window>,
+        }
+        let err = toml::from_str::<Cfg>("quiet_hours = [\"after six\"]").unwrap_err();
+        assert!(err.message().contains("expected `HH:MM-HH:MM`"), "got: {}", err);
+    }
+}
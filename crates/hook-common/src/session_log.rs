@@ -0,0 +1,168 @@
+//! Per-session activity log and the closing report built from it.
+//!
+//! With `HOOK_SESSION_REPORT=1` set, every decision that has findings is also
+//! appended to `<state dir>/session-log-<session>.jsonl` (see [`crate::state`])
+//! as the same JSON object the decision sink receives (see [`crate::sink`]).
+//! On Stop, [`crate::stop::StopChecks::report_session`] reads it back and shows
+//! the user a per-hook count of what was caught so far. Logging is
+//! best-effort: a log that can't be written or read is treated as empty.
+
+use crate::sink::Report;
+use crate::state;
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Whether `HOOK_SESSION_REPORT=1` is set.
+pub fn enabled() -> bool {
+    env::var("HOOK_SESSION_REPORT").is_ok_and(|v| v.trim() == "1")
+}
+
+/// The log file for `session_id`.
+pub fn path(session_id: &str) -> PathBuf {
+    state::session_path("session-log", session_id, "jsonl")
+}
+
+/// Append `report` to its session's log, if enabled.
+pub fn record(report: &Report) {
+    if enabled() && !report.session.is_empty() {
+        record_in(&path(&report.session), report);
+    }
+}
+
+fn record_in(path: &Path, report: &Report) {
+    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+        let _ = writeln!(file, "{}", report.payload());
+    }
+}
+
+/// Decisions one hook made during the session, by outcome.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HookActivity {
+    pub hook: String,
+    pub blocked: usize,
+    pub warned: usize,
+    pub noted: usize,
+}
+
+/// Per-hook counts from the log at `path` plus any `pending` decisions not
+/// written yet (the current Stop's own), hooks in order of first appearance.
+pub fn summarize(path: &Path, pending: &[&Report]) -> Vec<HookActivity> {
+    let log = fs::read_to_string(path).unwrap_or_default();
+    let logged = log
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter_map(|v| {
+            Some((
+                v["hook"].as_str()?.to_string(),
+                v["decision"].as_str()?.to_string(),
+            ))
+        });
+    let pending = pending.iter().map(|r| (r.hook.clone(), r.decision.clone()));
+
+    let mut activity: Vec<HookActivity> = Vec::new();
+    for (hook, decision) in logged.chain(pending) {
+        let i = match activity.iter().position(|a| a.hook == hook) {
+            Some(i) => i,
+            None => {
+                activity.push(HookActivity { hook, ..HookActivity::default() });
+                activity.len() - 1
+            }
+        };
+        match decision.as_str() {
+            "block" => activity[i].blocked += 1,
+            "warn" => activity[i].warned += 1,
+            _ => activity[i].noted += 1,
+        }
+    }
+    activity
+}
+
+/// The closing report shown to the user, `None` if nothing was caught.
+pub fn render(activity: &[HookActivity]) -> Option<String> {
+    if activity.is_empty() {
+        return None;
+    }
+    let lines: Vec<String> = activity
+        .iter()
+        .map(|a| {
+            let counts: Vec<String> = [(a.blocked, "blocked"), (a.warned, "warned"), (a.noted, "noted")]
+                .iter()
+                .filter(|(n, _)| *n > 0)
+                .map(|(n, what)| format!("{} {}", n, what))
+                .collect();
+            format!("  - {}: {}", a.hook, counts.join(", "))
+        })
+        .collect();
+    Some(format!("Hook activity this session:\n{}", lines.join("\n")))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_log(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("hook-common-session-log-{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("session-log-s1.jsonl")
+    }
+
+    fn report(hook: &str, decision: &str) -> Report {
+        Report::new(hook, "s1", decision, "msg".to_string())
+    }
+
+    #[test]
+    fn counts_each_hooks_decisions_from_the_log() {
+        let log = scratch_log("counts");
+        for (hook, decision) in [
+            ("windows-bash-guard", "block"),
+            ("mediocrity-detector", "warn"),
+            ("windows-bash-guard", "block"),
+            ("unrelated-issue-detector", "block"),
+            ("windows-bash-guard", "info"),
+        ] {
+            record_in(&log, &report(hook, decision));
+        }
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&log)
+            .unwrap()
+            .write_all(b"not json\n")
+            .unwrap();
+
+        let pending = report("mediocrity-detector", "block");
+        let activity = summarize(&log, &[&pending]);
+        let counts: Vec<(&str, usize, usize, usize)> = activity
+            .iter()
+            .map(|a| (a.hook.as_str(), a.blocked, a.warned, a.noted))
+            .collect();
+        assert_eq!(
+            counts,
+            [
+                ("windows-bash-guard", 2, 0, 1),
+                ("mediocrity-detector", 1, 1, 0),
+                ("unrelated-issue-detector", 1, 0, 0),
+            ]
+        );
+        assert_eq!(
+            render(&activity).unwrap(),
+            "Hook activity this session:\n\
+             \x20 - windows-bash-guard: 2 blocked, 1 noted\n\
+             \x20 - mediocrity-detector: 1 blocked, 1 warned\n\
+             \x20 - unrelated-issue-detector: 1 blocked"
+        );
+    }
+
+    #[test]
+    fn missing_log_has_nothing_to_report() {
+        let log = scratch_log("missing");
+        assert!(summarize(&log, &[]).is_empty());
+        assert_eq!(render(&[]), None);
+    }
+}
@@ -0,0 +1,193 @@
+//! Optional decision sink for central monitoring.
+//!
+//! With `HOOK_SINK_URL=http://host:port/path` set, every decision that has
+//! findings is also POSTed there as JSON (`{hook, session, decision, summary,
+//! ts}`) just before the hook exits. The hook's normal output is unchanged.
+//! Paths in the summary are anonymized under `HOOK_ANONYMIZE_PATHS=1` (see
+//! [`crate::anonymize`]). Delivery is best-effort: the whole request shares a [`TIMEOUT`] budget, and
+//! any failure (unreachable host, slow server, `https://` URL) is ignored.
+
+use serde_json::{json, Value};
+use std::env;
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Upper bound on the time a hook spends reporting one decision.
+pub const TIMEOUT: Duration = Duration::from_millis(300);
+
+/// One decision as sent to the sink.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Report {
+    pub hook: String,
+    pub session: String,
+    /// `block`, `warn` or `info`: the outcome after severity thresholds.
+    pub decision: String,
+    /// The findings' messages, one per line, secrets masked (and paths
+    /// anonymized, if enabled).
+    pub summary: String,
+    /// Unix time in milliseconds.
+    pub ts: u64,
+}
+
+impl Report {
+    pub fn new(hook: &str, session: &str, decision: &str, summary: String) -> Report {
+        let ts = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        Report {
+            hook: hook.to_string(),
+            session: session.to_string(),
+            decision: decision.to_string(),
+            summary,
+            ts,
+        }
+    }
+
+    pub fn payload(&self) -> Value {
+        json!({
+            "hook": self.hook,
+            "session": self.session,
+            "decision": self.decision,
+            "summary": self.summary,
+            "ts": self.ts,
+        })
+    }
+}
+
+/// POST `report` to `$HOOK_SINK_URL`, if set. Errors are swallowed.
+pub fn send(report: &Report) {
+    if let Some(url) = env::var("HOOK_SINK_URL").ok().filter(|u| !u.is_empty()) {
+        let _ = post(&url, &report.payload().to_string(), TIMEOUT);
+    }
+}
+
+/// Minimal HTTP/1.1 POST of a JSON body to an `http://` URL, within `timeout`
+/// overall. The response status is read (so the server sees a complete
+/// exchange) but not checked.
+pub fn post(url: &str, body: &str, timeout: Duration) -> io::Result<()> {
+    let deadline = Instant::now() + timeout;
+    let remaining = || {
+        deadline
+            .checked_duration_since(Instant::now())
+            .filter(|d| !d.is_zero())
+            .ok_or_else(|| io::Error::from(io::ErrorKind::TimedOut))
+    };
+
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "only http:// sinks"))?;
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let addr_text = if authority.contains(':') {
+        authority.to_string()
+    } else {
+        format!("{}:80", authority)
+    };
+    let addr = addr_text
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))?;
+
+    let mut stream = TcpStream::connect_timeout(&addr, remaining()?)?;
+    stream.set_write_timeout(Some(remaining()?))?;
+    write!(
+        stream,
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        path,
+        authority,
+        body.len(),
+        body
+    )?;
+    stream.set_read_timeout(Some(remaining()?))?;
+    let mut status = [0u8; 12];
+    let _ = stream.read(&mut status)?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Accept one request and return its body.
+    fn mock_server() -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (mut conn, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            loop {
+                let n = conn.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request).into_owned();
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let len: usize = head
+                        .lines()
+                        .find_map(|l| l.strip_prefix("Content-Length: "))
+                        .unwrap()
+                        .parse()
+                        .unwrap();
+                    if body.len() >= len {
+                        conn.write_all(b"HTTP/1.1 204 No Content\r\n\r\n").unwrap();
+                        assert!(head.starts_with("POST /hooks HTTP/1.1\r\n"), "got: {}", head);
+                        return body.to_string();
+                    }
+                }
+                if n == 0 {
+                    panic!("connection closed mid-request");
+                }
+            }
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn posts_report_payload() {
+        let (url, server) = mock_server();
+        let report = Report::new("test-hook", "s-1", "block", "do not do that".to_string());
+        post(&url, &report.payload().to_string(), Duration::from_secs(5)).unwrap();
+
+        let body: Value = serde_json::from_str(&server.join().unwrap()).unwrap();
+        assert_eq!(body["hook"], "test-hook");
+        assert_eq!(body["session"], "s-1");
+        assert_eq!(body["decision"], "block");
+        assert_eq!(body["summary"], "do not do that");
+        assert!(body["ts"].as_u64().unwrap() > 0);
+        assert_eq!(body.as_object().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn down_endpoint_fails_fast() {
+        // Nothing listens on a port whose listener was just dropped.
+        let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let start = Instant::now();
+        assert!(post(&format!("http://127.0.0.1:{}/", port), "{}", TIMEOUT).is_err());
+        assert!(start.elapsed() < TIMEOUT * 2, "took {:?}", start.elapsed());
+    }
+
+    #[test]
+    fn silent_endpoint_is_cut_off_at_timeout() {
+        // Accepts connections (via the backlog) but never answers.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let start = Instant::now();
+        assert!(post(&url, "{}", TIMEOUT).is_err());
+        assert!(start.elapsed() < TIMEOUT * 2, "took {:?}", start.elapsed());
+    }
+
+    #[test]
+    fn rejects_https() {
+        assert!(post("https://example.org/", "{}", TIMEOUT).is_err());
+    }
+}
@@ -0,0 +1,52 @@
+//! Per-session state files for hooks that need memory across invocations.
+//!
+//! Files live in `$HOOK_STATE_DIR` when set (tests point it at a scratch
+//! directory), otherwise in `<temp>/claude-hooks`. State is best-effort: a hook
+//! that can't read or write it behaves as if it were empty.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// The state directory, created on demand.
+pub fn dir() -> PathBuf {
+    let dir = match env::var_os("HOOK_STATE_DIR") {
+        Some(d) if !d.is_empty() => PathBuf::from(d),
+        _ => env::temp_dir().join("claude-hooks"),
+    };
+    let _ = fs::create_dir_all(&dir);
+    dir
+}
+
+/// `<state dir>/<hook>-<session>.<ext>`. The session id is sanitized so a
+/// hostile value can't escape the directory.
+pub fn session_path(hook: &str, session_id: &str, ext: &str) -> PathBuf {
+    dir().join(session_file_name(hook, session_id, ext))
+}
+
+fn session_file_name(hook: &str, session_id: &str, ext: &str) -> String {
+    let session: String = session_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    format!("{}-{}.{}", hook, session, ext)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_name_includes_hook_and_session() {
+        assert_eq!(session_file_name("h", "abc-123", "json"), "h-abc-123.json");
+    }
+
+    #[test]
+    fn file_name_sanitizes_session() {
+        assert_eq!(session_file_name("h", "../../etc/x", "json"), "h-______etc_x.json");
+    }
+}
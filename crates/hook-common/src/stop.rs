@@ -0,0 +1,399 @@
+//! Stop-hook orchestration: parse the finished turn once, run every
+//! registered Stop check on it, and answer with a single decision.
+//!
+//! Each check that blocked on its own would cost Claude a round-trip per
+//! check. Registered into one [`StopChecks`], their findings share one
+//! [`Decision`] and one reason: a single check's reason is used as is, several
+//! are combined into a turn report with a section per check.
+//!
+//! With [`StopChecks::report_session`], the answer also ends the turn with the
+//! session's hook activity (see [`crate::session_log`]) as a message to the
+//! user, whether or not the checks found anything. It never blocks.
+
+use crate::decision::{Decision, Emission, Finding};
+use crate::edits::{EditOp, WriteOp};
+use crate::session_log;
+use serde_json::Value;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::ptr;
+
+/// The current turn: everything after the last real user message.
+#[derive(Debug, Default)]
+pub struct Turn {
+    /// Content blocks (`text`, `tool_use`, …) of the turn's assistant
+    /// messages, in order.
+    pub blocks: Vec<Value>,
+}
+
+impl Turn {
+    /// The turn at the end of a transcript file, `None` if it can't be read.
+    pub fn load(transcript_path: &str) -> Option<Turn> {
+        std::fs::read_to_string(transcript_path)
+            .ok()
+            .map(|t| Turn::from_transcript(&t))
+    }
+
+    pub fn from_transcript(transcript: &str) -> Turn {
+        let lines: Vec<&str> = transcript.lines().collect();
+        let mut blocks = Vec::new();
+        for line in &lines[find_turn_start(&lines)..] {
+            let Ok(mut entry) = serde_json::from_str::<Value>(line) else {
+                continue;
+            };
+            if entry["type"].as_str() != Some("assistant") {
+                continue;
+            }
+            if let Some(content) = entry["message"]["content"].as_array_mut() {
+                blocks.append(content);
+            }
+        }
+        Turn { blocks }
+    }
+}
+
+/// Every Write/Edit/MultiEdit among a turn's content `blocks`, in order.
+pub fn turn_edits(blocks: &[Value]) -> Vec<EditOp> {
+    blocks
+        .iter()
+        .filter(|b| b["type"].as_str() == Some("tool_use"))
+        .filter_map(|b| WriteOp::from_tool_input(b["name"].as_str().unwrap_or(""), &b["input"]))
+        .map(|w| EditOp::from(&w))
+        .collect()
+}
+
+/// Walk backwards to find the last real user message (string content, not
+/// tool_result array). Everything after it belongs to the current turn.
+pub fn find_turn_start(lines: &[&str]) -> usize {
+    for i in (0..lines.len()).rev() {
+        // Quick pre-filter before JSON parsing
+        if !lines[i].contains("\"user\"") {
+            continue;
+        }
+
+        let entry: Value = match serde_json::from_str(lines[i]) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+
+        if entry["type"].as_str() == Some("user") && entry["message"]["content"].is_string() {
+            return i;
+        }
+    }
+
+    0
+}
+
+type CheckFn<'a> = Box<dyn Fn(&Turn) -> Vec<Finding> + 'a>;
+
+struct StopCheck<'a> {
+    name: &'static str,
+    check: CheckFn<'a>,
+    reason: fn(&[&Finding]) -> String,
+}
+
+/// The registered Stop checks, in run order.
+#[derive(Default)]
+pub struct StopChecks<'a> {
+    checks: Vec<StopCheck<'a>>,
+    session_log: Option<PathBuf>,
+}
+
+impl<'a> StopChecks<'a> {
+    pub fn new() -> Self {
+        StopChecks::default()
+    }
+
+    /// Add a check. `reason` builds its part of the message from its own
+    /// actionable findings, as it would if it were the only check.
+    pub fn register(
+        &mut self,
+        name: &'static str,
+        check: impl Fn(&Turn) -> Vec<Finding> + 'a,
+        reason: fn(&[&Finding]) -> String,
+    ) {
+        self.checks.push(StopCheck {
+            name,
+            check: Box::new(check),
+            reason,
+        });
+    }
+
+    /// Close the turn with a summary of the session log at `log` (see
+    /// [`TurnReport::finish`]).
+    pub fn report_session(&mut self, log: PathBuf) {
+        self.session_log = Some(log);
+    }
+
+    /// Run every check on `turn`, pushing all findings into `decision`.
+    pub fn run(&self, turn: &Turn, decision: &mut Decision) -> TurnReport {
+        let mut sections = Vec::new();
+        for c in &self.checks {
+            let start = decision.findings().len();
+            for f in (c.check)(turn) {
+                decision.push(f);
+            }
+            sections.push(Section {
+                name: c.name,
+                reason: c.reason,
+                range: start..decision.findings().len(),
+            });
+        }
+        TurnReport {
+            sections,
+            session_log: self.session_log.clone(),
+        }
+    }
+}
+
+struct Section {
+    name: &'static str,
+    reason: fn(&[&Finding]) -> String,
+    /// Indices of the check's findings in the decision.
+    range: Range<usize>,
+}
+
+/// Which check produced which of a decision's findings.
+pub struct TurnReport {
+    sections: Vec<Section>,
+    session_log: Option<PathBuf>,
+}
+
+impl TurnReport {
+    /// The reason for [`Decision::emit`]: `all` is the decision's findings and
+    /// `findings` the actionable ones `emit` passes in.
+    pub fn reason(&self, all: &[Finding], findings: &[&Finding]) -> String {
+        let parts: Vec<(&str, String)> = self
+            .sections
+            .iter()
+            .filter_map(|s| {
+                let own: Vec<&Finding> = findings
+                    .iter()
+                    .copied()
+                    .filter(|f| all[s.range.clone()].iter().any(|g| ptr::eq(g, *f)))
+                    .collect();
+                (!own.is_empty()).then(|| (s.name, (s.reason)(&own)))
+            })
+            .collect();
+        match parts.as_slice() {
+            [(_, reason)] => reason.clone(),
+            _ => {
+                let body = parts
+                    .iter()
+                    .map(|(name, reason)| format!("[{}]\n{}", name, reason))
+                    .collect::<Vec<_>>()
+                    .join("\n\n");
+                format!("{} Stop checks flagged this turn:\n\n{}", parts.len(), body)
+            }
+        }
+    }
+
+    /// `out` with the session summary added as a `systemMessage`, after any
+    /// warning it already shows. The summary counts `out`'s own decision,
+    /// which is only logged on exit. Output that isn't the hook protocol
+    /// (SARIF, annotations) is left alone.
+    pub fn finish(&self, mut out: Emission) -> Emission {
+        let Some(log) = &self.session_log else {
+            return out;
+        };
+        let pending: Vec<_> = out.report.iter().collect();
+        let Some(summary) = session_log::render(&session_log::summarize(log, &pending)) else {
+            return out;
+        };
+        let mut v = if out.stdout.trim().is_empty() {
+            serde_json::json!({})
+        } else {
+            match serde_json::from_str::<Value>(out.stdout.trim()) {
+                Ok(v) if v.get("decision").is_some() || v.get("systemMessage").is_some() => v,
+                _ => return out,
+            }
+        };
+        let message = match v["systemMessage"].as_str() {
+            Some(warning) => format!("{}\n\n{}", warning, summary),
+            None => summary,
+        };
+        v["systemMessage"] = Value::String(message);
+        out.stdout = format!("{}\n", v);
+        out
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decision::Event;
+
+    fn turn(texts: &[&str]) -> Turn {
+        Turn {
+            blocks: texts
+                .iter()
+                .map(|t| serde_json::json!({"type": "text", "text": t}))
+                .collect(),
+        }
+    }
+
+    fn texts_containing(turn: &Turn, needle: &'static str) -> Vec<Finding> {
+        turn.blocks
+            .iter()
+            .filter_map(|b| b["text"].as_str())
+            .filter(|t| t.contains(needle))
+            .map(|t| Finding::block(needle, t.to_string()))
+            .collect()
+    }
+
+    fn list(findings: &[&Finding]) -> String {
+        findings
+            .iter()
+            .map(|f| f.message.as_str())
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    #[test]
+    fn several_checks_block_once_with_a_combined_report() {
+        let mut checks = StopChecks::new();
+        checks.register("debug-prints", |t| texts_containing(t, "println"), list);
+        checks.register("skipped-tests", |t| texts_containing(t, "#[ignore]"), list);
+        checks.register("quiet-one", |_| vec![Finding::info("note", "fyi")], list);
+
+        let t = turn(&["added println!(x)", "marked it #[ignore]", "all done"]);
+        let mut decision = Decision::new("stop-checks", Event::Stop);
+        let report = checks.run(&t, &mut decision);
+        let out = decision.emit(|f| report.reason(decision.findings(), f));
+
+        assert_eq!(out.code, 0);
+        let v: Value = serde_json::from_str(out.stdout.trim()).unwrap();
+        assert_eq!(v["decision"], "block");
+        assert_eq!(out.stdout.trim().lines().count(), 1, "one decision, got: {}", out.stdout);
+        assert_eq!(
+            v["reason"],
+            "2 Stop checks flagged this turn:\n\n\
+             [debug-prints]\nadded println!(x)\n\n\
+             [skipped-tests]\nmarked it #[ignore]"
+        );
+    }
+
+    #[test]
+    fn single_check_keeps_its_own_reason() {
+        let mut checks = StopChecks::new();
+        checks.register("debug-prints", |t| texts_containing(t, "println"), list);
+        checks.register("skipped-tests", |t| texts_containing(t, "#[ignore]"), list);
+
+        let t = turn(&["added println!(x)"]);
+        let mut decision = Decision::new("stop-checks", Event::Stop);
+        let report = checks.run(&t, &mut decision);
+        let out = decision.emit(|f| report.reason(decision.findings(), f));
+        let v: Value = serde_json::from_str(out.stdout.trim()).unwrap();
+        assert_eq!(v["reason"], "added println!(x)");
+    }
+
+    #[test]
+    fn session_summary_is_added_without_changing_the_decision() {
+        let dir = std::env::temp_dir().join("hook-common-stop-session-report");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let log = dir.join("session-log-s1.jsonl");
+        std::fs::write(
+            &log,
+            "{\"hook\":\"windows-bash-guard\",\"session\":\"s1\",\"decision\":\"block\",\"summary\":\"x\",\"ts\":1}\n",
+        )
+        .unwrap();
+
+        let mut checks = StopChecks::new();
+        checks.register("debug-prints", |t| texts_containing(t, "println"), list);
+        checks.report_session(log.clone());
+
+        let mut decision = Decision::new("stop-checks", Event::Stop);
+        let report = checks.run(&turn(&["added println!(x)"]), &mut decision);
+        let out = report.finish(decision.emit(|f| report.reason(decision.findings(), f)));
+        let v: Value = serde_json::from_str(out.stdout.trim()).unwrap();
+        assert_eq!(v["decision"], "block");
+        assert_eq!(v["reason"], "added println!(x)");
+        assert_eq!(
+            v["systemMessage"],
+            "Hook activity this session:\n  - windows-bash-guard: 1 blocked\n  - stop-checks: 1 blocked"
+        );
+
+        // A clean turn still reports what the session caught.
+        let mut decision = Decision::new("stop-checks", Event::Stop);
+        let report = checks.run(&turn(&["all done"]), &mut decision);
+        let out = report.finish(decision.emit(|f| report.reason(decision.findings(), f)));
+        assert_eq!(out.code, 0);
+        assert_eq!(
+            out.stdout,
+            "{\"systemMessage\":\"Hook activity this session:\\n  - windows-bash-guard: 1 blocked\"}\n"
+        );
+
+        // Nothing logged and nothing found: silent.
+        std::fs::remove_file(&log).unwrap();
+        let out = report.finish(decision.emit(|f| report.reason(decision.findings(), f)));
+        assert!(out.stdout.is_empty());
+    }
+
+    #[test]
+    fn turn_holds_assistant_blocks_after_last_user_message() {
+        let transcript = [
+            r#"{"type":"user","message":{"role":"user","content":"First task"}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Old."}]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":"Second task"}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"On it."}]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"1"}]}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","name":"Write","input":{}}]}}"#,
+        ]
+        .join("\n");
+        let t = Turn::from_transcript(&transcript);
+        assert_eq!(t.blocks.len(), 2);
+        assert_eq!(t.blocks[0]["text"], "On it.");
+        assert_eq!(t.blocks[1]["name"], "Write");
+    }
+
+    #[test]
+    fn turn_edits_normalizes_every_write_shape() {
+        let blocks = vec![
+            serde_json::json!({"type": "text", "text": "Writing."}),
+            serde_json::json!({"type": "tool_use", "name": "Write",
+                "input": {"file_path": "a.rs", "content": "fn a() {}\nfn b() {}"}}),
+            serde_json::json!({"type": "tool_use", "name": "Edit",
+                "input": {"file_path": "a.rs", "old_string": "fn a() {}", "new_string": "fn a() {}\nfn b() {}"}}),
+            serde_json::json!({"type": "tool_use", "name": "MultiEdit",
+                "input": {"file_path": "a.rs", "edits": [
+                    {"old_string": "x", "new_string": "fn a() {}"},
+                    {"old_string": "y", "new_string": "fn b() {}"}
+                ]}}),
+            serde_json::json!({"type": "tool_use", "name": "Bash", "input": {"command": "ls"}}),
+        ];
+        let edits = turn_edits(&blocks);
+        assert_eq!(edits.len(), 3);
+        let expected = EditOp {
+            path: "a.rs".to_string(),
+            added_text: "fn a() {}\nfn b() {}".to_string(),
+        };
+        assert!(edits.iter().all(|e| *e == expected), "got: {:?}", edits);
+    }
+
+    #[test]
+    fn finds_turn_start_skips_tool_results() {
+        let lines = vec![
+            r#"{"type":"user","message":{"role":"user","content":"Fix the bug"}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"On it."}]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"123"}]}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Done."}]}}"#,
+        ];
+        assert_eq!(find_turn_start(&lines), 0);
+    }
+
+    #[test]
+    fn finds_latest_user_message() {
+        let lines = vec![
+            r#"{"type":"user","message":{"role":"user","content":"First task"}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Done."}]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":"Second task"}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Working."}]}}"#,
+        ];
+        assert_eq!(find_turn_start(&lines), 2);
+    }
+}
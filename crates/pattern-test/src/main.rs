@@ -0,0 +1,36 @@
+//! `pattern-test <group>`: read a text sample on stdin and list every match
+//! of a phrase detector's pattern group, with byte spans, using the hooks' own
+//! vocabularies and matching.
+//!
+//! `<group>` is `<hook>/<group>` (`mediocrity-detector/code-markers`) for one
+//! hook, or a bare group name (`phrases`) for that group in every detector
+//! that has it. An unknown group lists the available ones and exits 2.
+
+use hook_common::pattern::{hooks_report, Group};
+use std::env;
+use std::io::{self, Read};
+use std::process;
+
+/// Every phrase detector's pattern groups, by hook.
+fn hooks() -> Vec<(&'static str, Vec<Group<'static>>)> {
+    vec![
+        ("mediocrity-detector", mediocrity_detector::pattern_groups()),
+        ("unrelated-issue-detector", unrelated_issue_detector::pattern_groups()),
+    ]
+}
+
+fn main() {
+    let Some(query) = env::args().nth(1) else {
+        eprintln!("usage: pattern-test <group> < sample.txt");
+        process::exit(2);
+    };
+    let mut text = String::new();
+    let _ = io::stdin().read_to_string(&mut text);
+    match hooks_report(&hooks(), &query, &text) {
+        Ok(report) => print!("{}", report),
+        Err(problem) => {
+            eprintln!("pattern-test: {}", problem);
+            process::exit(2);
+        }
+    }
+}
@@ -0,0 +1,70 @@
+//! Integration tests driving the `pattern-test` binary via stdin/stdout.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+fn pattern_test(args: &[&str], sample: &str) -> (String, String, i32) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_pattern-test"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn pattern-test");
+    child.stdin.take().expect("stdin").write_all(sample.as_bytes()).expect("write stdin");
+    let out = child.wait_with_output().expect("wait");
+    (
+        String::from_utf8(out.stdout).expect("utf8 stdout"),
+        String::from_utf8(out.stderr).expect("utf8 stderr"),
+        out.status.code().unwrap_or(-1),
+    )
+}
+
+#[test]
+fn bare_group_reports_every_detector_with_it() {
+    let (stdout, _, code) = pattern_test(&["phrases"], "Good enough for now, it's a pre-existing issue.");
+    assert_eq!(code, 0);
+    assert_eq!(
+        stdout,
+        "mediocrity-detector/phrases:\n\
+         0..11      good enough              \"Good enough\"\n\
+         12..19     for now                  \"for now\"\n\
+         2 matches\n\
+         unrelated-issue-detector/phrases:\n\
+         28..46     pre-existing issue       \"pre-existing issue\"\n\
+         1 match\n"
+    );
+}
+
+#[test]
+fn qualified_group_reports_one_detector() {
+    let (stdout, _, code) = pattern_test(&["mediocrity-detector/code-markers"], "// TODO: later, todo");
+    assert_eq!(code, 0);
+    assert_eq!(
+        stdout,
+        "mediocrity-detector/code-markers:\n\
+         3..7       TODO                     \"TODO\"\n\
+         1 match\n"
+    );
+
+    let (stdout, _, code) = pattern_test(&["unrelated-issue-detector/phrases"], "All tests pass.");
+    assert_eq!((stdout.as_str(), code), ("unrelated-issue-detector/phrases:\nno matches\n", 0));
+}
+
+#[test]
+fn unknown_group_lists_the_known_ones() {
+    let (stdout, stderr, code) = pattern_test(&["nope"], "");
+    assert_eq!((stdout.as_str(), code), ("", 2));
+    assert!(
+        stderr.contains(
+            "unknown pattern group `nope` (groups: mediocrity-detector/phrases, \
+             mediocrity-detector/code-markers, unrelated-issue-detector/phrases)"
+        ),
+        "got: {}",
+        stderr
+    );
+
+    let (_, stderr, code) = pattern_test(&[], "");
+    assert_eq!(code, 2);
+    assert!(stderr.starts_with("usage: pattern-test <group>"), "got: {}", stderr);
+}
@@ -14,6 +14,9 @@
 //!
 //! Bypass: add `[no-rewrite]` to the tool description.
 
+use hook_common::config;
+use hook_common::dead_letter;
+use hook_common::doctor::{self, Doctor};
 use serde_json::{json, Value};
 use std::io::{self, Read};
 use std::process;
@@ -26,12 +29,19 @@ use std::process;
 const INJECT_PREFIX: &str = " printf '\\n\\n' ";
 
 fn main() {
+    if doctor::requested() {
+        Doctor::new("command-chain-separator").exit();
+    }
+
     let mut input = String::new();
-    if io::stdin().read_to_string(&mut input).is_err() {
+    if io::stdin().read_to_string(&mut input).is_err() || !config::enabled_here() {
         process::exit(0);
     }
+    dead_letter::guard("command-chain-separator", &input, || run(&input));
+}
 
-    let data: Value = match serde_json::from_str(&input) {
+fn run(input: &str) {
+    let data: Value = match serde_json::from_str(input) {
         Ok(v) => v,
         Err(_) => process::exit(0),
     };
@@ -0,0 +1,63 @@
+//! mediocrity-detector's vocabulary, shared by the hook and the
+//! `pattern-test` tool (see `crates/pattern-test`).
+
+use hook_common::pattern::Group;
+
+/// Hedging phrases matched case-insensitively, each with a hint of what a
+/// complete solution looks like.
+pub const PATTERNS: &[(&str, &str)] = &[
+    // Deferred work
+    ("for now", "do the deferred part now, or record it as a tracked issue with an owner"),
+    ("revisit later", "resolve the open question now, or file a tracked issue with the trigger for revisiting"),
+    ("revisit this", "resolve the open question now, or file a tracked issue with the trigger for revisiting"),
+    ("come back to this", "finish it in this change, or file a tracked issue describing what is left"),
+    ("should be replaced", "replace it now, or name the replacement and file a tracked issue"),
+    ("should be updated", "make the update now, or file a tracked issue with the exact change needed"),
+    ("should be revisited", "resolve the open question now, or file a tracked issue with the trigger for revisiting"),
+    ("will need to be", "do it now if it is in scope; otherwise tell the user exactly what remains"),
+    // Quality shortcuts
+    ("good enough", "state the bar it falls short of and meet it, or get the user's sign-off on the gap"),
+    ("acceptable solution", "state the bar it falls short of and meet it, or get the user's sign-off on the gap"),
+    ("simple enough", "check the edge cases the simple version skips and handle them"),
+    ("simple approach", "list the cases the simple approach gets wrong and handle them, or justify why they can't occur"),
+    ("basic implementation", "implement the missing behavior (error paths, edge cases, configuration)"),
+    ("simplified version", "implement the full version, or list exactly what was left out"),
+    ("quick and dirty", "clean it up: proper structure, error handling and tests"),
+    ("not ideal", "implement the ideal version, or explain the constraint that prevents it"),
+    // Version hedging
+    ("first version", "finish the feature, or list what the next version must add"),
+    ("initial version", "finish the feature, or list what the next version must add"),
+    // Placeholder/mock
+    ("placeholder", "implement the real logic, or leave a tracked TODO with an owner"),
+    ("hardcoded", "read the value from configuration, input or a named constant with its source documented"),
+    ("hard-coded", "read the value from configuration, input or a named constant with its source documented"),
+    ("workaround", "fix the underlying cause, or link the upstream issue the workaround is waiting on"),
+    ("temporary fix", "land the permanent fix, or file a tracked issue with the removal condition"),
+    ("temporary solution", "land the permanent solution, or file a tracked issue with the removal condition"),
+    ("temporary", "make it permanent, or file a tracked issue with the removal condition"),
+];
+
+/// Code markers matched case-sensitively, with their complete-solution hints.
+pub const CODE_MARKERS: &[(&str, &str)] = &[
+    ("TODO", "do the work now, or link a tracked issue with an owner in the comment"),
+    ("FIXME", "fix the defect before stopping"),
+    ("HACK", "replace the hack with a proper implementation, or document why it is required"),
+    ("XXX", "resolve the flagged problem before stopping"),
+];
+
+/// The pattern groups the hook matches, by name and with the case
+/// sensitivity its scan uses, for `--pattern-test` and `pattern-test`.
+pub fn pattern_groups() -> Vec<Group<'static>> {
+    vec![
+        Group {
+            name: "phrases",
+            patterns: PATTERNS.iter().map(|&(p, _)| p).collect(),
+            case_sensitive: false,
+        },
+        Group {
+            name: "code-markers",
+            patterns: CODE_MARKERS.iter().map(|&(m, _)| m).collect(),
+            case_sensitive: true,
+        },
+    ]
+}
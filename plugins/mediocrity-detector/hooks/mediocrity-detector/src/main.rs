@@ -3,55 +3,177 @@
 //! Strategy: trust but verify. Scans the current turn's assistant messages for
 //! patterns indicating corners were cut, then blocks the stop and asks Claude to
 //! explicitly report each assumption so the user can make a judgement call.
+//!
+//! Also blocks on explicit not-implemented markers added by this turn's edits
+//! outside tests: `todo!()`, `unimplemented!()`, `unreachable!()`,
+//! `throw new Error("not implemented")` and `raise NotImplementedError`.
+//!
+//! Also warns (without blocking) when the turn claims a root-cause fix but its
+//! edits only add symptom-level handling: try/catch, null checks, retries.
+//!
+//! Also warns when the turn claims to follow the existing style or pattern
+//! but an edit's added lines don't match the text it replaced: tab indentation
+//! in space-indented code (or the reverse), or camelCase names among
+//! snake_case ones (or the reverse).
+//!
+//! A second Stop check warns when the project has a formatter config
+//! (`rustfmt.toml`, `.prettierrc`, `[tool.black]` in `pyproject.toml`) and the
+//! turn committed edits without running a formatter since the last edit.
+//!
+//! In strict-turn mode (`[strict_turn]` in the config), a third Stop check
+//! blocks until the turn ends with a completion checklist: a marker line
+//! followed by a list item for each required entry (tests run, lints clean,
+//! edge cases).
+//!
+//! With `MEDIOCRITY_TEACH=1`, a blocking reason ends with a worked example
+//! (shortcut vs. complete solution) for each category of shortcut it found.
+//!
+//! All run as entries of a [`StopChecks`] registry, so their findings (and
+//! those of Stop checks added later) share this hook's single decision.
+//!
+//! With `HOOK_SESSION_REPORT=1`, every stop also shows the user a count of
+//! what each hook caught during the session.
 
-use serde_json::{json, Value};
-use std::collections::HashSet;
+use hook_common::bash::{program_args, program_name, simple_commands, Word};
+use hook_common::config;
+use hook_common::dead_letter;
+use hook_common::doctor::{self, Doctor};
+use hook_common::decision::{Decision, Event, Finding, Severity, DEFAULT_MAX_FINDINGS};
+use hook_common::edits::{is_test_path, EditOp, WriteOp};
+use hook_common::false_positive;
+use hook_common::pattern::{self, find_case_insensitive, DocUrls};
+use hook_common::quiet;
+use hook_common::session_log;
+use hook_common::state;
+use hook_common::stop::{StopChecks, Turn};
+use mediocrity_detector::{pattern_groups, CODE_MARKERS, PATTERNS};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashSet};
+use std::env;
+use std::fs;
 use std::io::{self, Read};
+use std::path::{Path, PathBuf};
 use std::process;
 
-/// Hedging phrases matched case-insensitively.
-const PATTERNS: &[&str] = &[
-    // Deferred work
-    "for now",
-    "revisit later",
-    "revisit this",
-    "come back to this",
-    "should be replaced",
-    "should be updated",
-    "should be revisited",
-    "will need to be",
-    // Quality shortcuts
-    "good enough",
-    "acceptable solution",
-    "simple enough",
-    "simple approach",
-    "basic implementation",
-    "simplified version",
-    "quick and dirty",
-    "not ideal",
-    // Version hedging
-    "first version",
-    "initial version",
-    // Placeholder/mock
-    "placeholder",
-    "hardcoded",
-    "hard-coded",
-    "workaround",
-    "temporary fix",
-    "temporary solution",
-    "temporary",
+/// Not-implemented markers in code added by an edit, with their
+/// complete-solution hints. Matched case-insensitively per added line; each
+/// entry lists the substrings that must all appear.
+const NOT_IMPLEMENTED_MARKERS: &[(&str, &[&str], &str)] = &[
+    ("todo!()", &["todo!("], "implement the branch, or return an error the caller can handle"),
+    ("unimplemented!()", &["unimplemented!("], "implement the branch, or return an error the caller can handle"),
+    ("unreachable!()", &["unreachable!("], "prove the branch is unreachable in the types, or handle it"),
+    (
+        "throw new Error(\"not implemented\")",
+        &["throw new error(", "not implemented"],
+        "implement the function, or remove it until it is needed",
+    ),
+    (
+        "raise NotImplementedError",
+        &["raise notimplementederror"],
+        "implement the method, or mark the class abstract if subclasses must provide it",
+    ),
+];
+
+/// Claims that a change fixes the cause rather than the symptom, matched
+/// case-insensitively against assistant text.
+const ROOT_CAUSE_CLAIMS: &[&str] = &[
+    "root cause",
+    "underlying issue",
+    "underlying problem",
+    "underlying bug",
+    "source of the problem",
 ];
 
-/// Code markers matched case-sensitively.
-const CODE_MARKERS: &[&str] = &["TODO", "FIXME", "HACK", "XXX"];
+/// Claims that an edit matches the surrounding code, matched
+/// case-insensitively against assistant text.
+const STYLE_CLAIMS: &[&str] = &[
+    "existing pattern",
+    "existing style",
+    "existing convention",
+    "same pattern as",
+    "same style as",
+    "consistent with the existing",
+    "consistent with the surrounding",
+    "matching the surrounding",
+];
+
+/// Optional `.claude/mediocrity-detector.toml`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Config {
+    /// "Learn more" link per pattern or code marker, appended to its finding.
+    doc_urls: DocUrls,
+    /// Only written files matching one of these are scanned (all when empty).
+    include_globs: Vec<String>,
+    /// Written files matching one of these are never scanned.
+    exclude_globs: Vec<String>,
+    /// Sessions whose transcript matches one of these are not checked at all.
+    ignore_transcript_globs: Vec<String>,
+    /// Local-time windows (`"18:00-09:00"`) in which findings warn instead of
+    /// blocking.
+    quiet_hours: Vec<quiet::Window>,
+    /// Require a completion checklist before stopping.
+    strict_turn: StrictTurn,
+    /// Findings listed per stop, most severe first ([`DEFAULT_MAX_FINDINGS`]
+    /// when unset, 0 for all).
+    max_findings: Option<usize>,
+    /// Skip findings already reported at an earlier stop of the session.
+    only_new_findings: bool,
+}
+
+/// `[strict_turn]`: the checklist a turn must contain when enabled.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct StrictTurn {
+    enabled: bool,
+    /// Line that starts the checklist, matched case-insensitively.
+    marker: String,
+    /// Entries the checklist must cover, each matched case-insensitively
+    /// against the list items after the marker.
+    items: Vec<String>,
+}
+
+impl Default for StrictTurn {
+    fn default() -> Self {
+        StrictTurn {
+            enabled: false,
+            marker: "Completion checklist".to_string(),
+            items: vec!["tests".to_string(), "lints".to_string(), "edge cases".to_string()],
+        }
+    }
+}
+
+impl Config {
+    fn scans(&self, file_path: &str) -> bool {
+        config::path_in_scope(file_path, &self.include_globs, &self.exclude_globs)
+    }
+
+    fn ignores_transcript(&self, transcript_path: &str) -> bool {
+        self.ignore_transcript_globs.iter().any(|g| config::glob_match(g, transcript_path))
+    }
+}
 
 fn main() {
+    if doctor::requested() {
+        let mut d = Doctor::new("mediocrity-detector");
+        let _: Config = d.config();
+        d.count("patterns", PATTERNS.len());
+        d.count("code markers", CODE_MARKERS.len() + NOT_IMPLEMENTED_MARKERS.len());
+        d.exit();
+    }
+    pattern::run_test_if_requested("mediocrity-detector", &pattern_groups());
+
     let mut input = String::new();
-    if io::stdin().read_to_string(&mut input).is_err() {
+    if io::stdin().read_to_string(&mut input).is_err() || !config::enabled_here() {
         process::exit(0);
     }
+    dead_letter::guard("mediocrity-detector", &input, || run(&input));
+}
 
-    let data: Value = match serde_json::from_str(&input) {
+fn run(input: &str) {
+    let data: Value = match serde_json::from_str(input) {
         Ok(v) => v,
         Err(_) => process::exit(0),
     };
@@ -65,108 +187,330 @@ fn main() {
         Some(p) => p,
         None => process::exit(0),
     };
+    let cfg: Config = config::load("mediocrity-detector");
+    if cfg.ignores_transcript(transcript_path) {
+        process::exit(0);
+    }
 
-    let transcript = match std::fs::read_to_string(transcript_path) {
-        Ok(t) => t,
-        Err(_) => process::exit(0),
+    let Some(turn) = Turn::load(transcript_path) else {
+        process::exit(0);
     };
 
-    let lines: Vec<&str> = transcript.lines().collect();
-    let turn_start = find_turn_start(&lines);
+    let session_id = data["session_id"].as_str().unwrap_or("unknown");
+    let reported_path = state::session_path("mediocrity-detector", session_id, "reported.json");
+    let reported = RefCell::new(Reported::load(&reported_path));
 
-    let mut findings: Vec<String> = Vec::new();
-    let mut seen = HashSet::new();
+    let mut checks = StopChecks::new();
+    checks.register(
+        "mediocrity-detector",
+        |turn| {
+            let findings = review(turn, &cfg);
+            if cfg.only_new_findings {
+                reported.borrow_mut().only_new(findings)
+            } else {
+                findings
+            }
+        },
+        build_reason,
+    );
+    let formatter = detect_formatter(&project_dir());
+    checks.register(
+        "unformatted-commit",
+        move |turn| check_unformatted_commit(turn, formatter).into_iter().collect(),
+        list_reason,
+    );
+    if cfg.strict_turn.enabled {
+        checks.register(
+            "strict-turn",
+            |turn| check_checklist(turn, &cfg.strict_turn).into_iter().collect(),
+            list_reason,
+        );
+    }
 
-    for line in &lines[turn_start..] {
-        let entry: Value = match serde_json::from_str(line) {
-            Ok(v) => v,
-            Err(_) => continue,
-        };
+    if session_log::enabled() {
+        checks.report_session(session_log::path(session_id));
+    }
+    let mut decision = Decision::new("mediocrity-detector", Event::Stop)
+        .with_session(session_id)
+        .with_quiet(quiet::is_quiet(&cfg.quiet_hours))
+        .with_max_findings(cfg.max_findings.unwrap_or(DEFAULT_MAX_FINDINGS));
+    let report = checks.run(&turn, &mut decision);
+    if cfg.only_new_findings {
+        reported.borrow().save(&reported_path);
+    }
 
-        if entry["type"].as_str() != Some("assistant") {
-            continue;
+    false_positive::report_if_requested("mediocrity-detector", input, decision.findings());
+    report
+        .finish(decision.emit(|findings| report.reason(decision.findings(), findings)))
+        .exit();
+}
+
+/// Findings reported at earlier stops of the session, as `rule: message`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Reported(BTreeSet<String>);
+
+impl Reported {
+    fn load(path: &Path) -> Reported {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, path: &Path) {
+        if let Ok(s) = serde_json::to_string(self) {
+            let _ = fs::write(path, s);
         }
+    }
 
-        let content = match entry["message"]["content"].as_array() {
-            Some(c) => c,
-            None => continue,
-        };
+    /// The findings not reported before, which count as reported from now on.
+    /// The same phrase in a different sentence is a new finding.
+    fn only_new(&mut self, findings: Vec<Finding>) -> Vec<Finding> {
+        findings
+            .into_iter()
+            .filter(|f| self.0.insert(format!("{}: {}", f.rule, f.message)))
+            .collect()
+    }
+}
 
-        for block in content {
-            let block_type = block["type"].as_str().unwrap_or("");
+/// Every mediocrity check on the turn, with configured doc links attached.
+fn review(turn: &Turn, cfg: &Config) -> Vec<Finding> {
+    let mut findings: Vec<Finding> = Vec::new();
+    let mut seen = HashSet::new();
+    let mut assistant_text = String::new();
+    let mut writes: Vec<WriteOp> = Vec::new();
 
-            match block_type {
-                "text" => {
-                    if let Some(text) = block["text"].as_str() {
-                        scan_text(text, &mut findings, &mut seen);
-                    }
-                }
-                "tool_use" => {
-                    let input = &block["input"];
-                    // Write tool: content field
-                    if let Some(t) = input["content"].as_str() {
-                        scan_text(t, &mut findings, &mut seen);
-                    }
-                    // Edit tool: new_string field
-                    if let Some(t) = input["new_string"].as_str() {
-                        scan_text(t, &mut findings, &mut seen);
-                    }
+    for block in &turn.blocks {
+        match block["type"].as_str().unwrap_or("") {
+            "text" => {
+                if let Some(text) = block["text"].as_str() {
+                    scan_text(text, &mut findings, &mut seen);
+                    assistant_text.push_str(text);
+                    assistant_text.push('\n');
                 }
-                _ => {}
             }
+            "tool_use" => scan_tool_use(
+                block["name"].as_str().unwrap_or(""),
+                &block["input"],
+                cfg,
+                &mut findings,
+                &mut seen,
+                &mut writes,
+            ),
+            _ => {}
         }
     }
 
-    if findings.is_empty() {
-        process::exit(0);
+    findings.extend(check_not_implemented(&writes));
+    findings.extend(check_symptom_fix(&assistant_text, &writes));
+    findings.extend(check_style_mismatch(&assistant_text, &writes));
+    findings.into_iter().map(|f| cfg.doc_urls.attach(f)).collect()
+}
+
+/// Scan what a tool call wrote, unless its file is outside the configured
+/// `include_globs`/`exclude_globs`, and collect it for the edit checks.
+fn scan_tool_use(
+    name: &str,
+    input: &Value,
+    cfg: &Config,
+    findings: &mut Vec<Finding>,
+    seen: &mut HashSet<String>,
+    writes: &mut Vec<WriteOp>,
+) {
+    if let Some(path) = input["file_path"].as_str() {
+        if !cfg.scans(path) {
+            return;
+        }
     }
+    let Some(w) = WriteOp::from_tool_input(name, input) else {
+        return;
+    };
+    scan_text(&EditOp::from(&w).added_text, findings, seen);
+    writes.push(w);
+}
+
+// ---------------------------------------------------------------------------
+// Reason
+// ---------------------------------------------------------------------------
+
+/// One category of shortcut with a worked example, for `MEDIOCRITY_TEACH=1`.
+struct Example {
+    category: &'static str,
+    /// Rules (patterns, markers, check names) in the category.
+    rules: &'static [&'static str],
+    shortcut: &'static str,
+    complete: &'static str,
+}
 
-    let list = findings
+/// Worked examples, one per category, shortcut first.
+const EXAMPLES: &[Example] = &[
+    Example {
+        category: "Deferred work",
+        rules: &[
+            "for now",
+            "revisit later",
+            "revisit this",
+            "come back to this",
+            "should be replaced",
+            "should be updated",
+            "should be revisited",
+            "will need to be",
+        ],
+        shortcut: "\"Skipped retries for now; we can revisit later.\"",
+        complete: "Retry with backoff (3 attempts) and a test for it, or tell the user retries are \
+                   missing and file an issue for them.",
+    },
+    Example {
+        category: "Quality shortcuts",
+        rules: &[
+            "good enough",
+            "acceptable solution",
+            "simple enough",
+            "simple approach",
+            "basic implementation",
+            "simplified version",
+            "quick and dirty",
+            "not ideal",
+        ],
+        shortcut: "\"A simple approach: split each CSV line on commas.\"",
+        complete: "Use a CSV parser, so quoted fields with commas and escaped quotes parse, with a \
+                   test for each.",
+    },
+    Example {
+        category: "Version hedging",
+        rules: &["first version", "initial version"],
+        shortcut: "\"This first version only exports JSON.\"",
+        complete: "Export every format the task asked for, or list the missing ones in the summary.",
+    },
+    Example {
+        category: "Placeholders and workarounds",
+        rules: &[
+            "placeholder",
+            "hardcoded",
+            "hard-coded",
+            "workaround",
+            "temporary fix",
+            "temporary solution",
+            "temporary",
+        ],
+        shortcut: "`const API_URL = \"http://localhost:3000\"; // hardcoded`",
+        complete: "`const API_URL = process.env.API_URL`, failing at startup with a clear error \
+                   when it is unset.",
+    },
+    Example {
+        category: "Code markers",
+        rules: &["TODO", "FIXME", "HACK", "XXX"],
+        shortcut: "`// TODO: handle errors` above `let cfg = load().unwrap();`",
+        complete: "`let cfg = load().context(\"reading config\")?;`: the error is handled and the \
+                   TODO has nothing left to say.",
+    },
+    Example {
+        category: "Not implemented",
+        rules: &[
+            "todo!()",
+            "unimplemented!()",
+            "unreachable!()",
+            "throw new Error(\"not implemented\")",
+            "raise NotImplementedError",
+        ],
+        shortcut: "`fn export(&self) -> Result<()> { todo!() }`",
+        complete: "Implement `export`, or leave it out of the API until something needs it.",
+    },
+    Example {
+        category: "Symptom fixes",
+        rules: &["symptom-fix"],
+        shortcut: "\"Fixed the root cause\" after wrapping the parse in try/catch and returning null.",
+        complete: "Find why the input is bad (the server sends an empty body on 204), handle that \
+                   case explicitly, and add a test for it.",
+    },
+    Example {
+        category: "Style mismatches",
+        rules: &["style-mismatch"],
+        shortcut: "\"Following the existing pattern\" while adding `fn loadUser()` among \
+                   `fn load_order()` and `fn load_cart()`.",
+        complete: "`fn load_user()`, indented like its neighbours, or say why the new code \
+                   departs from them.",
+    },
+];
+
+/// The worked examples for the categories of `findings`, in order of first
+/// appearance; `None` when no finding has a category.
+fn teach(findings: &[&Finding]) -> Option<String> {
+    let mut examples: Vec<&Example> = Vec::new();
+    for f in findings {
+        if let Some(e) = EXAMPLES.iter().find(|e| e.rules.contains(&f.rule.as_str())) {
+            if !examples.iter().any(|seen| seen.category == e.category) {
+                examples.push(e);
+            }
+        }
+    }
+    if examples.is_empty() {
+        return None;
+    }
+    let body = examples
         .iter()
-        .map(|f| format!("  - {}", f))
+        .map(|e| format!("{}\n   Shortcut: {}\n   Complete: {}", e.category, e.shortcut, e.complete))
         .collect::<Vec<_>>()
         .join("\n");
-    let reason = format!(
+    Some(format!("Worked examples:\n{}", body))
+}
+
+fn build_reason(findings: &[&Finding]) -> String {
+    reason(findings, env::var("MEDIOCRITY_TEACH").as_deref() == Ok("1"))
+}
+
+/// The mediocrity check's reason; with `teach`, blocking reasons end with the
+/// worked examples for their categories.
+fn reason(findings: &[&Finding], teach_mode: bool) -> String {
+    // Warnings alone don't block; they reach the user as a systemMessage.
+    if findings.iter().all(|f| f.severity == Severity::Warn) {
+        let list = findings
+            .iter()
+            .map(|f| format!("  - {}", f.render()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        return format!("mediocrity-detector:\n{}", list);
+    }
+    let plan = findings
+        .iter()
+        .enumerate()
+        .map(|(i, f)| match guidance(&f.rule) {
+            Some(hint) => format!("{}. {}\n   Complete solution: {}", i + 1, f.render(), hint),
+            None => format!("{}. {}", i + 1, f.render()),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let text = format!(
         "Shortcut/assumption language detected in this turn:\n{}\n\n\
          Before stopping, explicitly report to the user each shortcut or assumption. \
          For each: (1) what exactly you did and where, (2) why you chose this approach, \
-         (3) what a complete solution looks like. Be specific — the user needs to make \
-         an informed judgement call.\n\n\
+         (3) what a complete solution looks like, starting from the hint above. Be \
+         specific — the user needs to make an informed judgement call.\n\n\
          No explanation is needed if the flagged expression is itself a preventative \
          measure against the thing it names (e.g. code that detects a placeholder and \
          throws, a test asserting no TODO remains, a guard rejecting hardcoded values). \
          In that case, briefly note it and stop.",
-        list
+        plan
     );
-
-    println!("{}", json!({"decision": "block", "reason": reason}));
-    process::exit(0);
-}
-
-// ---------------------------------------------------------------------------
-// Transcript parsing
-// ---------------------------------------------------------------------------
-
-/// Walk backwards to find the last real user message (string content, not
-/// tool_result array). Everything after it belongs to the current turn.
-fn find_turn_start(lines: &[&str]) -> usize {
-    for i in (0..lines.len()).rev() {
-        // Quick pre-filter before JSON parsing
-        if !lines[i].contains("\"user\"") {
-            continue;
-        }
-
-        let entry: Value = match serde_json::from_str(lines[i]) {
-            Ok(v) => v,
-            Err(_) => continue,
-        };
-
-        if entry["type"].as_str() == Some("user") && entry["message"]["content"].is_string() {
-            return i;
-        }
+    match teach(findings).filter(|_| teach_mode) {
+        Some(examples) => format!("{}\n\n{}", text, examples),
+        None => text,
     }
+}
 
-    0
+/// Complete-solution hint for a pattern or code marker rule.
+fn guidance(rule: &str) -> Option<&'static str> {
+    PATTERNS
+        .iter()
+        .chain(CODE_MARKERS)
+        .find(|(pattern, _)| *pattern == rule)
+        .map(|&(_, hint)| hint)
+        .or_else(|| {
+            NOT_IMPLEMENTED_MARKERS
+                .iter()
+                .find(|(marker, _, _)| *marker == rule)
+                .map(|&(_, _, hint)| hint)
+        })
 }
 
 // ---------------------------------------------------------------------------
@@ -176,49 +520,36 @@ fn find_turn_start(lines: &[&str]) -> usize {
 /// Scan text for hedging patterns (case-insensitive) and code markers
 /// (case-sensitive). Deduplicates via `seen`. Each finding includes the
 /// surrounding phrase so the user can see the trigger in context.
-fn scan_text(text: &str, findings: &mut Vec<String>, seen: &mut HashSet<String>) {
-    for &pattern in PATTERNS {
+fn scan_text(text: &str, findings: &mut Vec<Finding>, seen: &mut HashSet<String>) {
+    for &(pattern, _) in PATTERNS {
         if seen.contains(pattern) {
             continue;
         }
         if let Some(pos) = find_case_insensitive(text, pattern) {
             let phrase = extract_phrase(text, pos, pattern.len());
-            findings.push(format!("\"{}\" → \"{}\"", pattern, phrase));
+            findings.push(Finding::block(
+                pattern,
+                format!("\"{}\" → \"{}\"", pattern, phrase),
+            ));
             seen.insert(pattern.to_string());
         }
     }
 
-    for &marker in CODE_MARKERS {
+    for &(marker, _) in CODE_MARKERS {
         if seen.contains(marker) {
             continue;
         }
         if let Some(pos) = text.find(marker) {
             let phrase = extract_phrase(text, pos, marker.len());
-            findings.push(format!("{} comment → \"{}\"", marker, phrase));
+            findings.push(Finding::block(
+                marker,
+                format!("{} comment → \"{}\"", marker, phrase),
+            ));
             seen.insert(marker.to_string());
         }
     }
 }
 
-/// Case-insensitive byte-level substring search (ASCII-folding only).
-/// Returns the byte offset of the first match in `haystack`.
-fn find_case_insensitive(haystack: &str, needle: &str) -> Option<usize> {
-    let h = haystack.as_bytes();
-    let n = needle.as_bytes();
-    if n.is_empty() || h.len() < n.len() {
-        return None;
-    }
-    'outer: for i in 0..=(h.len() - n.len()) {
-        for j in 0..n.len() {
-            if !h[i + j].eq_ignore_ascii_case(&n[j]) {
-                continue 'outer;
-            }
-        }
-        return Some(i);
-    }
-    None
-}
-
 /// Extract the surrounding sentence containing the match at `match_start`.
 /// Sentence boundaries are `.`, `!`, `?`, `\n`. A per-side cap of 120 bytes
 /// keeps runaway paragraphs short. Result is whitespace-trimmed and has
@@ -275,6 +606,454 @@ fn extract_phrase(text: &str, match_start: usize, match_len: usize) -> String {
     format!("{}{}{}", prefix, snippet, suffix)
 }
 
+// ---------------------------------------------------------------------------
+// Not-implemented markers
+// ---------------------------------------------------------------------------
+
+/// Block on not-implemented markers in lines the turn's edits added, one
+/// finding per marker. Test files and code after `#[cfg(test)]` within a
+/// hunk are skipped: a `todo!()` in a test stub is not a shipped shortcut.
+fn check_not_implemented(writes: &[WriteOp]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut seen = HashSet::new();
+    for write in writes.iter().filter(|w| !is_test_path(&w.file_path)) {
+        for line in added_non_test_lines(write) {
+            let lower = line.to_lowercase();
+            for &(marker, needles, _) in NOT_IMPLEMENTED_MARKERS {
+                if seen.contains(marker) || !needles.iter().all(|n| lower.contains(n)) {
+                    continue;
+                }
+                findings.push(Finding::block(
+                    marker,
+                    format!("`{}` added to `{}` → \"{}\"", marker, write.file_path, line),
+                ));
+                seen.insert(marker);
+            }
+        }
+    }
+    findings
+}
+
+/// Like [`added_lines`], but stops at a `#[cfg(test)]` within each hunk.
+fn added_non_test_lines(write: &WriteOp) -> Vec<&str> {
+    write
+        .hunks
+        .iter()
+        .flat_map(|(old, new)| {
+            let before: HashSet<&str> = old.lines().map(str::trim).collect();
+            new.lines()
+                .map(str::trim)
+                .take_while(|l| !l.starts_with("#[cfg(test)]"))
+                .filter(move |l| !l.is_empty() && !before.contains(l))
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Symptom-level fix claimed as root cause
+// ---------------------------------------------------------------------------
+
+/// Warn when the assistant claims a root-cause fix but every line the turn's
+/// edits added is symptom-level handling (or filler such as braces, comments,
+/// logging and bare returns). Turns without edits are not judged.
+fn check_symptom_fix(text: &str, writes: &[WriteOp]) -> Option<Finding> {
+    let (claim, pos) = ROOT_CAUSE_CLAIMS
+        .iter()
+        .find_map(|&c| find_case_insensitive(text, c).map(|pos| (c, pos)))?;
+
+    let added: Vec<&str> = writes.iter().flat_map(added_lines).collect();
+    let mut symptom = false;
+    for line in &added {
+        if is_symptom_line(line) {
+            symptom = true;
+        } else if !is_filler_line(line) {
+            return None;
+        }
+    }
+    if !symptom {
+        return None;
+    }
+
+    let mut files: Vec<&str> = writes.iter().map(|w| w.file_path.as_str()).collect();
+    files.dedup();
+    let files = files
+        .iter()
+        .map(|f| format!("`{}`", f))
+        .collect::<Vec<_>>()
+        .join(", ");
+    Some(Finding::warn(
+        "symptom-fix",
+        format!(
+            "\"{}\" → \"{}\", but this turn's edits to {} only add symptom-level handling \
+             (try/catch, null checks, retries). Check whether the cause itself was fixed.",
+            claim,
+            extract_phrase(text, pos, claim.len()),
+            files
+        ),
+    ))
+}
+
+/// Trimmed non-empty lines of `new` that don't appear (trimmed) in `old`, so
+/// code that was only re-indented into a new `try` block isn't counted.
+fn added_lines(write: &WriteOp) -> Vec<&str> {
+    write
+        .hunks
+        .iter()
+        .flat_map(|(old, new)| {
+            let before: HashSet<&str> = old.lines().map(str::trim).collect();
+            new.lines()
+                .map(str::trim)
+                .filter(move |l| !l.is_empty() && !before.contains(l))
+        })
+        .collect()
+}
+
+fn is_symptom_line(line: &str) -> bool {
+    const PREFIXES: &[&str] = &[
+        "try", "} catch", "catch", "except", "rescue", "finally", "} finally", "if err",
+    ];
+    const CONTAINS: &[&str] = &[
+        "== null", "!= null", "=== null", "!== null", "=== undefined", "!== undefined",
+        "is none", "is not none", "== nil", "!= nil", "?.", "?? ", "if (!", "if not ",
+        "if !", "retry", "retries", "attempts", "unwrap_or", "getordefault", "sleep(",
+        "settimeout(",
+    ];
+    let lower = line.to_lowercase();
+    PREFIXES.iter().any(|p| starts_with_word(&lower, p)) || CONTAINS.iter().any(|c| lower.contains(c))
+}
+
+/// Lines that neither fix nor hide anything on their own.
+fn is_filler_line(line: &str) -> bool {
+    const EXACT: &[&str] = &[
+        "{", "}", "};", "})", "});", ")", "else", "else:", "} else {", "pass", "return",
+        "return;", "return null;", "return none", "return nil", "return undefined;",
+        "return false;", "return [];", "return {};", "continue", "continue;", "break", "break;",
+    ];
+    const PREFIXES: &[&str] = &[
+        "//", "#", "/*", "*", "console.", "logger.", "log.", "log::", "eprintln!", "print(",
+    ];
+    let lower = line.to_lowercase();
+    EXACT.contains(&lower.as_str()) || PREFIXES.iter().any(|p| lower.starts_with(p))
+}
+
+/// `prefix` at the start of `line`, not followed by an identifier character
+/// (`try {` but not `tryParse`).
+fn starts_with_word(line: &str, prefix: &str) -> bool {
+    line.strip_prefix(prefix)
+        .is_some_and(|rest| !rest.starts_with(|c: char| c.is_alphanumeric() || c == '_'))
+}
+
+// ---------------------------------------------------------------------------
+// Style claimed to match but doesn't
+// ---------------------------------------------------------------------------
+
+/// Warn when the assistant claims to follow the existing style but an edit's
+/// added lines differ visibly from the text it replaced. Only edits are
+/// judged (a new file has nothing to compare with), and only when the
+/// replaced text shows one style consistently.
+fn check_style_mismatch(text: &str, writes: &[WriteOp]) -> Option<Finding> {
+    let (claim, pos) = STYLE_CLAIMS
+        .iter()
+        .find_map(|&c| find_case_insensitive(text, c).map(|pos| (c, pos)))?;
+
+    let mut problems: Vec<String> = Vec::new();
+    for w in writes {
+        for (old, new) in &w.hunks {
+            for problem in style_differences(old, new) {
+                let problem = format!("`{}`: {}", w.file_path, problem);
+                if !problems.contains(&problem) {
+                    problems.push(problem);
+                }
+            }
+        }
+    }
+    if problems.is_empty() {
+        return None;
+    }
+    Some(Finding::warn(
+        "style-mismatch",
+        format!(
+            "\"{}\" → \"{}\", but {}. Match the surrounding code or say why it differs.",
+            claim,
+            extract_phrase(text, pos, claim.len()),
+            problems.join("; ")
+        ),
+    ))
+}
+
+/// How the lines `new` adds differ from the style `old` shows: indentation
+/// (tabs vs spaces) and declared names (snake_case vs camelCase).
+fn style_differences(old: &str, new: &str) -> Vec<String> {
+    let before: HashSet<&str> = old.lines().collect();
+    let added: Vec<&str> = new.lines().filter(|l| !before.contains(l)).collect();
+    let mut out = Vec::new();
+
+    let indents = |lines: &[&str]| -> (usize, usize) {
+        lines.iter().fold((0, 0), |(tabs, spaces), l| match l.as_bytes().first() {
+            Some(b'\t') => (tabs + 1, spaces),
+            Some(b' ') => (tabs, spaces + 1),
+            _ => (tabs, spaces),
+        })
+    };
+    let old_lines: Vec<&str> = old.lines().collect();
+    match (indents(&old_lines), indents(&added)) {
+        ((0, s), (t, _)) if s > 0 && t > 0 => {
+            out.push(format!("{} added line(s) are tab-indented where the code uses spaces", t))
+        }
+        ((t, 0), (_, s)) if t > 0 && s > 0 => {
+            out.push(format!("{} added line(s) are space-indented where the code uses tabs", s))
+        }
+        _ => {}
+    }
+
+    let old_names = declared_names(old);
+    let styles: HashSet<NameStyle> = old_names.iter().filter_map(|n| name_style(n)).collect();
+    if let (true, Some(&style)) = (old_names.len() >= 2 && styles.len() == 1, styles.iter().next()) {
+        let odd: Vec<String> = added
+            .iter()
+            .flat_map(|l| declared_names(l))
+            .filter(|n| name_style(n).is_some_and(|s| s != style))
+            .map(|n| format!("`{}`", n))
+            .collect();
+        if !odd.is_empty() {
+            let (theirs, ours) = match style {
+                NameStyle::Snake => ("camelCase", "snake_case"),
+                NameStyle::Camel => ("snake_case", "camelCase"),
+            };
+            out.push(format!("{} {} among {} names", odd.join(", "), theirs, ours));
+        }
+    }
+    out
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum NameStyle {
+    Snake,
+    Camel,
+}
+
+/// `load_user` is snake_case and `loadUser` camelCase; single words and
+/// CONSTANTS are neither.
+fn name_style(name: &str) -> Option<NameStyle> {
+    let starts_lower = name.starts_with(|c: char| c.is_ascii_lowercase());
+    if !starts_lower {
+        None
+    } else if name.contains('_') && !name.contains(|c: char| c.is_ascii_uppercase()) {
+        Some(NameStyle::Snake)
+    } else if !name.contains('_') && name.contains(|c: char| c.is_ascii_uppercase()) {
+        Some(NameStyle::Camel)
+    } else {
+        None
+    }
+}
+
+/// Names introduced by declaration keywords (`fn`, `def`, `function`, `let`,
+/// `const`, `var`) in `text`.
+fn declared_names(text: &str) -> Vec<&str> {
+    const KEYWORDS: &[&str] = &["fn", "def", "function", "let", "const", "var"];
+    let words: Vec<&str> = text
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|w| !w.is_empty())
+        .collect();
+    words
+        .windows(2)
+        .filter(|pair| KEYWORDS.contains(&pair[0]))
+        .map(|pair| pair[1])
+        .filter(|&name| name != "mut")
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Commit without formatting
+// ---------------------------------------------------------------------------
+
+/// Formatter config files at the project root, with the formatter they imply.
+const FORMATTER_CONFIGS: &[(&str, &str)] = &[
+    ("rustfmt.toml", "rustfmt"),
+    (".rustfmt.toml", "rustfmt"),
+    (".prettierrc", "prettier"),
+    (".prettierrc.json", "prettier"),
+    (".prettierrc.yaml", "prettier"),
+    (".prettierrc.yml", "prettier"),
+    (".prettierrc.js", "prettier"),
+    (".prettierrc.cjs", "prettier"),
+    ("prettier.config.js", "prettier"),
+];
+
+/// Commands that run some formatter, matched against each simple command's
+/// words. Any of them counts, whichever formatter the project configures.
+const FORMAT_COMMANDS: &[&str] = &[
+    "cargo fmt",
+    "rustfmt",
+    "prettier",
+    "black",
+    "ruff format",
+    "npm run format",
+    "npm run fmt",
+    "pnpm format",
+    "yarn format",
+    "make fmt",
+    "make format",
+    "pre-commit run",
+];
+
+fn project_dir() -> PathBuf {
+    env::var_os("CLAUDE_PROJECT_DIR")
+        .map(PathBuf::from)
+        .or_else(|| env::current_dir().ok())
+        .unwrap_or_default()
+}
+
+/// The formatter the project is set up for, if any.
+fn detect_formatter(project: &Path) -> Option<&'static str> {
+    if let Some(&(_, formatter)) = FORMATTER_CONFIGS.iter().find(|(file, _)| project.join(file).is_file()) {
+        return Some(formatter);
+    }
+    let pyproject = fs::read_to_string(project.join("pyproject.toml")).ok()?;
+    pyproject
+        .lines()
+        .any(|l| l.trim() == "[tool.black]")
+        .then_some("black")
+}
+
+/// Warn when a `git commit` in this turn follows edits that no formatter run
+/// came after. Projects without a formatter config are not judged.
+fn check_unformatted_commit(turn: &Turn, formatter: Option<&str>) -> Option<Finding> {
+    let formatter = formatter?;
+    let mut unformatted: Vec<String> = Vec::new();
+    for block in &turn.blocks {
+        if block["type"].as_str() != Some("tool_use") {
+            continue;
+        }
+        let name = block["name"].as_str().unwrap_or("");
+        if let Some(w) = WriteOp::from_tool_input(name, &block["input"]) {
+            if !unformatted.contains(&w.file_path) {
+                unformatted.push(w.file_path);
+            }
+            continue;
+        }
+        let Some(command) = block["input"]["command"].as_str().filter(|_| name == "Bash") else {
+            continue;
+        };
+        for words in simple_commands(command) {
+            if runs_formatter(&words) {
+                unformatted.clear();
+            } else if is_git_commit(&words) && !unformatted.is_empty() {
+                let files = unformatted
+                    .iter()
+                    .map(|f| format!("`{}`", f))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                return Some(Finding::warn(
+                    "unformatted-commit",
+                    format!(
+                        "Committed edits to {} without running the formatter first; the \
+                         project is set up for {}. Run it and amend the commit, or the next \
+                         format run will mix formatting noise into an unrelated change.",
+                        files, formatter
+                    ),
+                ));
+            }
+        }
+    }
+    None
+}
+
+/// One of [`FORMAT_COMMANDS`] appears as consecutive words (`npx prettier
+/// --write .`, `cargo fmt --all`).
+fn runs_formatter(words: &[Word]) -> bool {
+    let names: Vec<&str> = words.iter().map(|w| program_name(&w.text)).collect();
+    FORMAT_COMMANDS.iter().any(|c| {
+        let c: Vec<&str> = c.split(' ').collect();
+        names.windows(c.len()).any(|w| w == c.as_slice())
+    })
+}
+
+/// `git commit`, also after global options (`git -C repo commit`).
+fn is_git_commit(words: &[Word]) -> bool {
+    let Some(args) = program_args(words, &["git"]) else {
+        return false;
+    };
+    let mut i = 0;
+    while let Some(w) = args.get(i) {
+        match w.text.as_str() {
+            "-C" | "-c" => i += 2,
+            t if t.starts_with('-') => i += 1,
+            t => return t == "commit",
+        }
+    }
+    false
+}
+
+// ---------------------------------------------------------------------------
+// Strict turn: completion checklist
+// ---------------------------------------------------------------------------
+
+/// Block unless some text block of the turn holds a checklist: the
+/// configured marker line, then list items (`- `, `* `, `- [x] `) covering
+/// every configured entry.
+fn check_checklist(turn: &Turn, strict: &StrictTurn) -> Option<Finding> {
+    let texts: Vec<&str> = turn.blocks.iter().filter_map(|b| b["text"].as_str()).collect();
+    let mut missing: Vec<&str> = strict.items.iter().map(String::as_str).collect();
+    let mut found = false;
+    for text in &texts {
+        let Some(items) = checklist_items(text, &strict.marker) else {
+            continue;
+        };
+        found = true;
+        missing.retain(|want| !items.iter().any(|i| find_case_insensitive(i, want).is_some()));
+        if missing.is_empty() {
+            return None;
+        }
+    }
+
+    let template = strict
+        .items
+        .iter()
+        .map(|i| format!("- {}: …", i))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let problem = if found {
+        format!("The completion checklist doesn't cover: {}.", missing.join(", "))
+    } else {
+        "Strict-turn mode is on and this turn has no completion checklist.".to_string()
+    };
+    Some(Finding::block(
+        "missing-checklist",
+        format!(
+            "{} Before stopping, end your reply with the checklist, stating what you \
+             actually did for each entry (or why it doesn't apply):\n\n{}\n{}",
+            problem, strict.marker, template
+        ),
+    ))
+}
+
+/// The list items after the first line of `text` that contains `marker`,
+/// up to the first line that is neither a list item nor blank.
+fn checklist_items<'t>(text: &'t str, marker: &str) -> Option<Vec<&'t str>> {
+    let mut lines = text.lines();
+    lines.find(|l| find_case_insensitive(l, marker).is_some())?;
+    let mut items = Vec::new();
+    for line in lines.map(str::trim) {
+        if line.is_empty() && items.is_empty() {
+            continue;
+        }
+        match line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+            Some(item) => items.push(item),
+            None => break,
+        }
+    }
+    Some(items)
+}
+
+fn list_reason(findings: &[&Finding]) -> String {
+    findings
+        .iter()
+        .map(|f| f.render())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -288,7 +1067,7 @@ mod tests {
         let mut findings = Vec::new();
         let mut seen = HashSet::new();
         scan_text("I used a simple implementation for now.", &mut findings, &mut seen);
-        assert!(findings.iter().any(|f| f.contains("for now")));
+        assert!(findings.iter().any(|f| f.message.contains("for now")));
     }
 
     #[test]
@@ -300,9 +1079,9 @@ mod tests {
             &mut findings,
             &mut seen,
         );
-        assert!(findings.iter().any(|f| f.contains("good enough")));
-        assert!(findings.iter().any(|f| f.contains("for now")));
-        assert!(findings.iter().any(|f| f.contains("revisit later")));
+        assert!(findings.iter().any(|f| f.message.contains("good enough")));
+        assert!(findings.iter().any(|f| f.message.contains("for now")));
+        assert!(findings.iter().any(|f| f.message.contains("revisit later")));
     }
 
     #[test]
@@ -310,7 +1089,7 @@ mod tests {
         let mut findings = Vec::new();
         let mut seen = HashSet::new();
         scan_text("// TODO: handle edge case", &mut findings, &mut seen);
-        assert!(findings.iter().any(|f| f.contains("TODO")));
+        assert!(findings.iter().any(|f| f.message.contains("TODO")));
     }
 
     #[test]
@@ -318,7 +1097,7 @@ mod tests {
         let mut findings = Vec::new();
         let mut seen = HashSet::new();
         scan_text("I updated the todo list component", &mut findings, &mut seen);
-        assert!(findings.iter().all(|f| !f.contains("TODO")));
+        assert!(findings.iter().all(|f| !f.message.contains("TODO")));
     }
 
     #[test]
@@ -327,7 +1106,7 @@ mod tests {
         let mut seen = HashSet::new();
         scan_text("for now this is fine", &mut findings, &mut seen);
         scan_text("I did this for now", &mut findings, &mut seen);
-        let count = findings.iter().filter(|f| f.contains("for now")).count();
+        let count = findings.iter().filter(|f| f.message.contains("for now")).count();
         assert_eq!(count, 1);
     }
 
@@ -348,7 +1127,7 @@ mod tests {
         let mut findings = Vec::new();
         let mut seen = HashSet::new();
         scan_text("This is a Basic Implementation.", &mut findings, &mut seen);
-        assert!(findings.iter().any(|f| f.contains("basic implementation")));
+        assert!(findings.iter().any(|f| f.message.contains("basic implementation")));
     }
 
     #[test]
@@ -360,7 +1139,7 @@ mod tests {
             &mut findings,
             &mut seen,
         );
-        assert!(findings.iter().any(|f| f.contains("temporary")));
+        assert!(findings.iter().any(|f| f.message.contains("temporary")));
     }
 
     #[test]
@@ -372,7 +1151,7 @@ mod tests {
             &mut findings,
             &mut seen,
         );
-        assert!(findings.iter().any(|f| f.contains("placeholder")));
+        assert!(findings.iter().any(|f| f.message.contains("placeholder")));
     }
 
     #[test]
@@ -384,7 +1163,7 @@ mod tests {
             &mut findings,
             &mut seen,
         );
-        assert!(findings.iter().any(|f| f.contains("workaround")));
+        assert!(findings.iter().any(|f| f.message.contains("workaround")));
     }
 
     #[test]
@@ -396,7 +1175,7 @@ mod tests {
             &mut findings,
             &mut seen,
         );
-        assert!(findings.iter().any(|f| f.contains("FIXME")));
+        assert!(findings.iter().any(|f| f.message.contains("FIXME")));
     }
 
     // -- Phrase extraction ----------------------------------------------------
@@ -413,12 +1192,12 @@ mod tests {
         // The "temporary" finding should include the full sentence as context.
         let temp = findings
             .iter()
-            .find(|f| f.starts_with("\"temporary\""))
+            .find(|f| f.rule == "temporary")
             .expect("temporary finding present");
         assert!(
-            temp.contains("temporary workaround for the race condition"),
+            temp.message.contains("temporary workaround for the race condition"),
             "expected surrounding phrase, got: {}",
-            temp
+            temp.message
         );
     }
 
@@ -463,27 +1242,503 @@ mod tests {
         assert!(phrase.contains("temporary"));
     }
 
-    // -- Transcript parsing ---------------------------------------------------
+    // -- Fix plan ---------------------------------------------------------------
+
+    #[test]
+    fn every_pattern_has_guidance() {
+        for &(pattern, hint) in PATTERNS.iter().chain(CODE_MARKERS) {
+            assert!(!hint.is_empty(), "no guidance for {}", pattern);
+        }
+        for &(marker, _, _) in NOT_IMPLEMENTED_MARKERS {
+            assert!(guidance(marker).is_some(), "no guidance for {}", marker);
+        }
+    }
+
+    #[test]
+    fn reason_pairs_findings_with_guidance() {
+        let mut findings = Vec::new();
+        let mut seen = HashSet::new();
+        scan_text("Added a placeholder. // TODO: wire it up", &mut findings, &mut seen);
+        let reason = build_reason(&findings.iter().collect::<Vec<_>>());
+        assert!(reason.contains(
+            "1. \"placeholder\" → \"Added a placeholder.\"\n   Complete solution: implement \
+             the real logic, or leave a tracked TODO with an owner"
+        ), "got: {}", reason);
+        assert!(reason.contains(
+            "2. TODO comment → \"// TODO: wire it up\"\n   Complete solution: do the work now, \
+             or link a tracked issue with an owner in the comment"
+        ), "got: {}", reason);
+    }
+
+    #[test]
+    fn guidance_for_hardcoded_points_to_configuration() {
+        assert!(guidance("hardcoded").unwrap().contains("configuration"));
+        assert_eq!(guidance("symptom-fix"), None);
+    }
+
+    // -- Not-implemented markers ------------------------------------------------
+
+    fn write(path: &str, content: &str) -> WriteOp {
+        WriteOp::from_tool_input("Write", &serde_json::json!({"file_path": path, "content": content}))
+            .unwrap()
+    }
+
+    #[test]
+    fn blocks_todo_macro_in_source() {
+        let w = write("src/lib.rs", "pub fn parse(s: &str) -> u32 {\n    todo!()\n}\n");
+        let f = check_not_implemented(&[w]);
+        assert_eq!(f.len(), 1);
+        assert_eq!(f[0].severity, Severity::Block);
+        assert_eq!(f[0].rule, "todo!()");
+        assert!(f[0].message.contains("`src/lib.rs`"), "got: {}", f[0].message);
+    }
+
+    #[test]
+    fn allows_todo_macro_in_tests() {
+        let w = write("tests/parse.rs", "#[test]\nfn parses() {\n    todo!()\n}\n");
+        assert!(check_not_implemented(&[w]).is_empty());
+        let w = write(
+            "src/lib.rs",
+            "pub fn f() {}\n\n#[cfg(test)]\nmod tests {\n    fn g() { todo!() }\n}\n",
+        );
+        assert!(check_not_implemented(&[w]).is_empty());
+    }
+
+    #[test]
+    fn blocks_js_and_python_markers() {
+        let js = write("src/api.ts", "export function sync() {\n  throw new Error('Not implemented');\n}");
+        let py = write("app/store.py", "def load(self):\n    raise NotImplementedError\n");
+        let rules: Vec<String> = check_not_implemented(&[js, py]).into_iter().map(|f| f.rule).collect();
+        assert_eq!(rules, vec!["throw new Error(\"not implemented\")", "raise NotImplementedError"]);
+    }
+
+    #[test]
+    fn ignores_marker_already_present_before_edit() {
+        let w = edit("    unimplemented!()\n", "    // parse later\n    unimplemented!()\n");
+        assert!(check_not_implemented(&[w]).is_empty());
+    }
+
+    // -- Symptom-level fix --------------------------------------------------------
+
+    fn edit(old: &str, new: &str) -> WriteOp {
+        WriteOp::from_tool_input(
+            "Edit",
+            &serde_json::json!({"file_path": "src/sync.js", "old_string": old, "new_string": new}),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn warns_on_root_cause_claim_with_symptom_edit() {
+        let w = edit(
+            "  return await fetchUser(id);",
+            "  try {\n    return await fetchUser(id);\n  } catch (e) {\n    console.error(e);\n    return null;\n  }",
+        );
+        let f = check_symptom_fix("This addresses the root cause of the crash.", &[w]).unwrap();
+        assert_eq!(f.severity, Severity::Warn);
+        assert!(f.message.contains("root cause"), "got: {}", f.message);
+        assert!(f.message.contains("src/sync.js"));
+    }
+
+    #[test]
+    fn warns_on_null_guard_claimed_as_underlying_fix() {
+        let w = edit(
+            "return user.name;",
+            "if (user == null) {\n  return null;\n}\nreturn user.name;",
+        );
+        assert!(check_symptom_fix("Fixed the underlying issue.", &[w]).is_some());
+    }
+
+    #[test]
+    fn allows_root_cause_claim_with_substantive_edit() {
+        let w = edit(
+            "const id = req.params.userId;",
+            "const id = req.params.id;",
+        );
+        assert!(check_symptom_fix("This addresses the root cause of the crash.", &[w]).is_none());
+    }
+
+    #[test]
+    fn allows_symptom_edit_without_claim() {
+        let w = edit("foo();", "try {\n  foo();\n} catch (e) {}");
+        assert!(check_symptom_fix("Wrapped the call in a try/catch.", &[w]).is_none());
+    }
+
+    #[test]
+    fn ignores_claim_without_edits() {
+        assert!(check_symptom_fix("I found the root cause.", &[]).is_none());
+    }
+
+    #[test]
+    fn warn_only_reason_is_addressed_to_user() {
+        let f = Finding::warn("symptom-fix", "msg");
+        assert_eq!(build_reason(&[&f]), "mediocrity-detector:\n  - msg");
+    }
+
+    // -- Style mismatch -----------------------------------------------------------
+
+    fn edit_at(path: &str, old: &str, new: &str) -> WriteOp {
+        WriteOp::from_tool_input(
+            "Edit",
+            &serde_json::json!({"file_path": path, "old_string": old, "new_string": new}),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn warns_on_tab_indented_additions_to_a_space_indented_file() {
+        let w = edit_at(
+            "src/cart.py",
+            "def total(items):\n    return sum(items)",
+            "def total(items):\n    return sum(items)\n\ndef count(items):\n\treturn len(items)",
+        );
+        let f = check_style_mismatch("Added `count`, following the existing pattern.", &[w]).unwrap();
+        assert_eq!(f.severity, Severity::Warn);
+        assert!(f.message.contains("`src/cart.py`: 1 added line(s) are tab-indented"), "got: {}", f.message);
+    }
+
+    #[test]
+    fn warns_on_camel_case_names_among_snake_case_ones() {
+        let w = edit_at(
+            "src/store.rs",
+            "fn load_order() {}\nfn load_cart() {}",
+            "fn load_order() {}\nfn load_cart() {}\nfn loadUser() {}",
+        );
+        let f = check_style_mismatch("Same pattern as the other loaders.", &[w]).unwrap();
+        assert!(f.message.contains("`loadUser` camelCase among snake_case names"), "got: {}", f.message);
+    }
+
+    #[test]
+    fn allows_consistent_additions_and_unclaimed_changes() {
+        let consistent = edit_at(
+            "src/cart.py",
+            "def total(items):\n    return sum(items)",
+            "def total(items):\n    return sum(items)\n\ndef item_count(items):\n    return len(items)",
+        );
+        assert!(check_style_mismatch("Following the existing pattern.", &[consistent]).is_none());
+
+        let tabbed = edit_at("src/a.py", "x = 1\n    y = 2", "x = 1\n    y = 2\n\tz = 3");
+        assert!(check_style_mismatch("Added z.", std::slice::from_ref(&tabbed)).is_none());
+        // A new file has no surrounding code to compare with.
+        let new_file = write("src/b.py", "def f():\n\treturn 1");
+        assert!(check_style_mismatch("Following the existing style.", &[new_file]).is_none());
+        // One name isn't enough to call a convention.
+        let w = edit_at("src/c.js", "const user_id = 1;", "const user_id = 1;\nconst orderId = 2;");
+        assert!(check_style_mismatch("Following the existing style.", &[w]).is_none());
+    }
+
+    // -- Teach mode -------------------------------------------------------------
+
+    #[test]
+    fn teach_mode_adds_one_example_per_triggered_category() {
+        let mut findings = Vec::new();
+        scan_text(
+            "Good enough for now, and simple enough. // TODO: errors",
+            &mut findings,
+            &mut HashSet::new(),
+        );
+        let findings: Vec<&Finding> = findings.iter().collect();
+
+        let taught = reason(&findings, true);
+        let examples = taught.split_once("\n\nWorked examples:\n").unwrap().1;
+        assert!(examples.starts_with("Deferred work\n   Shortcut: \"Skipped retries"), "got: {}", examples);
+        assert_eq!(examples.matches("Quality shortcuts").count(), 1, "got: {}", examples);
+        assert!(examples.contains("Code markers\n   Shortcut: `// TODO"), "got: {}", examples);
+        assert!(!examples.contains("Version hedging"));
+
+        assert!(!reason(&findings, false).contains("Worked examples"));
+        let f = Finding::block("missing-checklist", "no checklist");
+        assert!(!reason(&[&f], true).contains("Worked examples"));
+    }
+
+    #[test]
+    fn every_rule_has_exactly_one_example_category() {
+        let rules = PATTERNS
+            .iter()
+            .chain(CODE_MARKERS)
+            .map(|&(rule, _)| rule)
+            .chain(NOT_IMPLEMENTED_MARKERS.iter().map(|&(rule, _, _)| rule))
+            .chain(["symptom-fix", "style-mismatch"]);
+        for rule in rules {
+            let n = EXAMPLES.iter().filter(|e| e.rules.contains(&rule)).count();
+            assert_eq!(n, 1, "{} is in {} categories", rule, n);
+        }
+    }
+
+    // -- Doc URLs ---------------------------------------------------------------
+
+    #[test]
+    fn reason_includes_configured_doc_url() {
+        let cfg: Config = toml::from_str(
+            "[doc_urls]\n\"for now\" = \"https://wiki.example.org/deferred-work\"\n",
+        )
+        .unwrap();
+        let mut findings = Vec::new();
+        let mut seen = HashSet::new();
+        scan_text("I left a stub for now. // TODO: wire it", &mut findings, &mut seen);
+        let findings: Vec<Finding> = findings.into_iter().map(|f| cfg.doc_urls.attach(f)).collect();
+        let reason = build_reason(&findings.iter().collect::<Vec<_>>());
+        assert!(reason.contains("(learn more: https://wiki.example.org/deferred-work)"));
+        // Only the configured pattern gets a link.
+        assert_eq!(reason.matches("learn more").count(), 1);
+    }
+
+    // -- Focus files ------------------------------------------------------------
+
+    fn scan_write(cfg: &Config, path: &str, content: &str) -> (Vec<Finding>, Vec<WriteOp>) {
+        let mut findings = Vec::new();
+        let mut writes = Vec::new();
+        let input = serde_json::json!({"file_path": path, "content": content});
+        scan_tool_use("Write", &input, cfg, &mut findings, &mut HashSet::new(), &mut writes);
+        (findings, writes)
+    }
+
+    #[test]
+    fn excluded_path_is_not_scanned() {
+        let cfg: Config =
+            toml::from_str("include_globs = [\"src/**\"]\nexclude_globs = [\"src/vendor/**\"]").unwrap();
+
+        let (findings, writes) = scan_write(&cfg, "/repo/src/lib.rs", "// TODO: wire it up\n");
+        assert!(findings.iter().any(|f| f.rule == "TODO"));
+        assert_eq!(writes.len(), 1);
+
+        for path in ["/repo/docs/plan.md", "/repo/src/vendor/x.rs"] {
+            let (findings, writes) = scan_write(&cfg, path, "// TODO: wire it up\n");
+            assert!(findings.is_empty(), "{} was scanned", path);
+            assert!(writes.is_empty(), "{} was collected", path);
+        }
+    }
+
+    #[test]
+    fn ignores_matching_transcripts_only() {
+        let cfg: Config =
+            toml::from_str("ignore_transcript_globs = [\"**/scratch-*/*.jsonl\"]").unwrap();
+        assert!(cfg.ignores_transcript("/home/u/.claude/projects/scratch-notes/abc.jsonl"));
+        assert!(!cfg.ignores_transcript("/home/u/.claude/projects/-repo-app/abc.jsonl"));
+        assert!(!Config::default().ignores_transcript("/home/u/.claude/projects/scratch-notes/abc.jsonl"));
+    }
 
     #[test]
-    fn finds_turn_start_skips_tool_results() {
-        let lines = vec![
-            r#"{"type":"user","message":{"role":"user","content":"Fix the bug"}}"#,
-            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"On it."}]}}"#,
-            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"123"}]}}"#,
-            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Done."}]}}"#,
-        ];
-        assert_eq!(find_turn_start(&lines), 0);
+    fn multi_edit_new_strings_are_scanned() {
+        let input = serde_json::json!({"file_path": "src/lib.rs", "edits": [
+            {"old_string": "a", "new_string": "b"},
+            {"old_string": "c", "new_string": "// FIXME: wire the retry"}
+        ]});
+        let mut findings = Vec::new();
+        let mut writes = Vec::new();
+        scan_tool_use("MultiEdit", &input, &Config::default(), &mut findings, &mut HashSet::new(), &mut writes);
+        assert!(findings.iter().any(|f| f.rule == "FIXME"));
+        assert_eq!(writes.len(), 1);
+    }
+
+    #[test]
+    fn no_globs_scans_everything() {
+        let (findings, _) = scan_write(&Config::default(), "/repo/docs/plan.md", "placeholder");
+        assert!(!findings.is_empty());
     }
 
+    // -- Turn review ------------------------------------------------------------
+
     #[test]
-    fn finds_latest_user_message() {
-        let lines = vec![
-            r#"{"type":"user","message":{"role":"user","content":"First task"}}"#,
-            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Done."}]}}"#,
-            r#"{"type":"user","message":{"role":"user","content":"Second task"}}"#,
-            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Working."}]}}"#,
-        ];
-        assert_eq!(find_turn_start(&lines), 2);
+    fn review_scans_text_and_writes_of_the_turn() {
+        let turn = Turn {
+            blocks: vec![
+                serde_json::json!({"type": "text", "text": "Stubbed the parser for now."}),
+                serde_json::json!({"type": "tool_use", "name": "Write", "input": {
+                    "file_path": "src/parse.rs", "content": "fn parse() {\n    todo!()\n}\n"
+                }}),
+            ],
+        };
+        let rules: Vec<String> = review(&turn, &Config::default()).into_iter().map(|f| f.rule).collect();
+        assert_eq!(rules, vec!["for now", "todo!()"]);
+    }
+
+    // -- Commit without formatting ----------------------------------------------
+
+    fn tool_turn(calls: &[(&str, serde_json::Value)]) -> Turn {
+        Turn {
+            blocks: calls
+                .iter()
+                .map(|(name, input)| serde_json::json!({"type": "tool_use", "name": name, "input": input}))
+                .collect(),
+        }
+    }
+
+    fn bash(command: &str) -> (&'static str, serde_json::Value) {
+        ("Bash", serde_json::json!({"command": command}))
+    }
+
+    fn edit_call(path: &str) -> (&'static str, serde_json::Value) {
+        ("Edit", serde_json::json!({"file_path": path, "old_string": "a", "new_string": "b"}))
+    }
+
+    #[test]
+    fn warns_on_commit_after_unformatted_edits() {
+        let turn = tool_turn(&[edit_call("src/lib.rs"), bash("cargo test && git commit -am 'Fix parser'")]);
+        let f = check_unformatted_commit(&turn, Some("rustfmt")).unwrap();
+        assert_eq!(f.severity, Severity::Warn);
+        assert!(f.message.contains("`src/lib.rs`"), "got: {}", f.message);
+        assert!(f.message.contains("rustfmt"), "got: {}", f.message);
+
+        // Formatting before a later edit doesn't cover that edit.
+        let turn = tool_turn(&[
+            edit_call("src/a.rs"),
+            bash("cargo fmt"),
+            edit_call("src/b.rs"),
+            bash("git -C . commit -m x"),
+        ]);
+        let f = check_unformatted_commit(&turn, Some("rustfmt")).unwrap();
+        assert!(f.message.contains("`src/b.rs`") && !f.message.contains("`src/a.rs`"), "got: {}", f.message);
+    }
+
+    #[test]
+    fn formatted_or_unconfigured_commits_are_clean() {
+        let turn = tool_turn(&[edit_call("src/app.ts"), bash("npx prettier --write . && git commit -m x")]);
+        assert!(check_unformatted_commit(&turn, Some("prettier")).is_none());
+
+        let turn = tool_turn(&[edit_call("src/lib.rs"), bash("git commit -m x")]);
+        assert!(check_unformatted_commit(&turn, None).is_none());
+
+        // No edits this turn, or edits but no commit.
+        assert!(check_unformatted_commit(&tool_turn(&[bash("git commit -m x")]), Some("black")).is_none());
+        assert!(check_unformatted_commit(&tool_turn(&[edit_call("a.py"), bash("git status")]), Some("black")).is_none());
+    }
+
+    #[test]
+    fn detects_formatter_configs() {
+        let dir = env::temp_dir().join(format!("mediocrity-formatter-{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        assert_eq!(detect_formatter(&dir), None);
+        fs::write(dir.join("pyproject.toml"), "[project]\nname = \"x\"\n").unwrap();
+        assert_eq!(detect_formatter(&dir), None);
+        fs::write(dir.join("pyproject.toml"), "[tool.black]\nline-length = 100\n").unwrap();
+        assert_eq!(detect_formatter(&dir), Some("black"));
+        fs::write(dir.join(".prettierrc"), "{}").unwrap();
+        assert_eq!(detect_formatter(&dir), Some("prettier"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    // -- Strict turn ------------------------------------------------------------
+
+    fn text_turn(texts: &[&str]) -> Turn {
+        Turn {
+            blocks: texts
+                .iter()
+                .map(|t| serde_json::json!({"type": "text", "text": t}))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn strict_turn_allows_turn_with_checklist() {
+        let turn = text_turn(&[
+            "Fixed the parser.",
+            "## Completion checklist\n\n\
+             - [x] Tests: `cargo test` passes\n\
+             - [x] Lints: clippy clean\n\
+             - Edge cases: empty input, trailing comma",
+        ]);
+        assert!(check_checklist(&turn, &StrictTurn::default()).is_none());
+    }
+
+    #[test]
+    fn strict_turn_blocks_turn_without_checklist() {
+        let f = check_checklist(&text_turn(&["Fixed the parser. All done."]), &StrictTurn::default()).unwrap();
+        assert_eq!(f.severity, Severity::Block);
+        assert!(f.message.contains("no completion checklist"), "got: {}", f.message);
+        assert!(f.message.contains("Completion checklist\n- tests: …\n- lints: …"), "got: {}", f.message);
+
+        // A checklist that skips an entry, or lists it outside the block.
+        let turn = text_turn(&["Completion checklist:\n- tests: pass\n\nEdge cases and lints were fine."]);
+        let f = check_checklist(&turn, &StrictTurn::default()).unwrap();
+        assert!(f.message.contains("doesn't cover: lints, edge cases."), "got: {}", f.message);
+    }
+
+    #[test]
+    fn strict_turn_marker_and_items_are_configurable() {
+        let cfg: Config = toml::from_str(
+            "[strict_turn]\nenabled = true\nmarker = \"DONE:\"\nitems = [\"migration\"]",
+        )
+        .unwrap();
+        assert!(cfg.strict_turn.enabled);
+        let turn = text_turn(&["DONE:\n* migration: ran on staging"]);
+        assert!(check_checklist(&turn, &cfg.strict_turn).is_none());
+        assert!(check_checklist(&text_turn(&["## Completion checklist\n- migration"]), &cfg.strict_turn).is_some());
+        assert!(!Config::default().strict_turn.enabled);
+    }
+
+    // -- Only new findings ------------------------------------------------------
+
+    #[test]
+    fn only_new_findings_skips_phrases_reported_at_an_earlier_stop() {
+        let cfg: Config = toml::from_str("only_new_findings = true").unwrap();
+        assert!(cfg.only_new_findings && !Config::default().only_new_findings);
+
+        let path = std::env::temp_dir().join("mediocrity-detector-reported-test.json");
+        let _ = fs::remove_file(&path);
+        let lingering = "Good enough for now.";
+
+        let mut reported = Reported::load(&path);
+        let first = reported.only_new(review(&text_turn(&[lingering]), &cfg));
+        assert_eq!(rules(&first), ["for now", "good enough"]);
+        reported.save(&path);
+
+        let mut reported = Reported::load(&path);
+        let second = reported.only_new(review(&text_turn(&[lingering, "Left a placeholder."]), &cfg));
+        assert_eq!(rules(&second), ["placeholder"]);
+        let _ = fs::remove_file(&path);
+    }
+
+    fn rules(findings: &[Finding]) -> Vec<&str> {
+        findings.iter().map(|f| f.rule.as_str()).collect()
+    }
+
+    // -- Max findings -----------------------------------------------------------
+
+    fn stop_reason(cfg: &Config, turn: &Turn) -> String {
+        let mut checks = StopChecks::new();
+        checks.register("mediocrity-detector", |turn| review(turn, cfg), build_reason);
+        let mut decision = Decision::new("mediocrity-detector", Event::Stop)
+            .with_max_findings(cfg.max_findings.unwrap_or(DEFAULT_MAX_FINDINGS));
+        let report = checks.run(turn, &mut decision);
+        let out = decision.emit(|findings| report.reason(decision.findings(), findings));
+        let v: Value = serde_json::from_str(out.stdout.trim()).unwrap();
+        v["reason"].as_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn findings_are_capped_at_max_findings_with_a_more_note() {
+        let phrases: Vec<&str> = PATTERNS.iter().map(|&(p, _)| p).take(12).collect();
+        let turn = text_turn(&[&format!("Notes: {}.", phrases.join(", "))]);
+
+        let reason = stop_reason(&Config::default(), &turn);
+        assert!(reason.contains("10. "), "got: {}", reason);
+        assert!(!reason.contains("11. "), "got: {}", reason);
+        assert!(reason.ends_with("\n+2 more (raise `max_findings` to list them)"), "got: {}", reason);
+
+        let cfg: Config = toml::from_str("max_findings = 0").unwrap();
+        let reason = stop_reason(&cfg, &turn);
+        assert!(reason.contains("12. ") && !reason.contains("more (raise"), "got: {}", reason);
+    }
+
+    // -- Snapshots ------------------------------------------------------------
+    //
+    // The full text Claude sees is part of the hook's behavior, so wording
+    // changes should show up in review. After an intentional change, run
+    // `cargo insta review` (or `INSTA_UPDATE=always cargo test`) from this
+    // crate and commit the updated files under `src/snapshots/`.
+
+    #[test]
+    fn snapshot_default_reason() {
+        let mut findings = Vec::new();
+        let mut seen = HashSet::new();
+        scan_text(
+            "This is good enough for now. // TODO: handle the error path",
+            &mut findings,
+            &mut seen,
+        );
+        let refs: Vec<&Finding> = findings.iter().collect();
+        insta::assert_snapshot!(build_reason(&refs));
     }
 }
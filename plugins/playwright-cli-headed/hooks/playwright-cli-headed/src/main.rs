@@ -17,17 +17,27 @@
 //! Claude can bypass rewriting (but not the tip) by adding `[no-rewrite]` to
 //! the tool description.
 
+use hook_common::config;
+use hook_common::dead_letter;
+use hook_common::doctor::{self, Doctor};
 use serde_json::{json, Map, Value};
 use std::io::{self, Read};
 use std::process;
 
 fn main() {
+    if doctor::requested() {
+        Doctor::new("playwright-cli-headed").exit();
+    }
+
     let mut input = String::new();
-    if io::stdin().read_to_string(&mut input).is_err() {
+    if io::stdin().read_to_string(&mut input).is_err() || !config::enabled_here() {
         process::exit(0);
     }
+    dead_letter::guard("playwright-cli-headed", &input, || run(&input));
+}
 
-    let data: Value = match serde_json::from_str(&input) {
+fn run(input: &str) {
+    let data: Value = match serde_json::from_str(input) {
         Ok(v) => v,
         Err(_) => process::exit(0),
     };
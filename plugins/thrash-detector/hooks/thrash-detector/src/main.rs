@@ -0,0 +1,639 @@
+//! PostToolUse hook that detects Claude undoing its own changes.
+//!
+//! Strategy: remember every Write/Edit/MultiEdit hunk made in the session (in a
+//! per-session state file), then compare each new tool call against that
+//! history:
+//!
+//! 1. An Edit whose `old_string`/`new_string` is the inverse of an earlier
+//!    hunk, or a Write that brings back text an earlier edit replaced.
+//! 2. A Bash `git checkout`/`git restore`/`git reset --hard`/`git revert` that
+//!    throws away files edited earlier in the session.
+//! 3. A redirection that probably meant the other operator: `>` onto a file
+//!    read or appended to earlier in the session (or read by the same
+//!    command, `sort f > f`), or `>>` onto a source or config file.
+//!
+//! Findings are advisory: Claude gets `additionalContext` asking what changed
+//! its mind, nothing is blocked.
+
+use hook_common::bash::{output_redirects, simple_commands, Word};
+use hook_common::config;
+use hook_common::dead_letter;
+use hook_common::doctor::{self, Doctor};
+use hook_common::decision::{Decision, Event, Finding};
+use hook_common::edits::WriteOp;
+use hook_common::false_positive;
+use hook_common::state;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+use std::process;
+
+const HOOK: &str = "thrash-detector";
+
+/// Oldest hunks are dropped beyond this many, keeping the state file small.
+const MAX_EDITS: usize = 200;
+
+/// Oldest read/appended paths are dropped beyond this many.
+const MAX_READS: usize = 200;
+
+/// Extensions of source and config files, which are rewritten rather than
+/// appended to.
+const SOURCE_EXTENSIONS: &[&str] = &[
+    "rs", "py", "js", "jsx", "ts", "tsx", "mjs", "cjs", "go", "java", "kt", "c", "h", "cc",
+    "cpp", "hpp", "cs", "rb", "php", "swift", "sh", "json", "toml", "yaml", "yml", "ini",
+    "cfg", "conf", "xml",
+];
+
+/// Finding rules for check 3, reported under their own heading.
+const REDIRECT_RULES: &[&str] = &["truncate-after-read", "append-to-source"];
+
+fn main() {
+    if doctor::requested() {
+        let mut d = Doctor::new(HOOK);
+        d.count("redirect rules", REDIRECT_RULES.len());
+        d.exit();
+    }
+
+    let mut input = String::new();
+    if io::stdin().read_to_string(&mut input).is_err() || !config::enabled_here() {
+        process::exit(0);
+    }
+    dead_letter::guard(HOOK, &input, || run(&input));
+}
+
+fn run(input: &str) {
+    let data: Value = match serde_json::from_str(input) {
+        Ok(v) => v,
+        Err(_) => process::exit(0),
+    };
+
+    let session_id = data["session_id"].as_str().unwrap_or("unknown");
+    let tool_name = data["tool_name"].as_str().unwrap_or("");
+    let tool_input = &data["tool_input"];
+
+    let path = state::session_path(HOOK, session_id, "json");
+    let mut history = History::load(&path);
+    let mut decision = Decision::new(HOOK, Event::PostToolUse).with_session(session_id);
+
+    if let Some(write) = WriteOp::from_tool_input(tool_name, tool_input) {
+        if let Some(f) = check_write_revert(&mut history, &write) {
+            decision.push(f);
+        }
+        history.record(&write);
+    } else if tool_name == "Bash" {
+        let command = tool_input["command"].as_str().unwrap_or("");
+        if let Some(f) = check_git_discard(&mut history, command) {
+            decision.push(f);
+        }
+        for f in check_redirects(&mut history, command) {
+            decision.push(f);
+        }
+    } else if tool_name == "Read" {
+        history.record_read(tool_input["file_path"].as_str().unwrap_or(""));
+    } else {
+        process::exit(0);
+    }
+
+    history.save(&path);
+    false_positive::report_if_requested(HOOK, input, decision.findings());
+    decision.emit(build_reason).exit();
+}
+
+// ---------------------------------------------------------------------------
+// Edit history
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct History {
+    edits: Vec<EditRecord>,
+    /// Files read (Read tool) or appended to (`>>`), oldest first.
+    #[serde(default)]
+    reads: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct EditRecord {
+    file: String,
+    old: String,
+    new: String,
+}
+
+impl History {
+    fn load(path: &Path) -> History {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, path: &Path) {
+        if let Ok(s) = serde_json::to_string(self) {
+            let _ = fs::write(path, s);
+        }
+    }
+
+    fn record(&mut self, write: &WriteOp) {
+        let file = normalize_path(&write.file_path);
+        for (old, new) in &write.hunks {
+            if old != new {
+                self.edits.push(EditRecord {
+                    file: file.clone(),
+                    old: old.clone(),
+                    new: new.clone(),
+                });
+            }
+        }
+        if self.edits.len() > MAX_EDITS {
+            let excess = self.edits.len() - MAX_EDITS;
+            self.edits.drain(..excess);
+        }
+    }
+
+    fn record_read(&mut self, file: &str) {
+        let file = normalize_path(file);
+        if file.is_empty() {
+            return;
+        }
+        self.reads.retain(|f| *f != file);
+        self.reads.push(file);
+        if self.reads.len() > MAX_READS {
+            let excess = self.reads.len() - MAX_READS;
+            self.reads.drain(..excess);
+        }
+    }
+
+    fn forget_file(&mut self, file: &str) {
+        self.edits.retain(|e| e.file != file);
+    }
+
+    fn edited_files(&self) -> Vec<String> {
+        let mut files: Vec<String> = Vec::new();
+        for e in &self.edits {
+            if !files.contains(&e.file) {
+                files.push(e.file.clone());
+            }
+        }
+        files
+    }
+}
+
+/// Forward slashes so `C:\src\a.rs` and `C:/src/a.rs` compare equal.
+fn normalize_path(path: &str) -> String {
+    path.replace('\\', "/")
+}
+
+/// Whitespace-insensitive comparison key for edit text.
+fn squash(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// ---------------------------------------------------------------------------
+// Check 1: Inverse edits
+// ---------------------------------------------------------------------------
+
+/// Report (and drop from history) earlier hunks this write undoes. An Edit
+/// undoes a hunk when it swaps the same old/new text back; a Write undoes it
+/// when the new content contains the hunk's old text but no longer its new
+/// text.
+fn check_write_revert(history: &mut History, write: &WriteOp) -> Option<Finding> {
+    let file = normalize_path(&write.file_path);
+    let mut reverted = Vec::new();
+
+    for (i, rec) in history.edits.iter().enumerate() {
+        if rec.file != file || squash(&rec.old).is_empty() || squash(&rec.new).is_empty() {
+            continue;
+        }
+        let undone = write.hunks.iter().any(|(old, new)| {
+            if old.is_empty() {
+                new.contains(rec.old.trim()) && !new.contains(rec.new.trim())
+            } else {
+                squash(old) == squash(&rec.new) && squash(new) == squash(&rec.old)
+            }
+        });
+        if undone {
+            reverted.push(i);
+        }
+    }
+
+    if reverted.is_empty() {
+        return None;
+    }
+
+    for i in reverted.iter().rev() {
+        history.edits.remove(*i);
+    }
+
+    let plural = if reverted.len() == 1 { "" } else { "s" };
+    Some(Finding::warn(
+        "inverse-edit",
+        format!(
+            "This write to `{}` reverts {} edit{} you made earlier in this session.",
+            file,
+            reverted.len(),
+            plural
+        ),
+    ))
+}
+
+// ---------------------------------------------------------------------------
+// Check 2: git commands discarding session edits
+// ---------------------------------------------------------------------------
+
+/// Report `git` commands that discard files edited earlier in the session.
+/// Discarded files are dropped from history so the same edits aren't reported
+/// twice.
+fn check_git_discard(history: &mut History, command: &str) -> Option<Finding> {
+    let edited = history.edited_files();
+    if edited.is_empty() {
+        return None;
+    }
+
+    let mut discarded: Vec<String> = Vec::new();
+    let mut verbs: Vec<String> = Vec::new();
+
+    for segment in command.split([';', '&', '|', '\n']) {
+        let words: Vec<&str> = segment
+            .split_whitespace()
+            .map(|w| w.trim_matches(|c| c == '"' || c == '\''))
+            .collect();
+        let Some(git) = words.iter().position(|w| *w == "git") else {
+            continue;
+        };
+        let Some(sub) = words.get(git + 1) else {
+            continue;
+        };
+        let args: Vec<&str> = words[git + 2..].to_vec();
+
+        let hits: Vec<String> = match *sub {
+            "revert" => edited.clone(),
+            "reset" if args.contains(&"--hard") => edited.clone(),
+            "checkout" | "restore" => {
+                if *sub == "restore" && args.contains(&"--staged") && !args.contains(&"--worktree") {
+                    continue;
+                }
+                let paths: Vec<&str> = args
+                    .iter()
+                    .copied()
+                    .filter(|a| !a.starts_with('-'))
+                    .collect();
+                edited
+                    .iter()
+                    .filter(|f| paths.iter().any(|p| path_covers(p, f)))
+                    .cloned()
+                    .collect()
+            }
+            _ => continue,
+        };
+
+        if !hits.is_empty() {
+            verbs.push(format!("git {}", sub));
+            for h in hits {
+                if !discarded.contains(&h) {
+                    discarded.push(h);
+                }
+            }
+        }
+    }
+
+    if discarded.is_empty() {
+        return None;
+    }
+
+    for f in &discarded {
+        history.forget_file(f);
+    }
+
+    let list = discarded
+        .iter()
+        .map(|f| format!("`{}`", f))
+        .collect::<Vec<_>>()
+        .join(", ");
+    Some(Finding::warn(
+        "git-discard",
+        format!(
+            "`{}` throws away changes you made earlier in this session to {}.",
+            verbs.join("`, `"),
+            list
+        ),
+    ))
+}
+
+/// Whether a pathspec argument covers an edited file: `.` covers everything,
+/// otherwise the edited path must equal or end with the argument on a
+/// component boundary (`src/a.rs` covers `/repo/src/a.rs`).
+fn path_covers(arg: &str, file: &str) -> bool {
+    let arg = normalize_path(arg);
+    let arg = arg.trim_start_matches("./").trim_end_matches('/');
+    if arg.is_empty() || arg == "." {
+        return true;
+    }
+    file == arg
+        || file.ends_with(&format!("/{}", arg))
+        || file.contains(&format!("/{}/", arg))
+        || file.starts_with(&format!("{}/", arg))
+}
+
+// ---------------------------------------------------------------------------
+// Check 3: `>` vs `>>` mix-ups
+// ---------------------------------------------------------------------------
+
+/// Report `>` onto a file read or appended to earlier in the session (or read
+/// by the same command), and `>>` onto a source or config file. Files appended
+/// to are remembered as read, so a later `>` onto a log is caught; a file
+/// truncated is forgotten so it's only reported once.
+fn check_redirects(history: &mut History, command: &str) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for words in simple_commands(command) {
+        for r in output_redirects(&words) {
+            let target = normalize_path(&r.target);
+            if r.append {
+                if is_source_file(&target) {
+                    findings.push(Finding::warn(
+                        "append-to-source",
+                        format!(
+                            "`>> {}` appends to a source/config file; if you meant to replace \
+                             its contents, use `>` or the Write tool, otherwise check the file \
+                             doesn't now hold duplicated content.",
+                            r.target
+                        ),
+                    ));
+                }
+                history.record_read(&target);
+                continue;
+            }
+            let same_command = reads_file(&words, &target);
+            let earlier = history.reads.iter().position(|f| same_file(&target, f));
+            if same_command || earlier.is_some() {
+                let when = if same_command {
+                    "is also read by this command, and the shell truncates it before the \
+                     command runs"
+                } else {
+                    "was read or appended to earlier in this session"
+                };
+                findings.push(Finding::warn(
+                    "truncate-after-read",
+                    format!(
+                        "`> {}` truncates a file that {}; if you meant to add to it, use `>>`.",
+                        r.target, when
+                    ),
+                ));
+            }
+            if let Some(i) = earlier {
+                history.reads.remove(i);
+            }
+        }
+    }
+    findings
+}
+
+/// Whether a non-redirection word of the command names `file`.
+fn reads_file(words: &[Word], file: &str) -> bool {
+    let op = |w: &Word| w.text.trim_start_matches(|c: char| c.is_ascii_digit()).to_string();
+    words.iter().enumerate().any(|(i, w)| {
+        let after_bare_op = i > 0 && matches!(op(&words[i - 1]).as_str(), ">" | ">>" | ">|");
+        !op(w).starts_with('>') && !after_bare_op && same_file(&w.text, file)
+    })
+}
+
+/// Whether a redirect target and a recorded path name the same file: equal,
+/// or one is the other's suffix on a component boundary (`out.log` and
+/// `/repo/out.log`).
+fn same_file(a: &str, b: &str) -> bool {
+    let a = normalize_path(a);
+    let b = normalize_path(b);
+    let a = a.trim_start_matches("./");
+    let b = b.trim_start_matches("./");
+    !a.is_empty()
+        && !b.is_empty()
+        && (a == b || a.ends_with(&format!("/{}", b)) || b.ends_with(&format!("/{}", a)))
+}
+
+fn is_source_file(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => {
+            SOURCE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str())
+        }
+        _ => false,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Reason
+// ---------------------------------------------------------------------------
+
+fn build_reason(findings: &[&Finding]) -> String {
+    let list = |fs: &[&&Finding]| {
+        fs.iter()
+            .map(|f| format!("  - {}", f.message))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let (redirects, undos): (Vec<&&Finding>, Vec<&&Finding>) = findings
+        .iter()
+        .partition(|f| REDIRECT_RULES.contains(&f.rule.as_str()));
+
+    let mut parts = Vec::new();
+    if !undos.is_empty() {
+        parts.push(format!(
+            "thrash-detector: you are undoing your own work.\n{}\n\n\
+             Flip-flopping between approaches wastes turns. Before continuing, state \
+             what you learned that changed your mind, then commit to one approach.",
+            list(&undos)
+        ));
+    }
+    if !redirects.is_empty() {
+        parts.push(format!(
+            "thrash-detector: check this redirection.\n{}\n\n\
+             If the wrong operator was used, restore the file's contents before continuing.",
+            list(&redirects)
+        ));
+    }
+    parts.join("\n\n")
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn edit(file: &str, old: &str, new: &str) -> WriteOp {
+        WriteOp::from_tool_input(
+            "Edit",
+            &json!({"file_path": file, "old_string": old, "new_string": new}),
+        )
+        .unwrap()
+    }
+
+    fn history_with(writes: &[WriteOp]) -> History {
+        let mut h = History::default();
+        for w in writes {
+            h.record(w);
+        }
+        h
+    }
+
+    // -- Check 1: Inverse edits -----------------------------------------------
+
+    #[test]
+    fn warns_on_edit_then_revert() {
+        let mut h = history_with(&[edit("src/a.rs", "let x = 1;", "let x = 2;")]);
+        let f = check_write_revert(&mut h, &edit("src/a.rs", "let x = 2;", "let x = 1;")).unwrap();
+        assert!(f.message.contains("src/a.rs"));
+        assert!(f.message.contains("reverts 1 edit"));
+        // The reverted hunk is forgotten so it's only reported once.
+        assert!(h.edits.is_empty());
+    }
+
+    #[test]
+    fn revert_ignores_whitespace_differences() {
+        let mut h = history_with(&[edit("a.rs", "foo(1)", "foo(2)")]);
+        assert!(check_write_revert(&mut h, &edit("a.rs", "  foo(2)\n", "foo(1)")).is_some());
+    }
+
+    #[test]
+    fn warns_on_write_restoring_old_text() {
+        let mut h = history_with(&[edit("a.py", "retries = 3", "retries = 5")]);
+        let w = WriteOp::from_tool_input(
+            "Write",
+            &json!({"file_path": "a.py", "content": "import x\nretries = 3\n"}),
+        )
+        .unwrap();
+        assert!(check_write_revert(&mut h, &w).is_some());
+    }
+
+    #[test]
+    fn forward_progress_is_clean() {
+        let mut h = history_with(&[edit("a.rs", "let x = 1;", "let x = 2;")]);
+        assert!(check_write_revert(&mut h, &edit("a.rs", "let x = 2;", "let x = 3;")).is_none());
+        assert!(check_write_revert(&mut h, &edit("b.rs", "let x = 2;", "let x = 1;")).is_none());
+    }
+
+    #[test]
+    fn backslash_and_forward_slash_paths_match() {
+        let mut h = history_with(&[edit(r"C:\src\a.rs", "a", "b")]);
+        assert!(check_write_revert(&mut h, &edit("C:/src/a.rs", "b", "a")).is_some());
+    }
+
+    // -- Check 2: git discard -------------------------------------------------
+
+    #[test]
+    fn warns_on_checkout_of_edited_file() {
+        let mut h = history_with(&[edit("/repo/src/a.rs", "a", "b")]);
+        let f = check_git_discard(&mut h, "git checkout -- src/a.rs").unwrap();
+        assert!(f.message.contains("git checkout"));
+        assert!(f.message.contains("/repo/src/a.rs"));
+        assert!(h.edits.is_empty());
+    }
+
+    #[test]
+    fn warns_on_reset_hard_and_revert() {
+        let mut h = history_with(&[edit("a.rs", "a", "b")]);
+        assert!(check_git_discard(&mut h, "git reset --hard HEAD").is_some());
+
+        let mut h = history_with(&[edit("a.rs", "a", "b")]);
+        assert!(check_git_discard(&mut h, "git add -A && git revert HEAD --no-edit").is_some());
+    }
+
+    #[test]
+    fn warns_on_restore_dot() {
+        let mut h = history_with(&[edit("a.rs", "a", "b"), edit("b.rs", "a", "b")]);
+        let f = check_git_discard(&mut h, "git restore .").unwrap();
+        assert!(f.message.contains("a.rs") && f.message.contains("b.rs"));
+    }
+
+    #[test]
+    fn ignores_unrelated_git_commands() {
+        let mut h = history_with(&[edit("src/a.rs", "a", "b")]);
+        assert!(check_git_discard(&mut h, "git status && git diff").is_none());
+        assert!(check_git_discard(&mut h, "git checkout -- src/other.rs").is_none());
+        assert!(check_git_discard(&mut h, "git restore --staged src/a.rs").is_none());
+        assert!(check_git_discard(&mut h, "git reset HEAD~1").is_none());
+    }
+
+    #[test]
+    fn ignores_git_without_session_edits() {
+        let mut h = History::default();
+        assert!(check_git_discard(&mut h, "git reset --hard").is_none());
+    }
+
+    // -- Check 3: redirections ------------------------------------------------
+
+    #[test]
+    fn warns_on_truncating_a_file_read_earlier() {
+        let mut h = History::default();
+        h.record_read("/repo/data/results.csv");
+        let f = check_redirects(&mut h, "python summarize.py > data/results.csv");
+        assert_eq!(f.len(), 1);
+        assert_eq!(f[0].rule, "truncate-after-read");
+        assert!(f[0].message.contains("`> data/results.csv`"), "got: {}", f[0].message);
+        assert!(f[0].message.contains("earlier in this session"));
+        // Reported once; the truncated file is no longer "read".
+        assert!(check_redirects(&mut h, "python summarize.py > data/results.csv").is_empty());
+    }
+
+    #[test]
+    fn warns_on_truncating_an_appended_log() {
+        let mut h = History::default();
+        assert!(check_redirects(&mut h, "echo start >> build.log").is_empty());
+        assert_eq!(check_redirects(&mut h, "make 2> build.log").len(), 1);
+    }
+
+    #[test]
+    fn warns_on_reading_and_truncating_in_one_command() {
+        let mut h = History::default();
+        let f = check_redirects(&mut h, "sort names.txt > names.txt");
+        assert_eq!(f.len(), 1);
+        assert!(f[0].message.contains("before the command runs"));
+    }
+
+    #[test]
+    fn warns_on_appending_to_source() {
+        let mut h = History::default();
+        let f = check_redirects(&mut h, "cat snippet >> src/config.toml");
+        assert_eq!(f[0].rule, "append-to-source");
+        assert!(check_redirects(&mut h, "echo x >> notes.txt && echo y >> .gitignore").is_empty());
+    }
+
+    #[test]
+    fn fresh_output_files_are_clean() {
+        let mut h = History::default();
+        h.record_read("/repo/input.csv");
+        assert!(check_redirects(&mut h, "sort input.csv > sorted.csv 2>/dev/null").is_empty());
+    }
+
+    #[test]
+    fn reason_groups_redirects_separately() {
+        let undo = Finding::warn("inverse-edit", "reverted");
+        let redirect = Finding::warn("truncate-after-read", "truncated");
+        let reason = build_reason(&[&undo, &redirect]);
+        assert!(reason.starts_with("thrash-detector: you are undoing your own work.\n  - reverted"));
+        assert!(reason.contains("thrash-detector: check this redirection.\n  - truncated"));
+        assert!(!build_reason(&[&redirect]).contains("undoing"));
+    }
+
+    // -- Persistence ----------------------------------------------------------
+
+    #[test]
+    fn history_round_trips_through_state_file() {
+        let path = std::env::temp_dir().join(format!("thrash-detector-test-{}.json", process::id()));
+        history_with(&[edit("a.rs", "a", "b")]).save(&path);
+        let mut h = History::load(&path);
+        let _ = fs::remove_file(&path);
+        assert!(check_write_revert(&mut h, &edit("a.rs", "b", "a")).is_some());
+    }
+
+    #[test]
+    fn history_is_capped() {
+        let writes: Vec<WriteOp> = (0..MAX_EDITS + 5)
+            .map(|i| edit("a.rs", &format!("v{}", i), &format!("v{}", i + 1)))
+            .collect();
+        assert_eq!(history_with(&writes).edits.len(), MAX_EDITS);
+    }
+}
@@ -0,0 +1,68 @@
+//! unrelated-issue-detector's vocabulary, shared by the hook and the
+//! `pattern-test` tool (see `crates/pattern-test`).
+
+use hook_common::pattern::Group;
+
+/// Dismissal phrases matched case-insensitively. Kept narrow on purpose so the
+/// hook only fires when the agent is *actually* dismissing an issue, not when
+/// it incidentally mentions the words "unrelated" or "pre-existing".
+pub const PATTERNS: &[&str] = &[
+    // Pre-existing
+    "pre-existing issue",
+    "pre-existing bug",
+    "pre-existing problem",
+    "pre-existing failure",
+    "pre-existing error",
+    "preexisting issue",
+    "preexisting bug",
+    "preexisting problem",
+    "preexisting failure",
+    "preexisting error",
+    // Unrelated to this/my/the change/PR/work
+    "unrelated to this change",
+    "unrelated to my change",
+    "unrelated to the change",
+    "unrelated to these changes",
+    "unrelated to my changes",
+    "unrelated to this pr",
+    "unrelated to my pr",
+    "unrelated to this work",
+    "unrelated to this task",
+    "unrelated to this fix",
+    // Not related / not caused / not introduced
+    "not related to this change",
+    "not related to my change",
+    "not related to these changes",
+    "not related to my changes",
+    "not caused by this change",
+    "not caused by my change",
+    "not caused by these changes",
+    "not introduced by this change",
+    "not introduced by my change",
+    "not introduced by these changes",
+    "not introduced by my changes",
+    "not something we introduced",
+    "not something i introduced",
+    // Already broken / failing on main
+    "already broken on main",
+    "already failing on main",
+    "already failing before",
+    "already broken before",
+    "already present on main",
+    "broken on main",
+    // Out of scope
+    "outside the scope of this",
+    "outside the scope of my",
+    "beyond the scope of this",
+    "out of scope for this",
+    // Separate
+    "separate issue from",
+    "separate bug from",
+    "separate concern from",
+];
+
+/// The pattern groups the hook matches, by name, for `--pattern-test` and
+/// `pattern-test`.
+pub fn pattern_groups() -> Vec<Group<'static>> {
+    vec![Group { name: "phrases", patterns: PATTERNS.to_vec(), case_sensitive: false }]
+}
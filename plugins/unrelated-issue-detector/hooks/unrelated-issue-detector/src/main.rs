@@ -5,77 +5,298 @@
 //! (via a per-session offset file) for narrow dismissal phrases. If any are
 //! found, blocks the tool call and asks Claude to surface evidence for each
 //! dismissal so the user can make the judgement call.
-
-use serde_json::{json, Value};
+//!
+//! Matches are held in a per-session pending file for a short aggregation
+//! window, so dismissals spread over several quick tool calls produce a single
+//! block. A tool call after the window, or the Stop event, flushes them.
+//!
+//! `--watch <transcript>` runs the same scan as a long-lived process instead:
+//! it tails the transcript and prints one decision JSON line per batch of new
+//! content with dismissals, for integrations where spawning the binary on
+//! every tool call is too heavy. The per-call mode stays the default.
+//!
+//! Opt-in `[issue_escalation]`: the lines containing each dismissal are
+//! accumulated per session, and once they reach a threshold the hook runs
+//! `gh issue create` with them, once per session. Filing is best-effort; a
+//! missing or failing `gh` never changes the hook's decision.
+
+use hook_common::anonymize;
+use hook_common::config;
+use hook_common::dead_letter;
+use hook_common::doctor::{self, Doctor};
+use hook_common::decision::{Decision, Event, Finding};
+use hook_common::false_positive;
+use hook_common::pattern::{self, find_case_insensitive, DocUrls};
+use hook_common::quiet;
+use hook_common::redact::redact;
+use hook_common::state;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::collections::HashSet;
 use std::env;
 use std::fs;
 use std::io::{self, Read, Seek, SeekFrom};
-use std::path::PathBuf;
-use std::process;
-
-/// Dismissal phrases matched case-insensitively. Kept narrow on purpose so the
-/// hook only fires when the agent is *actually* dismissing an issue, not when
-/// it incidentally mentions the words "unrelated" or "pre-existing".
-const PATTERNS: &[&str] = &[
-    // Pre-existing
-    "pre-existing issue",
-    "pre-existing bug",
-    "pre-existing problem",
-    "pre-existing failure",
-    "pre-existing error",
-    "preexisting issue",
-    "preexisting bug",
-    "preexisting problem",
-    "preexisting failure",
-    "preexisting error",
-    // Unrelated to this/my/the change/PR/work
-    "unrelated to this change",
-    "unrelated to my change",
-    "unrelated to the change",
-    "unrelated to these changes",
-    "unrelated to my changes",
-    "unrelated to this pr",
-    "unrelated to my pr",
-    "unrelated to this work",
-    "unrelated to this task",
-    "unrelated to this fix",
-    // Not related / not caused / not introduced
-    "not related to this change",
-    "not related to my change",
-    "not related to these changes",
-    "not related to my changes",
-    "not caused by this change",
-    "not caused by my change",
-    "not caused by these changes",
-    "not introduced by this change",
-    "not introduced by my change",
-    "not introduced by these changes",
-    "not introduced by my changes",
-    "not something we introduced",
-    "not something i introduced",
-    // Already broken / failing on main
-    "already broken on main",
-    "already failing on main",
-    "already failing before",
-    "already broken before",
-    "already present on main",
-    "broken on main",
-    // Out of scope
-    "outside the scope of this",
-    "outside the scope of my",
-    "beyond the scope of this",
-    "out of scope for this",
-    // Separate
-    "separate issue from",
-    "separate bug from",
-    "separate concern from",
-];
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use unrelated_issue_detector::{pattern_groups, PATTERNS};
+
+const HOOK: &str = "unrelated-issue-detector";
+
+/// Default for `aggregation_window_secs`.
+const DEFAULT_WINDOW_SECS: u64 = 3;
+
+/// How often `--watch` checks the transcript for new content.
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Upper bound on the time spent creating the escalation issue.
+const ISSUE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Longest dismissal snippet kept for the escalation issue, in characters.
+const SNIPPET_MAX_CHARS: usize = 200;
+
+/// Optional `.claude/unrelated-issue-detector.toml`.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Config {
+    /// "Learn more" link per pattern, appended to its finding.
+    doc_urls: DocUrls,
+    /// How long matches are accumulated before one combined block. `0` blocks
+    /// on every tool call that finds a match.
+    aggregation_window_secs: u64,
+    /// Sessions whose transcript matches one of these are not checked at all.
+    ignore_transcript_globs: Vec<String>,
+    /// Local-time windows (`"18:00-09:00"`) in which findings warn instead of
+    /// blocking.
+    quiet_hours: Vec<quiet::Window>,
+    /// Filing a GitHub issue after repeated dismissals in a session.
+    issue_escalation: IssueEscalation,
+}
+
+impl Config {
+    fn ignores_transcript(&self, transcript_path: &str) -> bool {
+        self.ignore_transcript_globs.iter().any(|g| config::glob_match(g, transcript_path))
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            doc_urls: DocUrls::default(),
+            aggregation_window_secs: DEFAULT_WINDOW_SECS,
+            ignore_transcript_globs: Vec::new(),
+            quiet_hours: Vec::new(),
+            issue_escalation: IssueEscalation::default(),
+        }
+    }
+}
+
+/// `[issue_escalation]`. `{session}` and `{count}` in `title` and `labels` are
+/// replaced with the session id and the number of dismissals.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct IssueEscalation {
+    enabled: bool,
+    /// Dismissals in a session before the issue is created.
+    after: usize,
+    title: String,
+    labels: Vec<String>,
+    /// Program run as `<command> issue create …`.
+    command: String,
+}
+
+impl Default for IssueEscalation {
+    fn default() -> Self {
+        IssueEscalation {
+            enabled: false,
+            after: 3,
+            title: "{count} dismissed issues in session {session}".to_string(),
+            labels: Vec::new(),
+            command: "gh".to_string(),
+        }
+    }
+}
+
+impl IssueEscalation {
+    fn expand(&self, template: &str, session_id: &str, count: usize) -> String {
+        template
+            .replace("{session}", session_id)
+            .replace("{count}", &count.to_string())
+    }
+
+    /// Arguments for `<command>`: `issue create` with the title, a body
+    /// listing `snippets`, and one `--label` per label. The issue may be
+    /// public, so snippets are redacted (and their paths anonymized under
+    /// `HOOK_ANONYMIZE_PATHS=1`) first.
+    fn args(&self, session_id: &str, snippets: &[String]) -> Vec<String> {
+        let count = snippets.len();
+        let body = format!(
+            "{} issues were dismissed as pre-existing or unrelated in session `{}` (flagged by {}):\n\n{}\n",
+            count,
+            session_id,
+            HOOK,
+            snippets
+                .iter()
+                .map(|s| format!("- {}", anonymize::logged(&redact(s))))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+        let mut args = vec![
+            "issue".to_string(),
+            "create".to_string(),
+            "--title".to_string(),
+            self.expand(&self.title, session_id, count),
+            "--body".to_string(),
+            body,
+        ];
+        for label in &self.labels {
+            args.push("--label".to_string());
+            args.push(self.expand(label, session_id, count));
+        }
+        args
+    }
+}
+
+/// Dismissal snippets seen so far in a session, for [`IssueEscalation`].
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Dismissals {
+    snippets: Vec<String>,
+    /// The issue was created (or attempted) already.
+    filed: bool,
+}
+
+impl Dismissals {
+    fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, path: &Path) {
+        if let Ok(json) = serde_json::to_string(self) {
+            let _ = fs::write(path, json);
+        }
+    }
+
+    /// Add `new` snippets; true when they just reached `after` and the issue
+    /// is due. It is due once per session, even if creating it fails.
+    fn record(&mut self, new: Vec<String>, after: usize) -> bool {
+        for s in new {
+            if !self.snippets.contains(&s) {
+                self.snippets.push(s);
+            }
+        }
+        if self.filed || self.snippets.len() < after.max(1) {
+            return false;
+        }
+        self.filed = true;
+        true
+    }
+}
+
+/// Record the dismissals in `content` and create the issue once the
+/// threshold is reached. Failures are reported on stderr only.
+fn escalate(esc: &IssueEscalation, session_id: &str, content: &str) {
+    let path = state::session_path(HOOK, session_id, "dismissals.json");
+    let mut dismissals = Dismissals::load(&path);
+    let due = dismissals.record(dismissal_snippets(content), esc.after);
+    dismissals.save(&path);
+    if due {
+        if let Err(e) = run_with_timeout(&esc.command, &esc.args(session_id, &dismissals.snippets), ISSUE_TIMEOUT) {
+            eprintln!("{}: could not create the escalation issue with `{}`: {}", HOOK, esc.command, e);
+        }
+    }
+}
+
+/// Run `command` with `args`, killing it after `timeout`. A non-zero exit is
+/// an error.
+fn run_with_timeout(command: &str, args: &[String], timeout: Duration) -> io::Result<()> {
+    let mut child = Command::new(command)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return if status.success() {
+                Ok(())
+            } else {
+                Err(io::Error::other(format!("exited with {}", status)))
+            };
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(io::Error::from(io::ErrorKind::TimedOut));
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+}
+
+/// Matches waiting for the aggregation window to close.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Pending {
+    /// Unix time (ms) of the first match in the window.
+    first_seen_ms: u64,
+    patterns: Vec<String>,
+}
+
+impl Pending {
+    fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, path: &Path) {
+        if self.patterns.is_empty() {
+            let _ = fs::remove_file(path);
+        } else if let Ok(json) = serde_json::to_string(self) {
+            let _ = fs::write(path, json);
+        }
+    }
+
+    /// Add `new` patterns and return everything pending once the window has
+    /// closed (or `flush` is set), leaving the state empty.
+    fn aggregate(
+        &mut self,
+        new: Vec<String>,
+        now_ms: u64,
+        window_ms: u64,
+        flush: bool,
+    ) -> Option<Vec<String>> {
+        if self.patterns.is_empty() {
+            self.first_seen_ms = now_ms;
+        }
+        for p in new {
+            if !self.patterns.contains(&p) {
+                self.patterns.push(p);
+            }
+        }
+        if self.patterns.is_empty() {
+            return None;
+        }
+        if flush || now_ms.saturating_sub(self.first_seen_ms) >= window_ms {
+            return Some(std::mem::take(&mut self.patterns));
+        }
+        None
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
 
 fn offset_path(session_id: &str) -> PathBuf {
-    let mut p = env::temp_dir();
-    p.push(format!("unrelated-issue-{}.offset", session_id));
-    p
+    state::session_path(HOOK, session_id, "offset")
 }
 
 fn read_offset(session_id: &str) -> u64 {
@@ -126,23 +347,105 @@ fn extract_assistant_text(entry: &Value) -> String {
     String::new()
 }
 
-fn scan_text(text: &str, findings: &mut Vec<String>, seen: &mut HashSet<String>) {
-    let lower = text.to_lowercase();
+fn scan_text(text: &str, findings: &mut Vec<Finding>, seen: &mut HashSet<String>) {
     for &pattern in PATTERNS {
-        if !seen.contains(pattern) && lower.contains(pattern) {
-            findings.push(format!("\"{}\"", pattern));
+        if !seen.contains(pattern) && find_case_insensitive(text, pattern).is_some() {
+            findings.push(finding(pattern));
             seen.insert(pattern.to_string());
         }
     }
 }
 
+fn finding(pattern: &str) -> Finding {
+    Finding::block(pattern, format!("\"{}\"", pattern))
+}
+
+/// Transcript content appended after `offset`, and the offset to resume from.
+/// A file that shrank (rotated or rewritten) is read from the start again.
+fn read_new_content(path: &Path, offset: u64) -> Option<(String, u64)> {
+    let mut file = fs::File::open(path).ok()?;
+    let size = file.seek(SeekFrom::End(0)).ok()?;
+    let start = if size < offset { 0 } else { offset };
+    let mut content = String::new();
+    if size > start {
+        file.seek(SeekFrom::Start(start)).ok()?;
+        file.read_to_string(&mut content).ok()?;
+    }
+    Some((content, size))
+}
+
+/// The text of every assistant message in a chunk of transcript JSONL.
+fn assistant_texts(content: &str) -> impl Iterator<Item = String> + '_ {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .map(|entry| extract_assistant_text(&entry))
+        .filter(|text| !text.is_empty())
+}
+
+/// Findings for every assistant message in a chunk of transcript JSONL.
+fn scan_entries(content: &str) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut seen = HashSet::new();
+    for text in assistant_texts(content) {
+        scan_text(&text, &mut findings, &mut seen);
+    }
+    findings
+}
+
+/// Each line of an assistant message that contains a dismissal phrase,
+/// trimmed and cut to [`SNIPPET_MAX_CHARS`].
+fn dismissal_snippets(content: &str) -> Vec<String> {
+    let mut snippets: Vec<String> = Vec::new();
+    for text in assistant_texts(content) {
+        for line in text.lines().map(str::trim) {
+            if !PATTERNS.iter().any(|p| find_case_insensitive(line, p).is_some()) {
+                continue;
+            }
+            let snippet: String = line.chars().take(SNIPPET_MAX_CHARS).collect();
+            if !snippets.contains(&snippet) {
+                snippets.push(snippet);
+            }
+        }
+    }
+    snippets
+}
+
 fn main() {
+    if doctor::requested() {
+        let mut d = Doctor::new(HOOK);
+        let _: Config = d.config();
+        d.count("patterns", PATTERNS.len());
+        d.exit();
+    }
+    pattern::run_test_if_requested(HOOK, &pattern_groups());
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("--watch") {
+        let Some(path) = args.get(1) else {
+            eprintln!("usage: {} --watch <transcript.jsonl>", HOOK);
+            process::exit(2);
+        };
+        let cfg: Config = config::load(HOOK);
+        let mut tail = Tail::new(PathBuf::from(path));
+        watch(&mut tail, &cfg, &mut io::stdout(), || {
+            thread::sleep(WATCH_INTERVAL);
+            true
+        });
+        return;
+    }
+
     let mut input = String::new();
-    if io::stdin().read_to_string(&mut input).is_err() {
+    if io::stdin().read_to_string(&mut input).is_err() || !config::enabled_here() {
         process::exit(0);
     }
+    dead_letter::guard(HOOK, &input, || run(&input));
+}
 
-    let input_data: Value = match serde_json::from_str(&input) {
+fn run(input: &str) {
+    let input_data: Value = match serde_json::from_str(input) {
         Ok(v) => v,
         Err(_) => process::exit(0),
     };
@@ -156,58 +459,130 @@ fn main() {
         Some(p) if !p.is_empty() => p,
         _ => process::exit(0),
     };
+    let cfg: Config = config::load(HOOK);
+    if cfg.ignores_transcript(transcript_path) {
+        process::exit(0);
+    }
 
-    let last_offset = read_offset(session_id);
-
-    // Read only new transcript content since last check.
-    let mut file = match fs::File::open(transcript_path) {
-        Ok(f) => f,
-        Err(_) => process::exit(0),
-    };
-
-    let current_size = match file.seek(SeekFrom::End(0)) {
-        Ok(s) => s,
-        Err(_) => process::exit(0),
-    };
+    let stop = input_data.get("hook_event_name").and_then(|v| v.as_str()) == Some("Stop");
+    let pending_path = state::session_path(HOOK, session_id, "pending.json");
 
-    if current_size <= last_offset {
+    // Claude is already answering a flush from this hook: skip past its reply
+    // (which repeats the dismissal phrases) instead of blocking again.
+    if stop && input_data.get("stop_hook_active").and_then(|v| v.as_bool()) == Some(true) {
+        if let Ok(meta) = fs::metadata(transcript_path) {
+            save_offset(session_id, meta.len());
+        }
+        let _ = fs::remove_file(&pending_path);
         process::exit(0);
     }
 
-    if file.seek(SeekFrom::Start(last_offset)).is_err() {
+    // Read only new transcript content since last check.
+    let last_offset = read_offset(session_id);
+    let transcript = Path::new(transcript_path);
+    let Some((new_content, current_size)) = read_new_content(transcript, last_offset) else {
         process::exit(0);
+    };
+    // Always advance the offset so we never re-scan the same content.
+    if current_size != last_offset {
+        save_offset(session_id, current_size);
     }
 
-    let mut new_content = String::new();
-    if file.read_to_string(&mut new_content).is_err() {
+    let findings = scan_entries(&new_content);
+    if cfg.issue_escalation.enabled && !findings.is_empty() {
+        escalate(&cfg.issue_escalation, session_id, &new_content);
+    }
+
+    let mut pending = Pending::load(&pending_path);
+    let flushed = pending.aggregate(
+        findings.into_iter().map(|f| f.rule).collect(),
+        now_ms(),
+        cfg.aggregation_window_secs * 1000,
+        stop,
+    );
+    pending.save(&pending_path);
+    let Some(patterns) = flushed else {
         process::exit(0);
+    };
+
+    let event = if stop { Event::Stop } else { Event::PostToolUse };
+    let mut decision = Decision::new(HOOK, event)
+        .with_session(session_id)
+        .with_quiet(quiet::is_quiet(&cfg.quiet_hours));
+    for p in &patterns {
+        decision.push(cfg.doc_urls.attach(finding(p)));
     }
 
-    // Always advance the offset so we never re-scan the same content.
-    save_offset(session_id, current_size);
+    false_positive::report_if_requested(HOOK, input, decision.findings());
+    decision.emit(build_reason).exit();
+}
 
-    let mut findings = Vec::new();
-    let mut seen = HashSet::new();
+// ---------------------------------------------------------------------------
+// Watch mode
+// ---------------------------------------------------------------------------
+
+/// A transcript being followed from an in-memory offset.
+struct Tail {
+    path: PathBuf,
+    offset: u64,
+}
+
+impl Tail {
+    /// Start at the current end of the file: history before the watcher
+    /// started has already been seen (or deliberately skipped).
+    fn new(path: PathBuf) -> Self {
+        let offset = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        Tail { path, offset }
+    }
 
-    for line in new_content.lines() {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
+    /// The decision for content appended since the last poll, if it contains
+    /// any dismissals. Unfinished trailing lines wait for the next poll.
+    fn poll(&mut self, cfg: &Config) -> Option<String> {
+        let (content, size) = read_new_content(&self.path, self.offset)?;
+        let complete = match content.rfind('\n') {
+            Some(i) => &content[..=i],
+            None => "",
+        };
+        self.offset = size - (content.len() - complete.len()) as u64;
+
+        let mut decision = Decision::new(HOOK, Event::PostToolUse);
+        for f in scan_entries(complete) {
+            decision.push(cfg.doc_urls.attach(f));
         }
-        if let Ok(entry) = serde_json::from_str::<Value>(line) {
-            let text = extract_assistant_text(&entry);
-            if !text.is_empty() {
-                scan_text(&text, &mut findings, &mut seen);
+        let out = decision.emit(build_reason);
+        (!out.stdout.is_empty()).then_some(out.stdout)
+    }
+}
+
+/// Poll `tail` until `keep_going` returns false, writing each decision to
+/// `out` as it is produced. `keep_going` runs before every poll and does the
+/// waiting between them.
+fn watch(
+    tail: &mut Tail,
+    cfg: &Config,
+    out: &mut impl io::Write,
+    mut keep_going: impl FnMut() -> bool,
+) {
+    while keep_going() {
+        if let Some(line) = tail.poll(cfg) {
+            if out.write_all(line.as_bytes()).and_then(|_| out.flush()).is_err() {
+                return;
             }
         }
     }
+}
 
-    if findings.is_empty() {
-        process::exit(0);
-    }
+// ---------------------------------------------------------------------------
+// Reason
+// ---------------------------------------------------------------------------
 
-    let list = findings.join(", ");
-    let reason = format!(
+fn build_reason(findings: &[&Finding]) -> String {
+    let list = findings
+        .iter()
+        .map(|f| f.render())
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
         "Dismissal language detected in this turn: [{}]. Before moving on, \
          explicitly report to the user each issue you dismissed. For each: \
          (1) the exact symptom (error message, failing test, unexpected behavior), \
@@ -215,9 +590,7 @@ fn main() {
          a repro on main), (3) what you would investigate further if asked. \
          Be specific — the user needs to make an informed judgement call.",
         list
-    );
-
-    println!("{}", json!({"decision": "block", "reason": reason}));
+    )
 }
 
 // ---------------------------------------------------------------------------
@@ -237,7 +610,7 @@ mod tests {
             &mut findings,
             &mut seen,
         );
-        assert!(findings.iter().any(|f| f.contains("pre-existing issue")));
+        assert!(findings.iter().any(|f| f.message.contains("pre-existing issue")));
     }
 
     #[test]
@@ -249,7 +622,7 @@ mod tests {
             &mut findings,
             &mut seen,
         );
-        assert!(findings.iter().any(|f| f.contains("unrelated to this change")));
+        assert!(findings.iter().any(|f| f.message.contains("unrelated to this change")));
     }
 
     #[test]
@@ -261,7 +634,7 @@ mod tests {
             &mut findings,
             &mut seen,
         );
-        assert!(findings.iter().any(|f| f.contains("already broken on main")));
+        assert!(findings.iter().any(|f| f.message.contains("already broken on main")));
     }
 
     #[test]
@@ -298,7 +671,7 @@ mod tests {
             &mut findings,
             &mut seen,
         );
-        assert!(findings.iter().any(|f| f.contains("unrelated to this change")));
+        assert!(findings.iter().any(|f| f.message.contains("unrelated to this change")));
     }
 
     #[test]
@@ -309,7 +682,7 @@ mod tests {
         scan_text("another pre-existing issue", &mut findings, &mut seen);
         let count = findings
             .iter()
-            .filter(|f| f.contains("pre-existing issue"))
+            .filter(|f| f.message.contains("pre-existing issue"))
             .count();
         assert_eq!(count, 1);
     }
@@ -323,7 +696,7 @@ mod tests {
             &mut findings,
             &mut seen,
         );
-        assert!(findings.iter().any(|f| f.contains("not introduced by this change")));
+        assert!(findings.iter().any(|f| f.message.contains("not introduced by this change")));
     }
 
     #[test]
@@ -335,7 +708,7 @@ mod tests {
             &mut findings,
             &mut seen,
         );
-        assert!(findings.iter().any(|f| f.contains("out of scope for this")));
+        assert!(findings.iter().any(|f| f.message.contains("out of scope for this")));
     }
 
     #[test]
@@ -349,4 +722,230 @@ mod tests {
         );
         assert!(findings.is_empty());
     }
+
+    #[test]
+    fn reason_includes_configured_doc_url() {
+        let cfg: Config = toml::from_str(
+            "[doc_urls]\n\"pre-existing issue\" = \"https://wiki.example.org/triage\"\n",
+        )
+        .unwrap();
+        let mut findings = Vec::new();
+        let mut seen = HashSet::new();
+        scan_text(
+            "This is a pre-existing issue and unrelated to this change.",
+            &mut findings,
+            &mut seen,
+        );
+        let findings: Vec<Finding> = findings.into_iter().map(|f| cfg.doc_urls.attach(f)).collect();
+        let reason = build_reason(&findings.iter().collect::<Vec<_>>());
+        assert!(reason.contains("\"pre-existing issue\" (learn more: https://wiki.example.org/triage)"));
+        assert_eq!(reason.matches("learn more").count(), 1);
+    }
+
+    // -- Aggregation window ---------------------------------------------------
+
+    fn patterns(text: &str) -> Vec<String> {
+        let mut findings = Vec::new();
+        scan_text(text, &mut findings, &mut HashSet::new());
+        findings.into_iter().map(|f| f.rule).collect()
+    }
+
+    #[test]
+    fn invocations_within_window_produce_one_combined_block() {
+        let path = std::env::temp_dir().join("unrelated-issue-detector-test-window.pending.json");
+        let _ = fs::remove_file(&path);
+
+        // Two quick tool calls, each with a dismissal: nothing emitted yet.
+        let mut pending = Pending::load(&path);
+        assert_eq!(pending.aggregate(patterns("A pre-existing issue."), 10_000, 3_000, false), None);
+        pending.save(&path);
+
+        let mut pending = Pending::load(&path);
+        assert_eq!(pending.aggregate(patterns("Unrelated to this change."), 11_000, 3_000, false), None);
+        pending.save(&path);
+
+        // The next call after the window flushes both as one block.
+        let mut pending = Pending::load(&path);
+        let flushed = pending.aggregate(Vec::new(), 13_500, 3_000, false).unwrap();
+        pending.save(&path);
+        assert_eq!(flushed, vec!["pre-existing issue", "unrelated to this change"]);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn stop_flushes_before_window_closes() {
+        let mut pending = Pending::default();
+        assert_eq!(pending.aggregate(patterns("A pre-existing bug."), 0, 3_000, false), None);
+        assert_eq!(
+            pending.aggregate(Vec::new(), 500, 3_000, true),
+            Some(vec!["pre-existing bug".to_string()])
+        );
+    }
+
+    #[test]
+    fn zero_window_blocks_immediately() {
+        let mut pending = Pending::default();
+        assert!(pending.aggregate(patterns("A pre-existing bug."), 0, 0, false).is_some());
+    }
+
+    #[test]
+    fn nothing_pending_emits_nothing() {
+        let mut pending = Pending::default();
+        assert_eq!(pending.aggregate(Vec::new(), 99_000, 3_000, true), None);
+    }
+
+    #[test]
+    fn window_defaults_and_is_configurable() {
+        assert_eq!(Config::default().aggregation_window_secs, DEFAULT_WINDOW_SECS);
+        let cfg: Config = toml::from_str("aggregation_window_secs = 0").unwrap();
+        assert_eq!(cfg.aggregation_window_secs, 0);
+    }
+
+    // -- Issue escalation -----------------------------------------------------
+
+    #[test]
+    fn issue_is_due_once_when_dismissals_reach_threshold() {
+        let mut d = Dismissals::default();
+        assert!(!d.record(vec!["a pre-existing bug".to_string()], 2));
+        // The same snippet again is not a new dismissal.
+        assert!(!d.record(vec!["a pre-existing bug".to_string()], 2));
+        assert!(d.record(vec!["unrelated to this change".to_string()], 2));
+        assert!(!d.record(vec!["out of scope for this".to_string()], 2));
+        assert_eq!(d.snippets.len(), 3);
+    }
+
+    #[test]
+    fn snippets_are_the_dismissing_lines() {
+        let content = format!(
+            "{}{}",
+            assistant_line("Tests pass.\nThe lint failure is a pre-existing issue.\nDone."),
+            assistant_line(&format!("{} unrelated to this change", "x".repeat(300)))
+        );
+        let snippets = dismissal_snippets(&content);
+        assert_eq!(snippets[0], "The lint failure is a pre-existing issue.");
+        assert_eq!(snippets[1].chars().count(), SNIPPET_MAX_CHARS);
+        assert_eq!(snippets.len(), 2);
+    }
+
+    #[test]
+    fn issue_args_expand_templates() {
+        let esc: IssueEscalation = toml::from_str(
+            "enabled = true\ntitle = \"Triage {count} dismissals ({session})\"\nlabels = [\"triage\", \"session-{session}\"]\n",
+        )
+        .unwrap();
+        let args = esc.args("s1", &["a pre-existing bug".to_string(), "broken on main".to_string()]);
+        assert_eq!(
+            args,
+            vec![
+                "issue",
+                "create",
+                "--title",
+                "Triage 2 dismissals (s1)",
+                "--body",
+                "2 issues were dismissed as pre-existing or unrelated in session `s1` \
+                 (flagged by unrelated-issue-detector):\n\n\
+                 - a pre-existing bug\n- broken on main\n",
+                "--label",
+                "triage",
+                "--label",
+                "session-s1",
+            ]
+        );
+        assert_eq!(IssueEscalation::default().command, "gh");
+    }
+
+    #[test]
+    fn issue_args_redact_secrets_in_dismissals() {
+        let content = format!(
+            "{{\"type\":\"assistant\",\"message\":{{\"content\":[{{\"type\":\"text\",\"text\":\"{}\"}}]}}}}\n",
+            "The 401 with password=hunter2 and ghp_abcdefghijklmnopqrstuvwx is a pre-existing issue."
+        );
+        let snippets = dismissal_snippets(&content);
+        assert_eq!(snippets.len(), 1);
+        let args = IssueEscalation::default().args("s1", &snippets);
+        let body = &args[5];
+        assert!(!body.contains("hunter2") && !body.contains("ghp_abc"), "got: {}", body);
+        assert!(body.contains("password=[REDACTED]"), "got: {}", body);
+    }
+
+    // -- Watch mode -----------------------------------------------------------
+
+    fn assistant_line(text: &str) -> String {
+        format!(
+            "{}\n",
+            serde_json::json!({"type": "assistant", "message": {"content": [{"type": "text", "text": text}]}})
+        )
+    }
+
+    fn append(path: &Path, text: &str) {
+        use std::io::Write;
+        let mut f = fs::OpenOptions::new().append(true).open(path).unwrap();
+        f.write_all(text.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn watch_emits_one_decision_per_batch_of_appended_content() {
+        let path = std::env::temp_dir().join("unrelated-issue-detector-test-watch.jsonl");
+        fs::write(&path, assistant_line("An old pre-existing bug, already seen.")).unwrap();
+
+        let cfg = Config::default();
+        let mut tail = Tail::new(path.clone());
+        let mut out = Vec::new();
+        let mut polls = 0;
+        watch(&mut tail, &cfg, &mut out, || {
+            polls += 1;
+            match polls {
+                1 => append(&path, &assistant_line("That is unrelated to this change.")),
+                2 => append(&path, &assistant_line("Tests pass.")),
+                3 => {
+                    // A partially written line is held until it is complete.
+                    let line = assistant_line("Also a pre-existing issue.");
+                    append(&path, &line[..10]);
+                }
+                4 => {
+                    let line = assistant_line("Also a pre-existing issue.");
+                    append(&path, &line[10..]);
+                }
+                _ => return false,
+            }
+            true
+        });
+
+        let out = String::from_utf8(out).unwrap();
+        let decisions: Vec<Value> = out.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(decisions.len(), 2, "got: {}", out);
+        let first = decisions[0]["reason"].as_str().unwrap();
+        assert!(first.contains("unrelated to this change"));
+        assert!(!first.contains("pre-existing bug"), "history must not be re-reported");
+        assert!(decisions[1]["reason"].as_str().unwrap().contains("pre-existing issue"));
+    }
+
+    #[test]
+    fn tail_restarts_after_truncation() {
+        let path = std::env::temp_dir().join("unrelated-issue-detector-test-truncate.jsonl");
+        fs::write(&path, assistant_line("Some long earlier message that is fine.")).unwrap();
+        let mut tail = Tail::new(path.clone());
+        fs::write(&path, assistant_line("A pre-existing bug.")).unwrap();
+        assert!(tail.poll(&Config::default()).is_some());
+    }
+
+    // -- Snapshots ------------------------------------------------------------
+    //
+    // The full text Claude sees is part of the hook's behavior, so wording
+    // changes should show up in review. After an intentional change, run
+    // `cargo insta review` (or `INSTA_UPDATE=always cargo test`) from this
+    // crate and commit the updated files under `src/snapshots/`.
+
+    #[test]
+    fn snapshot_default_reason() {
+        let mut findings = Vec::new();
+        let mut seen = HashSet::new();
+        scan_text(
+            "That failing test is a pre-existing issue and unrelated to this change.",
+            &mut findings,
+            &mut seen,
+        );
+        let refs: Vec<&Finding> = findings.iter().collect();
+        insta::assert_snapshot!(build_reason(&refs));
+    }
 }
@@ -0,0 +1,399 @@
+//! Integration tests driving the hook binary via stdin/stdout — the actual
+//! contract Claude Code uses. Cargo builds the binary before running.
+//!
+//! Each test gets its own scratch project (with a config that blocks without
+//! batching), state directory and transcript, so the per-session offset file
+//! can be inspected between runs, and `--doctor` sees the same setup.
+
+use serde_json::{json, Value};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+const SESSION: &str = "cli-test";
+
+struct Scratch {
+    root: PathBuf,
+}
+
+impl Scratch {
+    fn new(name: &str) -> Self {
+        let root = std::env::temp_dir().join(format!(
+            "unrelated-issue-detector-cli-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("project/.claude")).unwrap();
+        fs::create_dir_all(root.join("state")).unwrap();
+        fs::write(
+            root.join("project/.claude/unrelated-issue-detector.toml"),
+            "aggregation_window_secs = 0\n",
+        )
+        .unwrap();
+        fs::write(root.join("transcript.jsonl"), "").unwrap();
+        Scratch { root }
+    }
+
+    /// Replace the project config.
+    fn config(&self, toml: &str) {
+        fs::write(self.root.join("project/.claude/unrelated-issue-detector.toml"), toml).unwrap();
+    }
+
+    fn transcript(&self) -> PathBuf {
+        self.root.join("transcript.jsonl")
+    }
+
+    fn offset_file(&self) -> PathBuf {
+        self.root
+            .join("state")
+            .join(format!("unrelated-issue-detector-{}.offset", SESSION))
+    }
+
+    fn offset(&self) -> u64 {
+        fs::read_to_string(self.offset_file())
+            .expect("offset file written")
+            .trim()
+            .parse()
+            .expect("offset is a number")
+    }
+
+    fn append(&self, text: &str) {
+        let line = json!({
+            "type": "assistant",
+            "message": {"role": "assistant", "content": [{"type": "text", "text": text}]}
+        });
+        let mut f = fs::OpenOptions::new()
+            .append(true)
+            .open(self.transcript())
+            .unwrap();
+        writeln!(f, "{}", line).unwrap();
+    }
+
+    /// The hook binary with this scratch project and state directory.
+    fn command(&self) -> Command {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_unrelated-issue-detector"));
+        cmd.current_dir(self.root.join("project"))
+            .env("CLAUDE_PROJECT_DIR", self.root.join("project"))
+            .env("HOOK_STATE_DIR", self.root.join("state"))
+            .env_remove("HOOK_MIN_BLOCK_SEVERITY")
+            .env_remove("HOOK_OPT_IN")
+            .env_remove("HOOK_REASON_FORMAT")
+            .env_remove("HOOK_REPORT_FP")
+            .env_remove("HOOK_QUIET")
+            .env_remove("HOOK_SINK_URL");
+        cmd
+    }
+
+    /// Run the hook as a PostToolUse call; returns (stdout, exit code).
+    fn run_hook(&self) -> (String, i32) {
+        self.run(self.command())
+    }
+
+    /// Run the hook reporting to the decision sink at `url`; returns stdout.
+    fn command_with_sink(&self, url: &str) -> String {
+        let mut cmd = self.command();
+        cmd.env("HOOK_SINK_URL", url);
+        self.run(cmd).0
+    }
+
+    fn run(&self, mut cmd: Command) -> (String, i32) {
+        let input = json!({
+            "session_id": SESSION,
+            "hook_event_name": "PostToolUse",
+            "transcript_path": self.transcript(),
+            "tool_name": "Bash",
+            "tool_input": {"command": "cargo test"}
+        });
+        let mut child = cmd
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("spawn hook binary");
+        child
+            .stdin
+            .as_mut()
+            .expect("stdin")
+            .write_all(input.to_string().as_bytes())
+            .expect("write stdin");
+        let out = child.wait_with_output().expect("wait");
+        (
+            String::from_utf8(out.stdout).expect("utf8 stdout"),
+            out.status.code().unwrap_or(-1),
+        )
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+fn block_reason(stdout: &str) -> String {
+    let v: Value = serde_json::from_str(stdout.trim())
+        .unwrap_or_else(|e| panic!("expected JSON on stdout, got {:?}: {}", stdout, e));
+    assert_eq!(v["decision"], "block", "got: {}", stdout);
+    v["reason"].as_str().expect("reason").to_string()
+}
+
+fn file_len(path: &Path) -> u64 {
+    fs::metadata(path).unwrap().len()
+}
+
+// ---------------------------------------------------------------------------
+// Offset file
+// ---------------------------------------------------------------------------
+
+#[test]
+fn each_dismissal_is_caught_exactly_once() {
+    let s = Scratch::new("once");
+
+    // 1. One dismissal: block, and the offset moves to the end of the transcript.
+    s.append("The failing lint check is a pre-existing issue, moving on.");
+    let (stdout, code) = s.run_hook();
+    assert_eq!(code, 0);
+    let reason = block_reason(&stdout);
+    assert!(reason.contains("pre-existing issue"), "got: {}", reason);
+    let first_end = file_len(&s.transcript());
+    assert_eq!(s.offset(), first_end);
+
+    // 2. Nothing new: no block, offset unchanged.
+    let (stdout, code) = s.run_hook();
+    assert_eq!(code, 0);
+    assert!(stdout.is_empty(), "expected no output, got: {}", stdout);
+    assert_eq!(s.offset(), first_end);
+
+    // 3. A second dismissal: block again, for the new content only.
+    s.append("That flaky test is unrelated to this change.");
+    let (stdout, code) = s.run_hook();
+    assert_eq!(code, 0);
+    let reason = block_reason(&stdout);
+    assert!(reason.contains("unrelated to this change"), "got: {}", reason);
+    assert!(!reason.contains("pre-existing issue"), "re-reported old content: {}", reason);
+    assert!(file_len(&s.transcript()) > first_end);
+    assert_eq!(s.offset(), file_len(&s.transcript()));
+}
+
+#[test]
+fn clean_content_still_advances_offset() {
+    let s = Scratch::new("clean");
+    s.append("All tests pass.");
+    let (stdout, _) = s.run_hook();
+    assert!(stdout.is_empty(), "got: {}", stdout);
+    assert_eq!(s.offset(), file_len(&s.transcript()));
+
+    // A dismissal added later is still caught: the offset only skipped clean text.
+    s.append("The warning is a pre-existing bug.");
+    let reason = block_reason(&s.run_hook().0);
+    assert!(reason.contains("pre-existing bug"), "got: {}", reason);
+}
+
+// ---------------------------------------------------------------------------
+// Ignored transcripts
+// ---------------------------------------------------------------------------
+
+#[test]
+fn ignored_transcript_is_not_processed() {
+    let s = Scratch::new("ignored");
+    s.config("aggregation_window_secs = 0\nignore_transcript_globs = [\"**/transcript.jsonl\"]\n");
+    s.append("The failing lint check is a pre-existing issue, moving on.");
+    let (stdout, code) = s.run_hook();
+    assert_eq!(code, 0);
+    assert!(stdout.is_empty(), "expected no output, got: {}", stdout);
+    assert!(!s.offset_file().exists(), "ignored transcript advanced the offset");
+
+    // Same transcript, glob no longer matching: processed as usual.
+    s.config("aggregation_window_secs = 0\nignore_transcript_globs = [\"**/scratch/*.jsonl\"]\n");
+    let reason = block_reason(&s.run_hook().0);
+    assert!(reason.contains("pre-existing issue"), "got: {}", reason);
+    assert_eq!(s.offset(), file_len(&s.transcript()));
+}
+
+// ---------------------------------------------------------------------------
+// Opt-in marker
+// ---------------------------------------------------------------------------
+
+#[test]
+fn opt_in_mode_enforces_only_with_marker() {
+    let s = Scratch::new("opt-in");
+    s.append("The failing lint check is a pre-existing issue, moving on.");
+    let opted_in = || {
+        let mut cmd = s.command();
+        cmd.env("HOOK_OPT_IN", "1");
+        s.run(cmd)
+    };
+
+    // No marker: silent, and the transcript is left for when the project opts in.
+    let (stdout, code) = opted_in();
+    assert_eq!(code, 0);
+    assert!(stdout.is_empty(), "expected no output, got: {}", stdout);
+    assert!(!s.offset_file().exists());
+
+    fs::write(s.root.join("project/.claude/hooks-enabled"), "").unwrap();
+    let reason = block_reason(&opted_in().0);
+    assert!(reason.contains("pre-existing issue"), "got: {}", reason);
+}
+
+// ---------------------------------------------------------------------------
+// Issue escalation
+// ---------------------------------------------------------------------------
+
+#[cfg(unix)]
+#[test]
+fn issue_is_created_once_after_threshold() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let s = Scratch::new("escalation");
+    // Stub `gh`: records each invocation's arguments, one per line.
+    let calls = s.root.join("gh-calls.txt");
+    let stub = s.root.join("gh-stub.sh");
+    fs::write(
+        &stub,
+        format!("#!/bin/sh\nprintf '%s\\n' \"$@\" >> '{}'\necho ---- >> '{}'\n", calls.display(), calls.display()),
+    )
+    .unwrap();
+    fs::set_permissions(&stub, fs::Permissions::from_mode(0o755)).unwrap();
+    s.config(&format!(
+        "aggregation_window_secs = 0\n[issue_escalation]\nenabled = true\nafter = 2\n\
+         labels = [\"triage\"]\ncommand = '{}'\n",
+        stub.display()
+    ));
+
+    s.append("The failing lint check is a pre-existing issue, moving on.");
+    assert!(block_reason(&s.run_hook().0).contains("pre-existing issue"));
+    assert!(!calls.exists(), "issue created before the threshold");
+
+    s.append("That flaky test is unrelated to this change.");
+    assert!(block_reason(&s.run_hook().0).contains("unrelated to this change"));
+    let recorded = fs::read_to_string(&calls).expect("stub was run");
+    let args: Vec<&str> = recorded.lines().collect();
+    assert_eq!(&args[..4], ["issue", "create", "--title", "2 dismissed issues in session cli-test"]);
+    assert!(recorded.contains("- The failing lint check is a pre-existing issue, moving on.\n"), "got: {}", recorded);
+    assert!(recorded.contains("- That flaky test is unrelated to this change.\n"), "got: {}", recorded);
+    assert!(recorded.ends_with("--label\ntriage\n----\n"), "got: {}", recorded);
+
+    // Later dismissals don't file again.
+    s.append("This is broken on main too.");
+    block_reason(&s.run_hook().0);
+    assert_eq!(fs::read_to_string(&calls).unwrap(), recorded);
+}
+
+#[test]
+fn failing_issue_command_does_not_change_the_decision() {
+    let s = Scratch::new("escalation-fail");
+    s.config(
+        "aggregation_window_secs = 0\n[issue_escalation]\nenabled = true\nafter = 1\n\
+         command = \"/nonexistent/gh\"\n",
+    );
+    s.append("The failing lint check is a pre-existing issue, moving on.");
+    let (stdout, code) = s.run_hook();
+    assert_eq!(code, 0);
+    assert!(block_reason(&stdout).contains("pre-existing issue"));
+}
+
+// ---------------------------------------------------------------------------
+// Decision sink
+// ---------------------------------------------------------------------------
+
+#[test]
+fn sink_receives_decision_and_down_sink_does_not_delay_it() {
+    use std::io::Read;
+    use std::net::TcpListener;
+    use std::time::{Duration, Instant};
+
+    let s = Scratch::new("sink");
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+    let server = std::thread::spawn(move || {
+        let (mut conn, _) = listener.accept().unwrap();
+        let mut request = String::new();
+        let _ = conn.read_to_string(&mut request);
+        request
+    });
+
+    s.append("The failing lint check is a pre-existing issue, moving on.");
+    let out = s.command_with_sink(&url);
+    assert!(block_reason(&out).contains("pre-existing issue"));
+    let request = server.join().unwrap();
+    let body: Value = serde_json::from_str(request.split_once("\r\n\r\n").unwrap().1).unwrap();
+    assert_eq!(body["hook"], "unrelated-issue-detector");
+    assert_eq!(body["session"], SESSION);
+    assert_eq!(body["decision"], "block");
+    assert!(body["summary"].as_str().unwrap().contains("pre-existing issue"));
+
+    // Nothing listens on a port whose listener was just dropped.
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    s.append("That flaky test is unrelated to this change.");
+    let start = Instant::now();
+    let out = s.command_with_sink(&format!("http://127.0.0.1:{}/", port));
+    assert!(start.elapsed() < Duration::from_secs(2), "took {:?}", start.elapsed());
+    assert!(block_reason(&out).contains("unrelated to this change"));
+}
+
+// ---------------------------------------------------------------------------
+// --pattern-test
+// ---------------------------------------------------------------------------
+
+fn pattern_test(group: &str, sample: &str) -> (String, String, i32) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_unrelated-issue-detector"))
+        .args(["--pattern-test", group])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn hook binary");
+    child.stdin.take().expect("stdin").write_all(sample.as_bytes()).expect("write stdin");
+    let out = child.wait_with_output().expect("wait");
+    (
+        String::from_utf8(out.stdout).expect("utf8 stdout"),
+        String::from_utf8(out.stderr).expect("utf8 stderr"),
+        out.status.code().unwrap_or(-1),
+    )
+}
+
+#[test]
+fn pattern_test_reports_matches_with_spans() {
+    let (stdout, _, code) = pattern_test("phrases", "This is a Pre-Existing Issue, not mine.");
+    assert_eq!(code, 0);
+    assert_eq!(stdout, "10..28     pre-existing issue       \"Pre-Existing Issue\"\n1 match\n");
+
+    let (stdout, _, code) = pattern_test("phrases", "All tests pass.");
+    assert_eq!((stdout.as_str(), code), ("no matches\n", 0));
+
+    let (_, stderr, code) = pattern_test("nope", "");
+    assert_eq!(code, 2);
+    assert!(stderr.contains("unknown pattern group `nope` (groups: phrases)"), "got: {}", stderr);
+}
+
+// ---------------------------------------------------------------------------
+// --doctor
+// ---------------------------------------------------------------------------
+
+#[test]
+fn doctor_reports_config_and_state_dir() {
+    let s = Scratch::new("doctor");
+    let out = s.command().arg("--doctor").output().expect("run --doctor");
+    assert_eq!(out.status.code(), Some(0));
+    let report = String::from_utf8(out.stdout).expect("utf8 stdout");
+
+    let config = s.root.join("project/.claude/unrelated-issue-detector.toml");
+    assert!(
+        report.contains(&format!("config: {} parses", config.display())),
+        "got: {}",
+        report
+    );
+    assert!(
+        report.contains(&format!("state dir: {} writable", s.root.join("state").display())),
+        "got: {}",
+        report
+    );
+    assert!(report.contains("patterns: "), "got: {}", report);
+
+    fs::write(&config, "aggregation_window = 0\n").unwrap();
+    let out = s.command().arg("--doctor").output().expect("run --doctor");
+    let report = String::from_utf8(out.stdout).expect("utf8 stdout");
+    assert!(report.contains("line 1: unknown field"), "got: {}", report);
+}
@@ -0,0 +1,3741 @@
+//! Analysis behind the windows-bash-guard hook: rewrites that fix common
+//! Windows+bash path pitfalls in a Bash command, and the registry of checks
+//! that note the pitfalls a rewrite can't explain. [`analyze`] runs the
+//! default checks and returns one finding per check that fires; the hook
+//! binary adds configuration, escalation and output on top.
+//!
+//! Fixes:
+//! 1. `/dev/stdin` → fd `0` in node commands (doesn't exist on Windows)
+//! 2. Backslash drive paths → forward slashes everywhere (fixes unquoted paths,
+//!    node -e escape bugs, and trailing `\"` in one pass). Where `C:` would be
+//!    misread (operands of `rsync`/`scp`/`tar`, text written to a shell
+//!    profile), they become MSYS paths (`/c/…`) instead
+//!
+//! Checks (advisory, reported via `additionalContext`):
+//! - `rsync` drive-path operands and filter files, plus trailing-slash semantics
+//! - Brace expansion (`{a,b}`) inside backslash drive paths
+//! - `git clone` destination directories given as backslash drive paths
+//! - `git -C <path>` (and `--git-dir`/`--work-tree`) global options
+//! - Path arguments to `python -m <module>`
+//! - `grep`/`sed`/`awk` pattern and script files passed via `-f`/`--file`
+//! - `dd` `if=`/`of=` operands
+//! - Go toolchain path arguments (`go build -o`, `-C`, package directories)
+//! - Scripts run by drive path (`bash C:\scripts\b.sh`, `./C:\scripts\b.sh`)
+//! - Every unquoted drive-path operand of `cp`/`mv`, source and destination
+//! - Directory variables (`HOME`, `TMPDIR`, `XDG_*`, …) assigned drive paths
+//! - Other variables given drive paths for one command (`FOO=C:\bar cmd`,
+//!   `env FOO=C:\bar cmd`)
+//! - `tar`/`scp`/`rsync` drive operands read as `host:path` remote specs
+//! - Local `.sh` scripts run via `bash`/`sh`/`./`, which fail on CRLF endings
+//! - Unquoted UNC paths (`\\server\share\f.txt`), mangled like drive paths
+//! - `chmod`/`chown` on drive paths, which also do little on NTFS
+//! - Drive-relative paths (`C:foo`), relative to drive C's current directory
+//! - Drive paths mixing `/` and `\` (`C:/src\foo`), a copy-paste artifact
+//! - Unquoted drive paths in `<<<` here-strings, which reach stdin mangled
+//! - Reserved device names (`> nul`, `touch aux.txt`) as file names
+//! - Colons inside file names (`touch report:final.txt`), NTFS stream syntax
+//! - cmd-style `%VAR%` references, which bash passes through literally
+//! - Drive paths given to file watchers (`watch`, `entr`, `nodemon`, `cargo watch`)
+//! - Search roots of `rg`, `ag`, `fd` and `ctags -R` given as drive paths
+//! - Drive paths and `;` PATH separators appended to shell profiles
+//!   (`echo 'export PATH=C:\x:$PATH' >> ~/.bashrc`)
+//! - Inline code paths (`node -e`, `python -c`, `ruby -e`, `perl -e`,
+//!   `php -r`) whose backslashes the language reads as escapes, after bash's
+//!   quoting (`node -e 'f("C:\tmp")'`)
+//! - Drive paths given to media tools (`ffmpeg`, `convert`, `magick`, `sox`)
+//! - Drive paths given to database CLIs (`sqlite3 C:\db\app.db`,
+//!   `psql -f C:\x.sql`, `mysql < C:\x.sql`)
+//! - Download targets of `curl -o`/`--output-dir` and `wget -O`/`-P` given as
+//!   backslash drive paths
+//! - Unquoted backslashes in `grep`/`sed`/`awk` patterns, which bash strips
+//!   before the regex sees them (`grep C:\Users log`, `grep \bword\b log`)
+
+use hook_common::bash::{
+    command_spans, is_assignment, is_backslash_drive_path, output_redirects, program_args, program_index,
+    program_name, raw_words, simple_commands, Word,
+};
+use hook_common::decision::{Finding, Severity};
+use serde::Deserialize;
+use std::ops::Range;
+use std::ptr;
+
+/// `[rewrites]`: the command rewrites, each on by default.
+#[derive(Debug, Deserialize)]
+#[serde(from = "RewriteKeys")]
+pub struct Rewrites {
+    /// Fix 1: `/dev/stdin` and friends → fd numbers in node commands.
+    pub dev_stdin: bool,
+    /// Fix 2: backslash drive paths → forward slashes. One pass covers
+    /// unquoted paths, `node -e` escapes and trailing `\"`.
+    pub drive_paths: bool,
+}
+
+impl Default for Rewrites {
+    fn default() -> Self {
+        Rewrites {
+            dev_stdin: true,
+            drive_paths: true,
+        }
+    }
+}
+
+/// The keys `[rewrites]` accepts. `trailing_backslash_quote`,
+/// `node_eval_backslash` and `unquoted_backslash` name the failure modes
+/// that the single `drive_paths` pass fixes, so setting any of them to
+/// `false` turns that pass off.
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RewriteKeys {
+    dev_stdin: bool,
+    drive_paths: bool,
+    trailing_backslash_quote: bool,
+    node_eval_backslash: bool,
+    unquoted_backslash: bool,
+}
+
+impl Default for RewriteKeys {
+    fn default() -> Self {
+        RewriteKeys {
+            dev_stdin: true,
+            drive_paths: true,
+            trailing_backslash_quote: true,
+            node_eval_backslash: true,
+            unquoted_backslash: true,
+        }
+    }
+}
+
+impl From<RewriteKeys> for Rewrites {
+    fn from(keys: RewriteKeys) -> Self {
+        Rewrites {
+            dev_stdin: keys.dev_stdin,
+            drive_paths: keys.drive_paths
+                && keys.trailing_backslash_quote
+                && keys.node_eval_backslash
+                && keys.unquoted_backslash,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Top-level fix orchestrator
+// ---------------------------------------------------------------------------
+
+pub struct FixResult {
+    pub command: String,
+    pub context: String,
+}
+
+impl PartialEq<&str> for FixResult {
+    fn eq(&self, other: &&str) -> bool {
+        self.command == *other
+    }
+}
+
+impl std::fmt::Debug for FixResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FixResult")
+            .field("command", &self.command)
+            .field("context", &self.context)
+            .finish()
+    }
+}
+
+/// Apply the enabled fixes to the command. Returns `Some(FixResult)` if
+/// anything changed.
+pub fn fix_command(command: &str, rewrites: &Rewrites) -> Option<FixResult> {
+    let mut result = command.to_string();
+    let mut fixes: Vec<String> = Vec::new();
+
+    // Fix 1: /dev/stdin → fd number in node commands
+    if rewrites.dev_stdin && fix_dev_stdin(&mut result) {
+        fixes.push("/dev/stdin replaced with fd number (doesn't exist on Windows)".to_string());
+    }
+
+    // Fix 2: backslash drive paths → forward slashes
+    if rewrites.drive_paths {
+        let (fixed, paths, msys) = fix_drive_paths(&result);
+        if !paths.is_empty() {
+            result = fixed;
+        }
+        let paths: Vec<String> = paths.into_iter().filter(|p| !msys.contains(p)).collect();
+        if !paths.is_empty() {
+            fixes.push(format!(
+                "backslash paths converted to forward slashes (avoids bash escape issues; found: {})",
+                paths.join(", ")
+            ));
+        }
+        if !msys.is_empty() {
+            fixes.push(format!(
+                "drive paths given to rsync/scp/tar or written to a shell profile converted to \
+                 MSYS paths (`/c/…`), since a `C:` prefix reads there as a remote host or \
+                 splits `PATH` (found: {})",
+                msys.join(", ")
+            ));
+        }
+    }
+
+    if fixes.is_empty() {
+        return None;
+    }
+
+    let context = format!(
+        "windows-bash-guard hook rewrote this command: {}. Use forward-slash paths on Windows to avoid this. To bypass rewriting, add [no-rewrite] to the Bash tool description.",
+        fixes.join("; ")
+    );
+
+    Some(FixResult { command: result, context })
+}
+
+// ---------------------------------------------------------------------------
+// Fix 1: /dev/stdin → file descriptor
+// ---------------------------------------------------------------------------
+
+/// Replace `'/dev/stdin'` → `0`, `'/dev/stdout'` → `1`, `'/dev/stderr'` → `2`
+/// in the simple commands that run node. These paths don't exist on Windows;
+/// `readFileSync(0)` reads from fd 0 (stdin) and works cross-platform. Other
+/// commands of the same line keep theirs (`curl -D '/dev/stderr' … | node x.js`).
+fn fix_dev_stdin(command: &mut String) -> bool {
+    let mut fixed = String::with_capacity(command.len());
+    let mut last = 0;
+    let mut changed = false;
+    for span in command_spans(command) {
+        let segment = &command[span.clone()];
+        if !runs_node(segment) {
+            continue;
+        }
+        let mut segment = segment.to_string();
+        for (quoted, fd) in [
+            ("'/dev/stdin'", "0"),
+            ("\"/dev/stdin\"", "0"),
+            ("'/dev/stdout'", "1"),
+            ("\"/dev/stdout\"", "1"),
+            ("'/dev/stderr'", "2"),
+            ("\"/dev/stderr\"", "2"),
+        ] {
+            if segment.contains(quoted) {
+                segment = segment.replace(quoted, fd);
+                changed = true;
+            }
+        }
+        fixed.push_str(&command[last..span.start]);
+        fixed.push_str(&segment);
+        last = span.end;
+    }
+    if changed {
+        fixed.push_str(&command[last..]);
+        *command = fixed;
+    }
+    changed
+}
+
+/// Programs that run the command after them (`npx node`, `time node`).
+const COMMAND_WRAPPERS: &[&str] = &["npx", "time", "env", "exec", "command", "nice", "xargs"];
+
+/// Whether a simple command runs node: `node` is its program, possibly after
+/// assignments and wrappers with their options (`env -i X=1 node`). A `node`
+/// anywhere else (`nodemon`, `> node_modules/x`, `cat node`) doesn't count.
+fn runs_node(segment: &str) -> bool {
+    runs_any(segment, &["node"])
+}
+
+/// Whether a simple command's program is one of `programs`, as in
+/// [`runs_node`].
+fn runs_any(segment: &str, programs: &[&str]) -> bool {
+    let commands = simple_commands(segment);
+    let Some(words) = commands.first() else {
+        return false;
+    };
+    for w in words.iter().filter(|w| !is_assignment(&w.text) && !w.text.starts_with('-')) {
+        match program_name(&w.text) {
+            name if programs.contains(&name) => return true,
+            name if COMMAND_WRAPPERS.contains(&name) => continue,
+            _ => return false,
+        }
+    }
+    false
+}
+
+// ---------------------------------------------------------------------------
+// Fix 2: Backslash drive paths → forward slashes
+// ---------------------------------------------------------------------------
+
+/// Find all Windows drive paths (`X:\...`) and convert backslashes to forward
+/// slashes. This fixes multiple failure modes in one pass:
+///
+/// - Unquoted `C:\src` → bash eats `\s` → `C:src` (fix: `C:/src`)
+/// - `"C:\path\"` → `\"` eats closing quote → EOF (fix: `"C:/path/"`)
+/// - `node -e "..C:\\src.."` → JS interprets `\s` as escape (fix: `C:/src`)
+/// - `node -e "..C:\\\\tmp.."` → multi-layer escaping hell (fix: `C:/tmp`)
+///
+/// Forward slashes work everywhere: bash, Node.js, and Windows APIs. Except
+/// in the simple commands that [`needs_msys_paths`]: there `C:/src` is still
+/// misread, so paths become `/c/src`, forward slashes included.
+///
+/// Returns the rewritten command, every distinct path that changed, as
+/// written in `command`, and those of them given MSYS form.
+fn fix_drive_paths(command: &str) -> (String, Vec<String>, Vec<String>) {
+    let mut out = String::with_capacity(command.len());
+    let mut paths: Vec<String> = Vec::new();
+    let mut msys: Vec<String> = Vec::new();
+    let mut last = 0;
+    for span in command_spans(command) {
+        out.push_str(&command[last..span.start]);
+        let segment = &command[span.clone()];
+        let as_msys = needs_msys_paths(segment);
+        let (fixed, found) = convert_drive_paths(segment, as_msys);
+        out.push_str(&fixed);
+        for path in found {
+            if as_msys && !msys.contains(&path) {
+                msys.push(path.clone());
+            }
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+        last = span.end;
+    }
+    out.push_str(&command[last..]);
+    (out, paths, msys)
+}
+
+/// [`fix_drive_paths`] for one simple command, writing `C:` as `/c` when
+/// `msys` is set. Returns the rewritten text and the paths that changed.
+fn convert_drive_paths(command: &str, msys: bool) -> (String, Vec<String>) {
+    let bytes = command.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    let mut paths: Vec<String> = Vec::new();
+
+    while i < bytes.len() {
+        // Match drive letter path: [A-Za-z]:\ at a word boundary
+        if i + 2 < bytes.len()
+            && bytes[i].is_ascii_alphabetic()
+            && bytes[i + 1] == b':'
+            && bytes[i + 2] == b'\\'
+            && (i == 0 || !bytes[i - 1].is_ascii_alphanumeric())
+        {
+            // Emit drive letter and colon, or `/c` for MSYS
+            let start = i;
+            let mut changed = false;
+            if msys {
+                out.push(b'/');
+                out.push(bytes[i].to_ascii_lowercase());
+            } else {
+                out.push(bytes[i]);
+                out.push(b':');
+            }
+            i += 2;
+            // Walk the path, converting backslash runs to single /
+            loop {
+                if i >= bytes.len() {
+                    break;
+                }
+
+                if bytes[i] == b'\\' {
+                    let run = bytes[i..].iter().take_while(|&&c| c == b'\\').count();
+                    // The last backslash before a newline continues the line;
+                    // it is kept, and the path ends there.
+                    if is_line_continuation(bytes, i + run - 1) {
+                        if run > 1 {
+                            out.push(b'/');
+                            changed = true;
+                            i += run - 1;
+                        }
+                        break;
+                    }
+                    // Consume all consecutive backslashes (1, 2, or 4)
+                    i += run;
+                    // Emit a single forward slash
+                    out.push(b'/');
+                    changed = true;
+
+                    // If next char isn't a path char, path ended
+                    // (the / is a trailing separator, which is fine)
+                    if i >= bytes.len() || !is_path_char(bytes[i]) {
+                        break;
+                    }
+                } else if is_path_char(bytes[i]) {
+                    out.push(bytes[i]);
+                    i += 1;
+                } else {
+                    break;
+                }
+            }
+            let path = &command[start..i];
+            if changed && !paths.iter().any(|p| p == path) {
+                paths.push(path.to_string());
+            }
+            continue;
+        }
+
+        out.push(bytes[i]);
+        i += 1;
+    }
+
+    match String::from_utf8(out) {
+        Ok(fixed) => (fixed, paths),
+        Err(_) => (command.to_string(), Vec::new()),
+    }
+}
+
+/// Whether the simple command `segment` takes a leading `C:` as something
+/// other than a drive: `rsync`, `scp` and `tar` read it as a remote host
+/// (see [`check_remote_host_colon`]), and in text written to a shell profile
+/// it is usually a `PATH` entry, which the colon splits.
+fn needs_msys_paths(segment: &str) -> bool {
+    runs_any(segment, REMOTE_COLON_TOOLS)
+        || simple_commands(segment)
+            .first()
+            .is_some_and(|words| !profile_targets(words).is_empty())
+}
+
+/// `path` as an MSYS path: `C:\src\a` → `/c/src/a`.
+fn msys_path(path: &str) -> String {
+    let fixed = forward_slashes(path);
+    let b = fixed.as_bytes();
+    if b.len() >= 2 && b[0].is_ascii_alphabetic() && b[1] == b':' {
+        format!("/{}{}", (b[0] as char).to_ascii_lowercase(), &fixed[2..])
+    } else {
+        fixed
+    }
+}
+
+/// `command` with the backslashes of every unquoted drive path turned into
+/// forward slashes, for block messages; `None` if there are none. Unlike
+/// [`fix_drive_paths`], quoted text is left as written and an escaped space
+/// (`C:\my\ dir`) stays escaped. Paths get MSYS form where
+/// [`fix_drive_paths`] gives it (see [`path_fix`]).
+pub fn suggest_fix(command: &str) -> Option<String> {
+    let spans = unquoted_drive_paths(command);
+    if spans.is_empty() {
+        return None;
+    }
+    let mut out = String::with_capacity(command.len());
+    let mut last = 0;
+    for span in spans {
+        out.push_str(&command[last..span.start]);
+        out.push_str(&path_fix(command, &span));
+        last = span.end;
+    }
+    out.push_str(&command[last..]);
+    Some(out)
+}
+
+/// The replacement for the drive path at `span` of `command`: its MSYS form
+/// inside a simple command that [`needs_msys_paths`], forward slashes
+/// elsewhere.
+pub fn path_fix(command: &str, span: &Range<usize>) -> String {
+    let path = &command[span.clone()];
+    let msys = command_spans(command)
+        .into_iter()
+        .find(|s| s.start <= span.start && span.end <= s.end)
+        .is_some_and(|s| needs_msys_paths(&command[s]));
+    if msys {
+        msys_path(path)
+    } else {
+        forward_slashes(path)
+    }
+}
+
+/// Byte ranges of the backslash drive paths (`C:\src\a/b`, `D:\my\ dir`)
+/// outside quotes in `command`.
+pub fn unquoted_drive_paths(command: &str) -> Vec<Range<usize>> {
+    let b = command.as_bytes();
+    let mut spans = Vec::new();
+    let mut quote: Option<u8> = None;
+    let mut i = 0;
+    while i < b.len() {
+        let c = b[i];
+        match quote {
+            Some(b'"') if c == b'\\' => i += 1,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == b'\'' || c == b'"' => quote = Some(c),
+            None if c.is_ascii_alphabetic()
+                && b.get(i + 1) == Some(&b':')
+                && b.get(i + 2) == Some(&b'\\')
+                && !is_line_continuation(b, i + 2)
+                && (i == 0 || !b[i - 1].is_ascii_alphanumeric()) =>
+            {
+                let start = i;
+                i += 2;
+                while i < b.len() {
+                    match b[i] {
+                        _ if is_line_continuation(b, i) => break,
+                        b'\\' if b.get(i + 1) == Some(&b' ') => i += 2,
+                        b'\\' | b'/' => i += 1,
+                        c if is_path_char(c) => i += 1,
+                        _ => break,
+                    }
+                }
+                spans.push(start..i);
+                continue;
+            }
+            None => {}
+        }
+        i += 1;
+    }
+    spans
+}
+
+/// A backslash-newline at `i`: bash drops both and joins the lines, so the
+/// backslash is neither a path separator nor an escape.
+fn is_line_continuation(b: &[u8], i: usize) -> bool {
+    b.get(i) == Some(&b'\\') && b.get(i + 1) == Some(&b'\n')
+}
+
+/// `path` with each run of backslashes turned into one `/`, except the
+/// escape of an escaped space.
+pub fn forward_slashes(path: &str) -> String {
+    let mut out = String::with_capacity(path.len());
+    let mut chars = path.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+        } else if chars.peek() == Some(&' ') {
+            out.push_str("\\ ");
+            chars.next();
+        } else {
+            while chars.peek() == Some(&'\\') {
+                chars.next();
+            }
+            out.push('/');
+        }
+    }
+    out
+}
+
+/// Characters that can appear within a path component (between separators).
+fn is_path_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b'~' | b'+' | b'@' | b'#')
+}
+
+// ---------------------------------------------------------------------------
+// Checks
+// ---------------------------------------------------------------------------
+
+/// A tool-specific check run against the original (un-rewritten) command. It
+/// returns an advisory note for a pitfall the generic rewrite can't explain on
+/// its own.
+pub struct Check {
+    /// Name used in config and `--list-checks`.
+    pub name: &'static str,
+    pub category: &'static str,
+    pub severity: Severity,
+    pub enabled_by_default: bool,
+    pub run: fn(&str) -> Option<String>,
+}
+
+/// Every check, in default run order.
+pub const CHECKS: &[Check] = &[
+    Check {
+        name: "rsync-paths",
+        category: "file-transfer",
+        severity: Severity::Warn,
+        enabled_by_default: true,
+        run: check_rsync_paths,
+    },
+    Check {
+        name: "brace-expansion",
+        category: "shell",
+        severity: Severity::Warn,
+        enabled_by_default: true,
+        run: check_brace_expansion,
+    },
+    Check {
+        name: "git-clone-target",
+        category: "vcs",
+        severity: Severity::Warn,
+        enabled_by_default: true,
+        run: check_git_clone_target,
+    },
+    Check {
+        name: "git-global-paths",
+        category: "vcs",
+        severity: Severity::Warn,
+        enabled_by_default: true,
+        run: check_git_global_paths,
+    },
+    Check {
+        name: "python-module-paths",
+        category: "toolchain",
+        severity: Severity::Warn,
+        enabled_by_default: true,
+        run: check_python_module_paths,
+    },
+    Check {
+        name: "pattern-file-paths",
+        category: "text",
+        severity: Severity::Warn,
+        enabled_by_default: true,
+        run: check_pattern_file_paths,
+    },
+    Check {
+        name: "dd-paths",
+        category: "disk",
+        severity: Severity::Warn,
+        enabled_by_default: true,
+        run: check_dd_paths,
+    },
+    Check {
+        name: "go-paths",
+        category: "toolchain",
+        severity: Severity::Warn,
+        enabled_by_default: true,
+        run: check_go_paths,
+    },
+    Check {
+        name: "script-paths",
+        category: "shell",
+        severity: Severity::Warn,
+        enabled_by_default: true,
+        run: check_script_paths,
+    },
+    Check {
+        name: "copy-paths",
+        category: "file-transfer",
+        severity: Severity::Warn,
+        enabled_by_default: true,
+        run: check_copy_paths,
+    },
+    Check {
+        name: "dir-env-vars",
+        category: "shell",
+        severity: Severity::Warn,
+        enabled_by_default: true,
+        run: check_dir_env_vars,
+    },
+    Check {
+        name: "env-prefix-paths",
+        category: "shell",
+        severity: Severity::Warn,
+        enabled_by_default: true,
+        run: check_env_prefix_paths,
+    },
+    Check {
+        name: "remote-host-colon",
+        category: "file-transfer",
+        severity: Severity::Warn,
+        enabled_by_default: true,
+        run: check_remote_host_colon,
+    },
+    Check {
+        name: "crlf-script",
+        category: "shell",
+        severity: Severity::Info,
+        enabled_by_default: true,
+        run: check_crlf_script,
+    },
+    Check {
+        name: "unc-paths",
+        category: "shell",
+        severity: Severity::Warn,
+        enabled_by_default: true,
+        run: check_unc_paths,
+    },
+    Check {
+        name: "permission-paths",
+        category: "shell",
+        severity: Severity::Warn,
+        enabled_by_default: true,
+        run: check_permission_paths,
+    },
+    Check {
+        name: "drive-relative-paths",
+        category: "shell",
+        severity: Severity::Warn,
+        enabled_by_default: true,
+        run: check_drive_relative_path,
+    },
+    Check {
+        name: "mixed-separators",
+        category: "shell",
+        severity: Severity::Warn,
+        enabled_by_default: true,
+        run: check_mixed_separators,
+    },
+    Check {
+        name: "here-string-paths",
+        category: "shell",
+        severity: Severity::Warn,
+        enabled_by_default: true,
+        run: check_here_string_paths,
+    },
+    Check {
+        name: "reserved-filenames",
+        category: "shell",
+        severity: Severity::Warn,
+        enabled_by_default: true,
+        run: check_reserved_filenames,
+    },
+    Check {
+        name: "colon-filenames",
+        category: "shell",
+        severity: Severity::Warn,
+        enabled_by_default: true,
+        run: check_invalid_colon_filename,
+    },
+    Check {
+        name: "windows-env-vars",
+        category: "shell",
+        severity: Severity::Warn,
+        enabled_by_default: true,
+        run: check_windows_env_var,
+    },
+    Check {
+        name: "watch-paths",
+        category: "toolchain",
+        severity: Severity::Warn,
+        enabled_by_default: true,
+        run: check_watch_paths,
+    },
+    Check {
+        name: "search-paths",
+        category: "text",
+        severity: Severity::Warn,
+        enabled_by_default: true,
+        run: check_search_paths,
+    },
+    Check {
+        name: "profile-paths",
+        category: "shell",
+        severity: Severity::Warn,
+        enabled_by_default: true,
+        run: check_profile_paths,
+    },
+    Check {
+        name: "inline-eval-paths",
+        category: "toolchain",
+        severity: Severity::Warn,
+        enabled_by_default: true,
+        run: check_inline_eval_paths,
+    },
+    Check {
+        name: "media-paths",
+        category: "media",
+        severity: Severity::Warn,
+        enabled_by_default: true,
+        run: check_media_paths,
+    },
+    Check {
+        name: "regex-backslashes",
+        category: "text",
+        severity: Severity::Warn,
+        enabled_by_default: true,
+        run: check_regex_tool_backslash,
+    },
+    Check {
+        name: "database-paths",
+        category: "toolchain",
+        severity: Severity::Warn,
+        enabled_by_default: true,
+        run: check_database_paths,
+    },
+    Check {
+        name: "download-paths",
+        category: "file-transfer",
+        severity: Severity::Warn,
+        enabled_by_default: true,
+        run: check_download_output_path,
+    },
+];
+
+/// Checks whose note only restates paths a tool-specific check already
+/// explains (`cp C:\src\a/b.txt` is both a `cp` and a mixed-separator
+/// problem).
+const OVERLAPPING_CHECKS: &[&str] = &["mixed-separators"];
+
+/// A finding for every check enabled by default that fires on `command`, in
+/// registry order. See [`analyze_with`].
+pub fn analyze(command: &str) -> Vec<Finding> {
+    let checks: Vec<&Check> = CHECKS.iter().filter(|c| c.enabled_by_default).collect();
+    analyze_with(&checks, command)
+}
+
+/// A finding for every one of `checks` that fires on `command`, in that
+/// order: the check's name as the rule, its severity and its note, minus
+/// [`OVERLAPPING_CHECKS`] whose paths all appear in another note.
+pub fn analyze_with(checks: &[&Check], command: &str) -> Vec<Finding> {
+    let fired: Vec<Finding> = checks
+        .iter()
+        .filter_map(|c| (c.run)(command).map(|note| Finding::new(c.name, c.severity, note)))
+        .collect();
+    let keep: Vec<bool> = fired
+        .iter()
+        .map(|f| !OVERLAPPING_CHECKS.contains(&f.rule.as_str()) || !covered(f, &fired))
+        .collect();
+    fired.into_iter().zip(keep).filter_map(|(f, keep)| keep.then_some(f)).collect()
+}
+
+/// Whether every path quoted in the first sentence of `finding`'s note is
+/// also quoted in the note of another fired check.
+fn covered(finding: &Finding, fired: &[Finding]) -> bool {
+    let note = finding.message.as_str();
+    let head = note.split(". ").next().unwrap_or(note);
+    let paths: Vec<String> = head
+        .split('`')
+        .skip(1)
+        .step_by(2)
+        .filter(|p| p.len() > 1)
+        .map(|p| format!("`{}`", p))
+        .collect();
+    !paths.is_empty()
+        && paths
+            .iter()
+            .all(|p| fired.iter().any(|f| f.rule != finding.rule && f.message.contains(p.as_str())))
+}
+
+/// `rsync -a C:\src\ C:\dst\` and `--exclude-from=C:\filters.txt`: bash eats
+/// the backslashes, and once the paths are fixed rsync's trailing-slash rule
+/// decides whether the directory or only its contents get copied.
+fn check_rsync_paths(command: &str) -> Option<String> {
+    let paths = drive_path_args(command, &["rsync"]);
+    if paths.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "rsync got backslash drive path(s) {}; bash strips the backslashes, including in \
+         filter files passed via `--exclude-from=`/`--include-from=`/`--files-from=`. \
+         Use MSYS paths (a `C:` prefix reads as a remote host even with forward slashes), \
+         and mind rsync's trailing-slash rule: `/c/src/` copies the contents of src, \
+         `/c/src` copies the directory itself.",
+        format_paths(&paths)
+    ))
+}
+
+/// `ls C:\src\{a,b}`: bash eats the separators, and the `\{` escapes the
+/// brace so the expansion doesn't happen either. Quoted words expand no
+/// braces at all and keep their backslashes.
+fn check_brace_expansion(command: &str) -> Option<String> {
+    let paths: Vec<String> = simple_commands(command)
+        .into_iter()
+        .flatten()
+        .filter(|w| !w.quoted)
+        .map(|w| arg_value(&w.text).to_string())
+        .filter(|t| is_backslash_drive_path(t) && has_brace_expansion(t))
+        .collect();
+    if paths.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "brace expansion in backslash drive path(s) {}: bash strips the backslashes and \
+         `\\{{` escapes the brace, so the braces are passed literally instead of expanding. \
+         Use forward slashes ({}) and keep the braces unquoted.",
+        format_paths(&paths),
+        fixed_paths(&paths)
+    ))
+}
+
+/// `git clone https://host/repo C:\repos\x`: bash strips the backslashes from
+/// the destination and git creates `C:reposx` in the current directory. The
+/// clone URL (or a local source path) is left alone, and so is a quoted
+/// destination.
+fn check_git_clone_target(command: &str) -> Option<String> {
+    /// `git` options that consume the following word.
+    const GIT_VALUE_OPTS: &[&str] = &["-C", "-c", "--git-dir", "--work-tree", "--namespace"];
+    /// `git clone` options that consume the following word.
+    const CLONE_VALUE_OPTS: &[&str] = &[
+        "-b", "--branch", "-o", "--origin", "-u", "--upload-pack", "-c", "--config",
+        "--depth", "--reference", "--reference-if-able", "--separate-git-dir", "--template",
+        "-j", "--jobs", "--shallow-since", "--shallow-exclude", "--filter",
+    ];
+
+    let mut targets = Vec::new();
+    for words in simple_commands(command) {
+        let Some(args) = program_args(&words, &["git"]) else {
+            continue;
+        };
+        let git_args = skip_options(args, GIT_VALUE_OPTS);
+        if git_args.first().map(|w| w.text.as_str()) != Some("clone") {
+            continue;
+        }
+        let mut positionals = Vec::new();
+        let mut rest = &git_args[1..];
+        while let Some((first, tail)) = skip_options(rest, CLONE_VALUE_OPTS).split_first() {
+            positionals.push(first);
+            rest = tail;
+        }
+        // A lone positional is the repository, not a destination.
+        if positionals.len() < 2 {
+            continue;
+        }
+        let target = positionals[positionals.len() - 1];
+        if !target.quoted && is_backslash_drive_path(&target.text) {
+            targets.push(target.text.clone());
+        }
+    }
+    if targets.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "git clone destination {} is a backslash drive path; bash strips the backslashes \
+         and git clones into a mangled relative directory (`{}`) instead. Use forward \
+         slashes: {}.",
+        format_paths(&targets),
+        bash_unquote(&targets[0]),
+        fixed_paths(&targets)
+    ))
+}
+
+/// `git -C C:\repo status`: git runs in `C:repo` (relative to the current
+/// directory) instead, unless the path is quoted. Only options before the subcommand are global; `-C`
+/// after it means something else (`git commit -C <commit>`).
+fn check_git_global_paths(command: &str) -> Option<String> {
+    const PATH_OPTS: &[&str] = &["-C", "--git-dir", "--work-tree"];
+    /// Other global options that consume the following word.
+    const VALUE_OPTS: &[&str] = &["-c", "--namespace", "--exec-path", "--config-env"];
+
+    let mut paths = Vec::new();
+    for words in simple_commands(command) {
+        let Some(args) = program_args(&words, &["git"]) else {
+            continue;
+        };
+        let mut i = 0;
+        while let Some(w) = args.get(i) {
+            let text = w.text.as_str();
+            if !text.starts_with('-') {
+                break;
+            }
+            let value = if PATH_OPTS.contains(&text) {
+                i += 1;
+                args.get(i).filter(|v| !v.quoted).map(|v| v.text.as_str())
+            } else if let Some((opt, value)) = text.split_once('=') {
+                (PATH_OPTS.contains(&opt) && !w.quoted).then_some(value)
+            } else {
+                if VALUE_OPTS.contains(&text) {
+                    i += 1;
+                }
+                None
+            };
+            if let Some(v) = value.filter(|v| is_backslash_drive_path(v)) {
+                if !paths.iter().any(|p| p == v) {
+                    paths.push(v.to_string());
+                }
+            }
+            i += 1;
+        }
+    }
+    if paths.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "git global option path(s) {} are backslash drive paths; bash strips the \
+         backslashes, so git runs against a mangled relative path (`{}`). Use forward \
+         slashes: {}.",
+        format_paths(&paths),
+        bash_unquote(&paths[0]),
+        fixed_paths(&paths)
+    ))
+}
+
+/// `python -m http.server --directory C:\web`: the module's unquoted
+/// path-valued arguments lose their backslashes just like any other program's.
+fn check_python_module_paths(command: &str) -> Option<String> {
+    let mut modules = Vec::new();
+    let mut paths = Vec::new();
+    for words in simple_commands(command) {
+        let Some(idx) = program_index(&words) else {
+            continue;
+        };
+        let python = program_name(&words[idx].text);
+        if !matches!(python, "python" | "python3" | "py") {
+            continue;
+        }
+        let args = &words[idx + 1..];
+        let Some(m) = args.iter().position(|w| w.text == "-m") else {
+            continue;
+        };
+        let Some(module) = args.get(m + 1) else {
+            continue;
+        };
+        let before = paths.len();
+        for w in args[m + 2..].iter().filter(|w| !w.quoted) {
+            let value = arg_value(&w.text);
+            if is_backslash_drive_path(value) && !paths.iter().any(|p| p == value) {
+                paths.push(value.to_string());
+            }
+        }
+        if paths.len() > before {
+            modules.push(format!("`{} -m {}`", python, module.text));
+        }
+    }
+    if paths.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{} got backslash drive path(s) {}; bash strips the backslashes before \
+         the module sees them. Use forward slashes: {}.",
+        modules.join(", "),
+        format_paths(&paths),
+        fixed_paths(&paths)
+    ))
+}
+
+/// `grep -f C:\patterns.txt`, `sed --file=C:\script.sed`, `awk -f C:\prog.awk`:
+/// the pattern/script file path loses its backslashes unless quoted. Only
+/// these tools are considered, since `-f` means "force" for most others
+/// (`rm -f`, `cp -f`).
+fn check_pattern_file_paths(command: &str) -> Option<String> {
+    const TOOLS: &[&str] = &["grep", "egrep", "fgrep", "sed", "awk", "gawk", "mawk"];
+
+    let mut tools: Vec<String> = Vec::new();
+    let mut paths = Vec::new();
+    for words in simple_commands(command) {
+        let Some(idx) = program_index(&words) else {
+            continue;
+        };
+        let tool = program_name(&words[idx].text);
+        if !TOOLS.contains(&tool) {
+            continue;
+        }
+        let args = &words[idx + 1..];
+        let before = paths.len();
+        for (i, w) in args.iter().enumerate() {
+            let text = w.text.as_str();
+            let value = match text {
+                "-f" | "--file" => args.get(i + 1).filter(|w| !w.quoted).map(|w| w.text.as_str()),
+                _ if w.quoted => None,
+                _ => text
+                    .strip_prefix("--file=")
+                    .or_else(|| text.strip_prefix("-f").filter(|v| !v.is_empty())),
+            };
+            if let Some(value) = value {
+                if is_backslash_drive_path(value) && !paths.iter().any(|p| p == value) {
+                    paths.push(value.to_string());
+                }
+            }
+        }
+        let tool = format!("`{}`", tool);
+        if paths.len() > before && !tools.contains(&tool) {
+            tools.push(tool);
+        }
+    }
+    if paths.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "pattern/script file {} passed via `-f`/`--file` is a backslash drive path; bash \
+         strips the backslashes, so {} can't find the file. Use forward slashes: {}.",
+        format_paths(&paths),
+        tools.join(", "),
+        fixed_paths(&paths)
+    ))
+}
+
+/// `dd if=C:\disk.img of=C:\out.img`: both unquoted operands lose their
+/// backslashes, and a mangled `of=` is written to without any confirmation.
+fn check_dd_paths(command: &str) -> Option<String> {
+    let mut paths = Vec::new();
+    for words in simple_commands(command) {
+        let Some(args) = program_args(&words, &["dd"]) else {
+            continue;
+        };
+        for w in args.iter().filter(|w| !w.quoted) {
+            let value = ["if=", "of="]
+                .iter()
+                .find_map(|op| w.text.strip_prefix(op));
+            if let Some(value) = value {
+                if is_backslash_drive_path(value) && !paths.iter().any(|p| p == value) {
+                    paths.push(value.to_string());
+                }
+            }
+        }
+    }
+    if paths.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "dd got backslash drive path(s) {} in `if=`/`of=`; bash strips the backslashes. \
+         Use forward slashes: {}. dd overwrites `of=` byte for byte with no confirmation, \
+         so double-check the target before running it.",
+        format_paths(&paths),
+        fixed_paths(&paths)
+    ))
+}
+
+/// `go build -o C:\bin\app.exe .`, `go run -C C:\proj .`, `go test C:\proj\pkg`:
+/// unquoted output files, working directories and package directories all
+/// lose their backslashes, so Go writes `C:binapp.exe` or can't find the
+/// package.
+fn check_go_paths(command: &str) -> Option<String> {
+    let mut subcommands = Vec::new();
+    let mut paths = Vec::new();
+    for words in simple_commands(command) {
+        let Some(args) = program_args(&words, &["go"]) else {
+            continue;
+        };
+        let Some(sub) = args.first() else {
+            continue;
+        };
+        let before = paths.len();
+        for w in args[1..].iter().filter(|w| !w.quoted) {
+            let value = arg_value(&w.text);
+            if is_backslash_drive_path(value) && !paths.iter().any(|p| p == value) {
+                paths.push(value.to_string());
+            }
+        }
+        let sub = format!("`go {}`", sub.text);
+        if paths.len() > before && !subcommands.contains(&sub) {
+            subcommands.push(sub);
+        }
+    }
+    if paths.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{} got backslash drive path(s) {}; bash strips the backslashes, so `-o`, `-C` and \
+         package directories point somewhere else. Use forward slashes: {}.",
+        subcommands.join(", "),
+        format_paths(&paths),
+        fixed_paths(&paths)
+    ))
+}
+
+/// `cargo watch -w C:\src`, `nodemon --watch C:\src`, `watch ls C:\src`,
+/// `… | entr cat C:\log`: watched paths and the commands watchers re-run lose
+/// their backslashes like any other, and the mistake repeats on every change.
+fn check_watch_paths(command: &str) -> Option<String> {
+    let mut tools: Vec<String> = Vec::new();
+    let mut paths: Vec<String> = Vec::new();
+    for words in simple_commands(command) {
+        let Some(idx) = program_index(&words) else {
+            continue;
+        };
+        let (tool, args) = match program_name(&words[idx].text) {
+            name @ ("watch" | "entr" | "nodemon") => (name, &words[idx + 1..]),
+            "cargo" if words.get(idx + 1).is_some_and(|w| w.text == "watch") => {
+                ("cargo watch", &words[idx + 2..])
+            }
+            _ => continue,
+        };
+        let before = paths.len();
+        for w in args.iter().filter(|w| !w.quoted) {
+            let value = arg_value(&w.text);
+            if is_backslash_drive_path(value) && !paths.iter().any(|p| p == value) {
+                paths.push(value.to_string());
+            }
+        }
+        let tool = format!("`{}`", tool);
+        if paths.len() > before && !tools.contains(&tool) {
+            tools.push(tool);
+        }
+    }
+    if paths.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{} got backslash drive path(s) {}; bash strips the backslashes, so the watcher \
+         watches (or re-runs a command on) a path that doesn't exist, again on every change. \
+         Use forward slashes: {}.",
+        tools.join(", "),
+        format_paths(&paths),
+        fixed_paths(&paths)
+    ))
+}
+
+/// `rg foo C:\src`, `fd -e rs . C:\src`, `ctags -R C:\src`: the search or
+/// index root loses its backslashes, so the tool is pointed at a path that
+/// doesn't exist.
+fn check_search_paths(command: &str) -> Option<String> {
+    let mut tools: Vec<String> = Vec::new();
+    let mut paths: Vec<String> = Vec::new();
+    for words in simple_commands(command) {
+        let Some(idx) = program_index(&words) else {
+            continue;
+        };
+        let tool = program_name(&words[idx].text);
+        if !matches!(tool, "rg" | "ag" | "fd" | "fdfind" | "ctags") {
+            continue;
+        }
+        let args = &words[idx + 1..];
+        let before = paths.len();
+        for w in args.iter().filter(|w| !w.quoted) {
+            let value = arg_value(&w.text);
+            if is_backslash_drive_path(value) && !paths.iter().any(|p| p == value) {
+                paths.push(value.to_string());
+            }
+        }
+        let tool = format!("`{}`", tool);
+        if paths.len() > before && !tools.contains(&tool) {
+            tools.push(tool);
+        }
+    }
+    if paths.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{} got backslash drive path(s) {}; bash strips the backslashes, so the search \
+         root doesn't exist and nothing is searched or indexed. Use forward slashes: {}.",
+        tools.join(", "),
+        format_paths(&paths),
+        fixed_paths(&paths)
+    ))
+}
+
+/// `ffmpeg -i C:\in.mp4 C:\out.mp4`: bash strips the backslashes from input
+/// and output alike, so the input isn't found and the output lands at a
+/// drive-relative `C:out.mp4`. Git Bash's `convert` may also be Windows'
+/// own `convert.exe`; `magick` avoids that.
+fn check_media_paths(command: &str) -> Option<String> {
+    let mut tools: Vec<String> = Vec::new();
+    let mut paths: Vec<String> = Vec::new();
+    for words in simple_commands(command) {
+        let Some(idx) = program_index(&words) else {
+            continue;
+        };
+        let tool = program_name(&words[idx].text);
+        if !matches!(tool, "ffmpeg" | "convert" | "magick" | "sox") {
+            continue;
+        }
+        let before = paths.len();
+        for w in words[idx + 1..].iter().filter(|w| !w.quoted) {
+            let value = arg_value(&w.text);
+            if is_backslash_drive_path(value) && !paths.iter().any(|p| p == value) {
+                paths.push(value.to_string());
+            }
+        }
+        let tool = format!("`{}`", tool);
+        if paths.len() > before && !tools.contains(&tool) {
+            tools.push(tool);
+        }
+    }
+    if paths.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{} got backslash drive path(s) {}; bash strips the backslashes, so inputs \
+         aren't found and outputs are written to a drive-relative path (`{}`). \
+         Use forward slashes: {}.",
+        tools.join(", "),
+        format_paths(&paths),
+        bash_unquote(&paths[paths.len() - 1]),
+        fixed_paths(&paths)
+    ))
+}
+
+/// `sqlite3 C:\db\app.db`, `psql -f C:\x.sql`, `mysql db < C:\x.sql`: bash
+/// strips the backslashes, and sqlite3 then quietly creates a new, empty
+/// database at the mangled path instead of failing.
+fn check_database_paths(command: &str) -> Option<String> {
+    let mut tools: Vec<String> = Vec::new();
+    let mut paths: Vec<String> = Vec::new();
+    // The first path given to sqlite3, which it opens as the database.
+    let mut database: Option<String> = None;
+    for words in simple_commands(command) {
+        let Some(idx) = program_index(&words) else {
+            continue;
+        };
+        let tool = program_name(&words[idx].text);
+        if !matches!(tool, "sqlite3" | "psql" | "mysql" | "mariadb") {
+            continue;
+        }
+        let before = paths.len();
+        for w in words[idx + 1..].iter().filter(|w| !w.quoted) {
+            let value = arg_value(w.text.strip_prefix('<').unwrap_or(&w.text));
+            if is_backslash_drive_path(value) && !paths.iter().any(|p| p == value) {
+                if tool == "sqlite3" && database.is_none() {
+                    database = Some(value.to_string());
+                }
+                paths.push(value.to_string());
+            }
+        }
+        let tool = format!("`{}`", tool);
+        if paths.len() > before && !tools.contains(&tool) {
+            tools.push(tool);
+        }
+    }
+    if paths.is_empty() {
+        return None;
+    }
+    let sqlite = match database {
+        Some(db) => format!(
+            ", and `sqlite3` silently creates an empty database at the mangled path (`{}`)",
+            bash_unquote(&db)
+        ),
+        None => String::new(),
+    };
+    Some(format!(
+        "{} got backslash drive path(s) {}; bash strips the backslashes, so the script \
+         or database isn't found{}. Use forward slashes: {}.",
+        tools.join(", "),
+        format_paths(&paths),
+        sqlite,
+        fixed_paths(&paths)
+    ))
+}
+
+/// `curl -o C:\d\f https://…`, `wget -O C:\d\f …`: bash strips the
+/// backslashes, so the download is written to a drive-relative `C:df` (the
+/// current directory, under a garbled name) and the intended path stays
+/// empty. curl's `-O` takes no value and is left alone.
+fn check_download_output_path(command: &str) -> Option<String> {
+    let mut tools: Vec<String> = Vec::new();
+    let mut paths: Vec<String> = Vec::new();
+    for words in simple_commands(command) {
+        let Some(idx) = program_index(&words) else {
+            continue;
+        };
+        let tool = program_name(&words[idx].text);
+        let (short, long): (&[&str], &[&str]) = match tool {
+            "curl" => (&["-o"], &["--output", "--output-dir"]),
+            "wget" => (&["-O", "-P"], &["--output-document", "--directory-prefix"]),
+            _ => continue,
+        };
+        let args = &words[idx + 1..];
+        let before = paths.len();
+        for (i, w) in args.iter().enumerate() {
+            let text = w.text.as_str();
+            let target = if short.contains(&text) || long.contains(&text) {
+                args.get(i + 1)
+                    .filter(|t| !t.quoted)
+                    .map(|t| t.text.as_str())
+            } else if w.quoted {
+                None
+            } else {
+                long.iter()
+                    .find_map(|o| text.strip_prefix(o).and_then(|rest| rest.strip_prefix('=')))
+                    .or_else(|| short.iter().find_map(|o| text.strip_prefix(o)))
+            };
+            if let Some(path) = target.filter(|p| is_backslash_drive_path(p)) {
+                if !paths.iter().any(|p| p == path) {
+                    paths.push(path.to_string());
+                }
+            }
+        }
+        let tool = format!("`{}`", tool);
+        if paths.len() > before && !tools.contains(&tool) {
+            tools.push(tool);
+        }
+    }
+    if paths.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{} writes to backslash drive path(s) {}; bash strips the backslashes, so the \
+         download lands at a drive-relative path (`{}`: the current directory, under a \
+         garbled name). Use forward slashes: {}.",
+        tools.join(", "),
+        format_paths(&paths),
+        bash_unquote(&paths[0]),
+        fixed_paths(&paths)
+    ))
+}
+
+/// `grep C:\Users log`, `sed s/\bx/y/`: bash strips unquoted backslashes from
+/// the pattern (or script) before the tool sees it. A drive path there was
+/// meant literally and needs `-F` or doubled backslashes besides quoting;
+/// anything else (`\b`, `\d`) was meant for the regex and only needs quoting.
+/// File operands are left to the other checks.
+fn check_regex_tool_backslash(command: &str) -> Option<String> {
+    // Tools and patterns holding drive paths, and holding regex escapes.
+    let mut paths: (Vec<String>, Vec<String>) = Default::default();
+    let mut escapes: (Vec<String>, Vec<String>) = Default::default();
+    for words in simple_commands(command) {
+        let Some(idx) = program_index(&words) else {
+            continue;
+        };
+        let tool = program_name(&words[idx].text);
+        // Options that give the pattern, and the other options that take a
+        // separate value.
+        let (pattern_opts, value_opts): (&[&str], &[&str]) = match tool {
+            "grep" | "egrep" | "fgrep" => (
+                &["-e", "--regexp", "-f", "--file"],
+                &["-m", "--max-count", "-A", "-B", "-C", "-d", "-D", "--label"],
+            ),
+            "sed" => (&["-e", "--expression", "-f", "--file"], &["-l", "--line-length"]),
+            "awk" | "gawk" | "mawk" => (&["-f", "--file"], &["-v", "--assign", "-F", "--field-separator"]),
+            _ => continue,
+        };
+        let args = &words[idx + 1..];
+        let explicit = args.iter().any(|w| {
+            let name = w.text.split('=').next().unwrap_or("");
+            pattern_opts.contains(&name)
+        });
+        let mut patterns: Vec<&Word> = Vec::new();
+        let mut i = 0;
+        let mut options_done = false;
+        while i < args.len() {
+            let text = args[i].text.as_str();
+            if options_done || !text.starts_with('-') || text == "-" {
+                if !explicit && patterns.is_empty() {
+                    patterns.push(&args[i]);
+                }
+                if !explicit {
+                    options_done = true;
+                }
+            } else if text == "--" {
+                options_done = true;
+            } else if matches!(text, "-e" | "--regexp" | "--expression") {
+                patterns.extend(args.get(i + 1));
+                i += 1;
+            } else if pattern_opts.contains(&text) || value_opts.contains(&text) {
+                i += 1;
+            }
+            i += 1;
+        }
+
+        for w in patterns.iter().filter(|w| !w.quoted && w.text.contains('\\')) {
+            let (tools, list) = if has_drive_path(&w.text) { &mut paths } else { &mut escapes };
+            if !list.contains(&w.text) {
+                list.push(w.text.clone());
+            }
+            let tool = format!("`{}`", tool);
+            if !tools.contains(&tool) {
+                tools.push(tool);
+            }
+        }
+    }
+    let mut notes = Vec::new();
+    if !paths.1.is_empty() {
+        notes.push(format!(
+            "{} pattern(s) {} hold a Windows path with unquoted backslashes, which bash \
+             strips (`C:\\Users` becomes `C:Users`). To match the path as written, quote \
+             it and match it literally (`grep -F 'C:\\Users'`), or double its backslashes \
+             inside single quotes for a regex.",
+            paths.0.join(", "),
+            format_paths(&paths.1)
+        ));
+    }
+    if !escapes.1.is_empty() {
+        notes.push(format!(
+            "{} pattern(s) {} have unquoted backslashes, which bash strips before the \
+             regex sees them (`\\bword\\b` becomes `bwordb`). Quote the pattern \
+             (`grep '\\bword\\b'`).",
+            escapes.0.join(", "),
+            format_paths(&escapes.1)
+        ));
+    }
+    (!notes.is_empty()).then(|| notes.join(" "))
+}
+
+/// Whether `text` contains a drive path (`C:\x`) at a word boundary, as in
+/// `s/C:\x/y/`.
+fn has_drive_path(text: &str) -> bool {
+    let b = text.as_bytes();
+    (0..b.len()).any(|i| {
+        text.is_char_boundary(i)
+            && is_backslash_drive_path(&text[i..])
+            && (i == 0 || !b[i - 1].is_ascii_alphanumeric())
+    })
+}
+
+/// An interpreter that runs code given on its command line.
+struct InlineEval {
+    /// Program names, as [`program_name`] returns them.
+    programs: &'static [&'static str],
+    /// Options whose value is the code, given as the next word.
+    options: &'static [&'static str],
+    /// Whether the options also take the code after `=` (`--eval=CODE`, and
+    /// `-e=CODE` too, since node splits any option at its first `=`).
+    equals_form: bool,
+    /// How the note names the command (`node -e`).
+    label: &'static str,
+    /// How the code arrives and what the language makes of it.
+    reads: &'static str,
+    fix: &'static str,
+    /// Whether a string literal opened by `quote` (`None` if the path isn't
+    /// in one) after the code `before` reads backslash escapes.
+    reads_escapes: fn(before: &str, quote: Option<u8>) -> bool,
+}
+
+const INLINE_EVALS: &[InlineEval] = &[
+    InlineEval {
+        programs: &["node"],
+        options: &["-e", "--eval", "-p", "--print"],
+        equals_form: true,
+        label: "node -e",
+        reads: "JavaScript with single backslashes, which JS reads as escapes (`\\t` is a tab, `\\s` is just `s`)",
+        fix: "Use forward slashes in the script (`C:/tmp/x`).",
+        reads_escapes: |_, _| true,
+    },
+    InlineEval {
+        programs: &["python", "python3", "py"],
+        options: &["-c"],
+        equals_form: false,
+        label: "python -c",
+        reads: "Python with single backslashes, which Python reads as escapes (`\\t` is a tab, `\\x` starts a hex code)",
+        fix: "Use forward slashes (`C:/tmp/x`) or a raw string (`r'C:\\tmp\\x'`).",
+        reads_escapes: |before, _| !is_python_raw_prefix(before),
+    },
+    InlineEval {
+        programs: &["ruby"],
+        options: &["-e"],
+        equals_form: false,
+        label: "ruby -e",
+        reads: "Ruby with single backslashes, which Ruby reads as escapes in double-quoted strings (`\\t` is a tab)",
+        fix: "Use forward slashes (`C:/tmp/x`) or a single-quoted Ruby string.",
+        reads_escapes: |_, quote| quote != Some(b'\''),
+    },
+    InlineEval {
+        programs: &["perl"],
+        options: &["-e", "-E"],
+        equals_form: false,
+        label: "perl -e",
+        reads: "Perl with single backslashes, which Perl reads as escapes in double-quoted strings (`\\t` is a tab)",
+        fix: "Use forward slashes (`C:/tmp/x`) or a single-quoted Perl string.",
+        reads_escapes: |_, quote| quote != Some(b'\''),
+    },
+    InlineEval {
+        programs: &["php"],
+        options: &["-r"],
+        equals_form: false,
+        label: "php -r",
+        reads: "PHP with single backslashes, which PHP reads as escapes in double-quoted strings (`\\t` is a tab)",
+        fix: "Use forward slashes (`C:/tmp/x`) or a single-quoted PHP string.",
+        reads_escapes: |_, quote| quote != Some(b'\''),
+    },
+];
+
+/// `node -e 'readFileSync("C:\tmp\x")'`, `python -c "open('C:\\tmp')"`: the
+/// code reaches the interpreter after bash's quoting, and the language reads
+/// a single backslash as an escape (`\t` is a tab). Single quotes pass
+/// backslashes through as typed; in double quotes `\\` becomes one backslash,
+/// so `"…C:\\\\src…"` arrives as `C:\\src`, which the language reads as
+/// `C:\src`. String literals that don't read escapes (Python's `r'…'`,
+/// single quotes in Ruby, Perl and PHP) are left alone.
+fn check_inline_eval_paths(command: &str) -> Option<String> {
+    let mut notes: Vec<(&InlineEval, Vec<String>)> = Vec::new();
+    for span in command_spans(command) {
+        let segment = &command[span];
+        let Some(eval) = INLINE_EVALS.iter().find(|e| runs_any(segment, e.programs)) else {
+            continue;
+        };
+        let words = raw_words(segment);
+        for (i, w) in words.iter().enumerate() {
+            let code = if eval.options.contains(w) {
+                words.get(i + 1).copied()
+            } else if eval.equals_form {
+                eval.options
+                    .iter()
+                    .find_map(|o| w.strip_prefix(*o).and_then(|rest| rest.strip_prefix('=')))
+            } else {
+                None
+            };
+            let Some(code) = code.map(bash_unquote) else {
+                continue;
+            };
+            for range in escaped_drive_paths(&code) {
+                let before = &code[..range.start];
+                let quote = before.bytes().rev().find(|&c| c == b'\'' || c == b'"');
+                let before = before.trim_end_matches(['\'', '"']);
+                if !(eval.reads_escapes)(before, quote) {
+                    continue;
+                }
+                let path = code[range].to_string();
+                match notes.iter_mut().find(|(e, _)| ptr::eq(*e, eval)) {
+                    Some((_, paths)) if paths.contains(&path) => {}
+                    Some((_, paths)) => paths.push(path),
+                    None => notes.push((eval, vec![path])),
+                }
+            }
+        }
+    }
+    if notes.is_empty() {
+        return None;
+    }
+    let problems: Vec<String> = notes
+        .iter()
+        .map(|(eval, paths)| {
+            format!(
+                "`{}` code path(s) {} reach {}.",
+                eval.label,
+                format_paths(paths),
+                eval.reads
+            )
+        })
+        .collect();
+    let fixes: Vec<&str> = notes.iter().map(|(eval, _)| eval.fix).collect();
+    Some(format!(
+        "{} Bash keeps backslashes in single quotes and turns `\\\\` into `\\` in double \
+         quotes. {}",
+        problems.join(" "),
+        fixes.join(" ")
+    ))
+}
+
+/// Whether the code `before` a quote ends in a Python raw-string prefix
+/// (`r`, `R`, `rb`, `fr`, …).
+fn is_python_raw_prefix(before: &str) -> bool {
+    let prefix = &before[before.trim_end_matches(|c: char| c.is_ascii_alphabetic()).len()..];
+    prefix.len() <= 2 && prefix.contains(['r', 'R'])
+}
+
+/// The text bash passes on for a word: single-quoted text as is; in double
+/// quotes `\` only escapes `\`, `"`, `$` and `` ` ``; unquoted, it escapes
+/// any character except a newline, which it joins to the next line.
+fn bash_unquote(word: &str) -> String {
+    let mut out = String::with_capacity(word.len());
+    let mut quote: Option<char> = None;
+    let mut chars = word.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some('"'), '\\') => match chars.peek() {
+                Some(&n @ ('\\' | '"' | '$' | '`')) => {
+                    out.push(n);
+                    chars.next();
+                }
+                _ => out.push(c),
+            },
+            (Some(_), _) => out.push(c),
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '\\') => out.extend(chars.next().filter(|&n| n != '\n')),
+            (None, _) => out.push(c),
+        }
+    }
+    out
+}
+
+/// Drive paths in source code with a backslash run of odd length, i.e. one
+/// ending in an escape rather than a literal backslash (`C:\tmp`, but not
+/// `C:\\tmp`).
+fn escaped_drive_paths(code: &str) -> Vec<Range<usize>> {
+    let b = code.as_bytes();
+    let mut paths = Vec::new();
+    let mut i = 0;
+    while i + 2 < b.len() {
+        if !(is_backslash_drive_path(&code[i..]) && (i == 0 || !b[i - 1].is_ascii_alphanumeric())) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        let mut escaped = false;
+        i += 2;
+        while i < b.len() && (b[i] == b'\\' || is_path_char(b[i])) {
+            let run = b[i..].iter().take_while(|&&c| c == b'\\').count();
+            escaped |= run % 2 == 1;
+            i += run.max(1);
+        }
+        if escaped {
+            paths.push(start..i);
+        }
+    }
+    paths
+}
+
+/// Shell startup files that every new shell sources.
+const PROFILE_FILES: &[&str] = &[".bashrc", ".bash_profile", ".zshrc", ".profile"];
+
+/// The shell profiles an `echo` or `printf` writes to, none for any other
+/// command.
+fn profile_targets(words: &[Word]) -> Vec<String> {
+    let Some(idx) = program_index(words) else {
+        return Vec::new();
+    };
+    if !matches!(program_name(&words[idx].text), "echo" | "printf") {
+        return Vec::new();
+    }
+    output_redirects(words)
+        .into_iter()
+        .map(|r| r.target)
+        .filter(|t| PROFILE_FILES.contains(&t.rsplit(['/', '\\']).next().unwrap_or(t)))
+        .collect()
+}
+
+/// `echo 'export PATH=C:\x:$PATH' >> ~/.bashrc`: the text is written as is,
+/// quotes and all, and parsed again by every new shell, which strips the
+/// backslashes then. A `;`-separated `PATH` value (`C:/a;C:/b`) ends the
+/// assignment at the `;` instead.
+fn check_profile_paths(command: &str) -> Option<String> {
+    let mut profiles: Vec<String> = Vec::new();
+    let mut problems: Vec<String> = Vec::new();
+    let mut entries: Vec<String> = Vec::new();
+    for words in simple_commands(command) {
+        let Some(idx) = program_index(&words) else {
+            continue;
+        };
+        let targets = profile_targets(&words);
+        if targets.is_empty() {
+            continue;
+        }
+        let mut content: Vec<&str> = Vec::new();
+        let mut i = idx + 1;
+        while i < words.len() {
+            let w = &words[i];
+            i += 1;
+            match redirect_target(&w.text).filter(|_| !w.quoted) {
+                Some("") => i += 1,
+                Some(_) => {}
+                None => content.push(&w.text),
+            }
+        }
+        let before = problems.len();
+        for text in content {
+            for path in embedded_drive_paths(text) {
+                if !entries.iter().any(|e| e == path) {
+                    entries.push(path.to_string());
+                }
+                let path = format!("`{}`", path);
+                if !problems.contains(&path) {
+                    problems.push(path);
+                }
+            }
+            for value in semicolon_path_values(text) {
+                for entry in value.split(';').filter(|e| e.as_bytes().get(1) == Some(&b':')) {
+                    if !entries.iter().any(|e| e == entry) {
+                        entries.push(entry.to_string());
+                    }
+                }
+                let value = format!("`{}` (`;`-separated)", value);
+                if !problems.contains(&value) {
+                    problems.push(value);
+                }
+            }
+        }
+        if problems.len() > before {
+            for t in targets.into_iter().map(|t| format!("`{}`", t)) {
+                if !profiles.contains(&t) {
+                    profiles.push(t);
+                }
+            }
+        }
+    }
+    if problems.is_empty() {
+        return None;
+    }
+    let stripped = entries
+        .iter()
+        .find(|e| e.contains('\\'))
+        .map(|e| format!(" (`{}` becomes `{}`)", e, bash_unquote(e)))
+        .unwrap_or_default();
+    let msys: Vec<String> = entries.iter().map(|e| msys_path(e)).collect();
+    let forward = entries.first().map_or_else(|| "C:/x".to_string(), |e| forward_slashes(e));
+    Some(format!(
+        "Text written to shell profile {} has {}. Every new shell sources it, so the mistake \
+         recurs on each start: backslashes are stripped{} and bash separates `PATH` entries \
+         with `:`, not `;`. Use MSYS paths (`export PATH={}:$PATH`); `{}` doesn't work in \
+         `PATH` either, since its `:` splits the entry.",
+        profiles.join(", "),
+        problems.join(", "),
+        stripped,
+        msys.join(":"),
+        forward
+    ))
+}
+
+/// Backslash drive paths inside a word (`PATH=C:\x:$PATH` → `C:\x`), each up
+/// to the next `:`, `;` or quote.
+fn embedded_drive_paths(text: &str) -> Vec<&str> {
+    let b = text.as_bytes();
+    let mut paths = Vec::new();
+    let mut i = 0;
+    while i + 2 < b.len() {
+        let starts = (i == 0 || !b[i - 1].is_ascii_alphanumeric()) && is_backslash_drive_path(&text[i..]);
+        if !starts {
+            i += 1;
+            continue;
+        }
+        let len = 2 + b[i + 2..]
+            .iter()
+            .take_while(|c| !matches!(c, b':' | b';' | b'"' | b'\'') && !c.is_ascii_whitespace())
+            .count();
+        paths.push(&text[i..i + len]);
+        i += len;
+    }
+    paths
+}
+
+/// Values of `…PATH=` assignments in `text` that use `;` as a separator.
+fn semicolon_path_values(text: &str) -> Vec<&str> {
+    let mut values = Vec::new();
+    for (pos, _) in text.match_indices("PATH=") {
+        let value = text[pos + "PATH=".len()..]
+            .trim_start_matches(['"', '\''])
+            .split(|c: char| c.is_whitespace() || c == '"' || c == '\'')
+            .next()
+            .unwrap_or("");
+        if value.contains(';') {
+            values.push(value);
+        }
+    }
+    values
+}
+
+/// `bash C:\scripts\build.sh`, `./C:\scripts\build.sh`, `C:\scripts\build.sh`:
+/// the unquoted script path loses its backslashes before the shebang is even
+/// read.
+fn check_script_paths(command: &str) -> Option<String> {
+    let mut scripts = Vec::new();
+    let mut dotted = false;
+    for words in simple_commands(command) {
+        let Some((word, via_shell)) = script_word(&words).filter(|(w, _)| !w.quoted) else {
+            continue;
+        };
+        let script = word.text.as_str();
+        let bare = if via_shell { None } else { script.strip_prefix("./") };
+        let script = bare.unwrap_or(script);
+        if is_backslash_drive_path(script) && !scripts.iter().any(|s| s == script) {
+            dotted |= bare.is_some();
+            scripts.push(script.to_string());
+        }
+    }
+    if scripts.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "Script path(s) {} are backslash drive paths; bash strips the backslashes and runs \
+         (or fails to find) `{}` instead. Use forward slashes: {}{}.",
+        format_paths(&scripts),
+        bash_unquote(&scripts[0]),
+        fixed_paths(&scripts),
+        if dotted { ", without a leading `./`" } else { "" }
+    ))
+}
+
+/// `bash deploy.sh`, `./run.sh`: a script checked out with CRLF endings fails
+/// with `/bin/bash^M: bad interpreter` or `$'\r': command not found`. The
+/// hook can't see the file's bytes, so this only fires for local relative
+/// `.sh` paths, where a Windows checkout is the likely source.
+fn check_crlf_script(command: &str) -> Option<String> {
+    let mut scripts: Vec<String> = Vec::new();
+    for words in simple_commands(command) {
+        let Some((word, via_shell)) = script_word(&words) else {
+            continue;
+        };
+        let script = word.text.as_str();
+        // A bare `deploy.sh` as the program is looked up on PATH.
+        let local = !script.starts_with(['/', '~', '$'])
+            && !script.contains(':')
+            && (via_shell || script.contains('/'));
+        if local && script.ends_with(".sh") && !scripts.iter().any(|s| s == script) {
+            scripts.push(script.to_string());
+        }
+    }
+    let first = scripts.first()?;
+    Some(format!(
+        "Running shell script(s) {}: if a script was saved or checked out with Windows CRLF \
+         line endings, bash fails with `/bin/bash^M: bad interpreter` or `$'\\r': command \
+         not found`. On that error, strip the carriage returns (`sed -i 's/\\r$//' {}`) and \
+         set `git config core.autocrlf input` so the next checkout keeps LF.",
+        format_paths(&scripts),
+        first
+    ))
+}
+
+/// `cat \\srv\share\f.txt`: the rewrite only knows drive letters, and bash
+/// unescapes the unquoted backslashes, so the command sees `\srvsharef.txt`.
+fn check_unc_paths(command: &str) -> Option<String> {
+    let mut paths: Vec<String> = Vec::new();
+    for w in simple_commands(command).into_iter().flatten().filter(|w| !w.quoted) {
+        let value = arg_value(&w.text);
+        if is_unc_path(value) && !paths.iter().any(|p| p == value) {
+            paths.push(value.to_string());
+        }
+    }
+    if paths.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "UNC path(s) {} are unquoted; bash reads `\\\\` as one \
+         backslash and drops the rest, so `\\\\server\\share\\f.txt` becomes `\\serversharef.txt`. Use forward slashes (`//server/share/f.txt`) or quote the \
+         path (`'\\\\server\\share'` or `\"\\\\\\\\server\\\\share\"`).",
+        format_paths(&paths)
+    ))
+}
+
+/// `chmod -R 755 C:\project`: the backslash form is mangled like any other
+/// operand, and even `C:/project` is a Windows path, where chmod/chown barely
+/// apply (NTFS uses ACLs; git-bash only emulates the executable bit).
+fn check_permission_paths(command: &str) -> Option<String> {
+    let mut paths: Vec<String> = Vec::new();
+    let mut mangled = false;
+    for words in simple_commands(command) {
+        let Some(args) = program_args(&words, &["chmod", "chown", "chgrp"]) else {
+            continue;
+        };
+        for w in args {
+            let b = w.text.as_bytes();
+            let drive = b.len() >= 3
+                && b[0].is_ascii_alphabetic()
+                && b[1] == b':'
+                && matches!(b[2], b'\\' | b'/');
+            if drive && !paths.contains(&w.text) {
+                mangled |= !w.quoted && is_backslash_drive_path(&w.text);
+                paths.push(w.text.clone());
+            }
+        }
+    }
+    if paths.is_empty() {
+        return None;
+    }
+    let mut note = format!("chmod/chown target(s) {} are Windows drive paths. ", format_paths(&paths));
+    if mangled {
+        note.push_str(
+            "Unquoted, bash strips the backslashes, so `C:\\project` becomes `C:project`; use \
+             forward slashes. ",
+        );
+    }
+    note.push_str(
+        "Even then, Windows permissions are NTFS ACLs: chmod only toggles git-bash's emulated \
+         read-only and executable bits and chown is a no-op, so a permission error on Windows \
+         needs `icacls` (or `git update-index --chmod=+x` for a script's executable bit in git).",
+    );
+    Some(note)
+}
+
+/// `cat C:foo.txt`: with no separator after the colon, Windows resolves the
+/// path against drive C's current directory, whatever that is for the
+/// process. Arguments of tools that take `host:path` (scp, rsync, ssh, …) are
+/// remote specs, not paths, and are left alone.
+fn check_drive_relative_path(command: &str) -> Option<String> {
+    const REMOTE_TOOLS: &[&str] = &["scp", "rsync", "ssh", "sftp", "tar"];
+
+    let mut paths: Vec<String> = Vec::new();
+    for words in simple_commands(command) {
+        let Some(idx) = program_index(&words) else {
+            continue;
+        };
+        if REMOTE_TOOLS.contains(&program_name(&words[idx].text)) {
+            continue;
+        }
+        for w in words[idx..].iter().filter(|w| !w.quoted) {
+            let value = arg_value(&w.text);
+            if is_drive_relative(value) && !paths.iter().any(|p| p == value) {
+                paths.push(value.to_string());
+            }
+        }
+    }
+    if paths.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "Drive-relative path(s) {}: with no slash after the colon, `C:foo` means `foo` in the \
+         current directory of drive C, which depends on the process and is rarely what was \
+         meant. Use an absolute path (`C:/foo` or `/c/foo`).",
+        format_paths(&paths)
+    ))
+}
+
+/// `C:foo`: a drive letter and colon with no separator after them.
+pub fn is_drive_relative(path: &str) -> bool {
+    let b = path.as_bytes();
+    b.len() >= 3 && b[0].is_ascii_alphabetic() && b[1] == b':' && b[2].is_ascii_alphanumeric()
+}
+
+/// `C:/src\foo`: a drive path using both separators. A backslash before a
+/// space or tab is an escaped space, not a separator.
+pub fn has_mixed_separators(path: &str) -> bool {
+    let b = path.as_bytes();
+    let drive = b.len() >= 3 && b[0].is_ascii_alphabetic() && b[1] == b':' && matches!(b[2], b'\\' | b'/');
+    let separator = |i: usize| b[i] == b'\\' && !matches!(b.get(i + 1), Some(b' ' | b'\t'));
+    drive && b.contains(&b'/') && (2..b.len()).any(separator)
+}
+
+/// `cat C:/src\foo`: half-converted paths work in some tools and not others,
+/// and unquoted, bash eats the backslashes. A backslash before a space or tab
+/// is an escaped space (`C:/my\ dir`), not a separator.
+fn check_mixed_separators(command: &str) -> Option<String> {
+    let mut paths: Vec<String> = Vec::new();
+    for w in simple_commands(command).into_iter().flatten().filter(|w| !w.quoted) {
+        let value = arg_value(&w.text);
+        if has_mixed_separators(value) && !paths.iter().any(|p| p == value) {
+            paths.push(value.to_string());
+        }
+    }
+    if paths.is_empty() {
+        return None;
+    }
+    let fixed: Vec<String> = paths.iter().map(|p| p.replace('\\', "/")).collect();
+    Some(format!(
+        "Path(s) {} mix `/` and `\\` separators. Tools disagree on such paths, and \
+         unquoted, bash drops the backslashes. Normalize to forward slashes: {}.",
+        format_paths(&paths),
+        format_paths(&fixed)
+    ))
+}
+
+/// `node read.js <<< C:\data\x`: a here-string word is expanded like any
+/// other, so unquoted its backslashes are gone before the program reads
+/// stdin. The word may follow `<<<` or be attached to it (`<<<C:\data\x`).
+fn check_here_string_paths(command: &str) -> Option<String> {
+    let mut paths: Vec<String> = Vec::new();
+    for words in simple_commands(command) {
+        let mut i = 0;
+        while i < words.len() {
+            let here = match words[i].text.strip_prefix("<<<") {
+                Some("") => words.get(i + 1),
+                Some(_) => Some(&words[i]),
+                None => None,
+            };
+            i += 1;
+            let Some(w) = here.filter(|w| !w.quoted) else {
+                continue;
+            };
+            let value = w.text.strip_prefix("<<<").unwrap_or(&w.text);
+            if is_backslash_drive_path(value) && !paths.iter().any(|p| p == value) {
+                paths.push(value.to_string());
+            }
+        }
+    }
+    if paths.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "Here-string path(s) {} are unquoted, so bash strips the backslashes before the \
+         text reaches stdin (`{}` arrives as `{}`). Quote the here-string (`<<< '{}'`) or \
+         use forward slashes (`<<< {}`).",
+        format_paths(&paths),
+        paths[0],
+        bash_unquote(&paths[0]),
+        paths[0],
+        forward_slashes(&paths[0])
+    ))
+}
+
+/// `echo hi > nul`: `CON`, `PRN`, `AUX`, `NUL`, `COM1`–`COM9` and `LPT1`–`LPT9`
+/// are device names on Windows, with or without an extension. Redirecting to
+/// `nul` from bash leaves a file that Explorer and most tools can't delete,
+/// when `/dev/null` was meant. Arguments of `echo`/`printf` are text, so only
+/// their redirect targets count.
+fn check_reserved_filenames(command: &str) -> Option<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut null_redirect = false;
+    for words in simple_commands(command) {
+        let Some(idx) = program_index(&words) else {
+            continue;
+        };
+        let text_only = matches!(program_name(&words[idx].text), "echo" | "printf");
+        let mut i = idx + 1;
+        while i < words.len() {
+            let w = &words[i];
+            i += 1;
+            let (target, redirect) = match redirect_target(&w.text).filter(|_| !w.quoted) {
+                Some("") => match words.get(i) {
+                    Some(next) => {
+                        i += 1;
+                        (next.text.as_str(), true)
+                    }
+                    None => continue,
+                },
+                Some(rest) => (rest, true),
+                None if text_only => continue,
+                None => (arg_value(&w.text), false),
+            };
+            if is_reserved_filename(target) {
+                null_redirect |= redirect && target.eq_ignore_ascii_case("nul");
+                if !names.iter().any(|n| n == target) {
+                    names.push(target.to_string());
+                }
+            }
+        }
+    }
+    if names.is_empty() {
+        return None;
+    }
+    let hint = if null_redirect {
+        " To discard output, redirect to `/dev/null` (`> /dev/null 2>&1`), which Git Bash maps to NUL."
+    } else {
+        ""
+    };
+    Some(format!(
+        "File name(s) {} are reserved Windows device names (CON, PRN, AUX, NUL, COM1-9, LPT1-9; an \
+         extension doesn't help). Creating such a file fails, hangs, or leaves one that \
+         Windows tools can't open or delete. Pick another name.{}",
+        format_paths(&names),
+        hint
+    ))
+}
+
+/// `touch report:final.txt`: on NTFS, `name:stream` opens the alternate data
+/// stream `stream` of `name`, so the file shows up as an empty `report`.
+/// Only words that are file names for sure are checked (operands of commands
+/// that create or move files, redirect targets, git pathspecs after `--`):
+/// elsewhere `a:b` is more often `host:port`, `user:group` or `rev:path`.
+fn check_invalid_colon_filename(command: &str) -> Option<String> {
+    const FILE_COMMANDS: &[&str] = &["touch", "mkdir", "cp", "mv", "tee", "ln", "install"];
+
+    let mut names: Vec<String> = Vec::new();
+    for words in simple_commands(command) {
+        let Some(idx) = program_index(&words) else {
+            continue;
+        };
+        let program = program_name(&words[idx].text);
+        let mut operands = FILE_COMMANDS.contains(&program);
+        let mut i = idx + 1;
+        while i < words.len() {
+            let w = &words[i];
+            i += 1;
+            let name = match redirect_target(&w.text).filter(|_| !w.quoted) {
+                Some("") => match words.get(i) {
+                    Some(next) => {
+                        i += 1;
+                        next.text.as_str()
+                    }
+                    None => continue,
+                },
+                Some(target) => target,
+                None if w.text == "--" => {
+                    operands |= program == "git";
+                    continue;
+                }
+                None if operands && !w.text.starts_with('-') => w.text.as_str(),
+                None => continue,
+            };
+            if has_stream_colon(name) && !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    if names.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "File name(s) {} contain a colon. On NTFS `name:stream` writes to a hidden \
+         alternate data stream of `name` instead of creating the file you see, and \
+         most Windows tools reject it. Remove the colon (e.g. `report-final.txt`).",
+        format_paths(&names)
+    ))
+}
+
+/// A colon with an alphanumeric character on each side, other than the one
+/// of a leading drive letter (`C:foo`).
+fn has_stream_colon(name: &str) -> bool {
+    let b = name.as_bytes();
+    (1..b.len().saturating_sub(1)).any(|i| {
+        b[i] == b':'
+            && b[i - 1].is_ascii_alphanumeric()
+            && b[i + 1].is_ascii_alphanumeric()
+            && !(i == 1 && b[0].is_ascii_alphabetic())
+    })
+}
+
+/// `cat %TEMP%\x`: bash doesn't expand cmd's `%NAME%`, so the command gets the
+/// literal text. Single quotes make it literal on purpose and are skipped;
+/// names need two letters, so printf/date formats (`%s`, `%Y%m%d`) pass.
+fn check_windows_env_var(command: &str) -> Option<String> {
+    let b = command.as_bytes();
+    let mut vars: Vec<String> = Vec::new();
+    let (mut single, mut double) = (false, false);
+    let mut i = 0;
+    while i < b.len() {
+        match b[i] {
+            b'\\' if !single => i += 1,
+            b'\'' if !double => single = !single,
+            b'"' if !single => double = !double,
+            b'%' if !single => {
+                let name_len = b[i + 1..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || **c == b'_')
+                    .count();
+                let name = &command[i + 1..i + 1 + name_len];
+                let closed = b.get(i + 1 + name_len) == Some(&b'%');
+                if closed && name.bytes().filter(u8::is_ascii_alphabetic).count() >= 2 {
+                    if !vars.iter().any(|v| v == name) {
+                        vars.push(name.to_string());
+                    }
+                    i += name_len + 1;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    if vars.is_empty() {
+        return None;
+    }
+    let found: Vec<String> = vars.iter().map(|v| format!("%{}%", v)).collect();
+    let fixed: Vec<String> = vars.iter().map(|v| format!("\"${}\"", v)).collect();
+    Some(format!(
+        "{} use cmd.exe syntax, which bash doesn't expand: the text is passed through as is. \
+         Use {} instead (quoted, since Windows values often contain spaces).",
+        format_paths(&found),
+        format_paths(&fixed)
+    ))
+}
+
+/// The target of a redirect word: `Some("")` for a bare operator (`>`, `2>>`,
+/// `&>`) whose target is the next word, `Some(target)` when attached
+/// (`>nul`), `None` if the word isn't a redirect.
+fn redirect_target(text: &str) -> Option<&str> {
+    let rest = text.trim_start_matches(|c: char| c.is_ascii_digit());
+    ["&>>", "&>", ">>", ">|", ">&", ">", "<"]
+        .iter()
+        .find_map(|op| rest.strip_prefix(op))
+        .filter(|target| !target.starts_with(['<', '>']))
+}
+
+/// Whether the last component of `path`, minus any extension, is a Windows
+/// device name.
+fn is_reserved_filename(path: &str) -> bool {
+    let base = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    let stem = base.split('.').next().unwrap_or(base).to_ascii_uppercase();
+    match stem.as_bytes() {
+        b"CON" | b"PRN" | b"AUX" | b"NUL" => true,
+        [b'C', b'O', b'M', n] | [b'L', b'P', b'T', n] => (b'1'..=b'9').contains(n),
+        _ => false,
+    }
+}
+
+/// `\\host\share…`: two backslashes, a host name, and a share separator.
+fn is_unc_path(text: &str) -> bool {
+    let Some(rest) = text.strip_prefix("\\\\") else {
+        return false;
+    };
+    let host_len = rest
+        .bytes()
+        .take_while(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_'))
+        .count();
+    host_len > 0 && rest[host_len..].starts_with('\\')
+}
+
+/// The script a simple command runs, and whether it is run through `bash`/`sh`:
+/// their script operand (none with `-c`, which takes a command string), or
+/// else the program word itself.
+fn script_word(words: &[Word]) -> Option<(&Word, bool)> {
+    /// Shell options that consume the following word.
+    const SHELL_VALUE_OPTS: &[&str] = &["-o", "+o", "-O", "+O", "--rcfile", "--init-file"];
+
+    let idx = program_index(words)?;
+    let program = &words[idx];
+    if !matches!(program_name(&program.text), "bash" | "sh") {
+        return Some((program, false));
+    }
+    let args = &words[idx + 1..];
+    if args.iter().any(|w| w.text == "-c") {
+        return None;
+    }
+    skip_options(args, SHELL_VALUE_OPTS).first().map(|w| (w, true))
+}
+
+/// `cp -r C:\src\a C:\dst\b`: bash strips the backslashes from every unquoted
+/// operand, so source and destination are both mangled. Each one is reported;
+/// quoted operands keep their backslashes and are fine.
+fn check_copy_paths(command: &str) -> Option<String> {
+    let mut tools: Vec<String> = Vec::new();
+    let mut paths: Vec<String> = Vec::new();
+    for words in simple_commands(command) {
+        let Some(idx) = program_index(&words) else {
+            continue;
+        };
+        let tool = program_name(&words[idx].text);
+        if !matches!(tool, "cp" | "mv") {
+            continue;
+        }
+        let before = paths.len();
+        for w in words[idx + 1..].iter().filter(|w| !w.quoted) {
+            let value = arg_value(&w.text);
+            if is_backslash_drive_path(value) && !paths.iter().any(|p| p == value) {
+                paths.push(value.to_string());
+            }
+        }
+        let tool = format!("`{}`", tool);
+        if paths.len() > before && !tools.contains(&tool) {
+            tools.push(tool);
+        }
+    }
+    if paths.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{} got unquoted backslash drive path(s) {}; bash strips the backslashes from \
+         each one, so `{}` becomes `{}`, on the source and the destination side alike. \
+         Use forward slashes ({}) or quote every path.",
+        tools.join(", "),
+        format_paths(&paths),
+        paths[0],
+        bash_unquote(&paths[0]),
+        fixed_paths(&paths)
+    ))
+}
+
+/// `TMPDIR=C:\temp cargo build`, `export HOME=C:\Users\me`: the value loses
+/// its backslashes, so every tool that reads the variable gets `C:temp`, a
+/// directory relative to wherever it happens to run.
+fn check_dir_env_vars(command: &str) -> Option<String> {
+    /// Builtins whose arguments are assignments.
+    const DECLARERS: &[&str] = &["export", "declare", "typeset", "local", "readonly", "env"];
+
+    let mut hits: Vec<String> = Vec::new();
+    for words in simple_commands(command) {
+        let declares = words
+            .iter()
+            .find(|w| !is_assignment(&w.text))
+            .is_some_and(|w| DECLARERS.contains(&program_name(&w.text)));
+        for w in &words {
+            if !is_assignment(&w.text) {
+                if declares {
+                    continue;
+                }
+                break;
+            }
+            let (name, value) = w.text.split_once('=').unwrap_or_default();
+            if is_dir_var(name) && !w.quoted && is_backslash_drive_path(value) && !hits.contains(&w.text) {
+                hits.push(w.text.clone());
+            }
+        }
+    }
+    if hits.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "Directory variable(s) {} set to unquoted backslash drive paths; bash strips the \
+         backslashes, so tools that create files there (temp files, caches, config) write \
+         to a mangled relative path like `{}` in the current directory instead. Use \
+         forward slashes: {}.",
+        format_paths(&hits),
+        bash_unquote(hits[0].split_once('=').map_or("", |(_, value)| value)),
+        fixed_paths(&hits)
+    ))
+}
+
+/// Programs that read a colon before any slash as `host:path`.
+const REMOTE_COLON_TOOLS: &[&str] = &["tar", "scp", "rsync"];
+
+/// `tar -cf a.tar C:\src`, `scp C:\f.txt h:`: tar, scp and rsync read a
+/// colon before any slash as `host:path`, so `C:` names a remote host `C`
+/// and the copy fails with "Cannot connect" or "Could not resolve hostname".
+/// Forward slashes don't help (`C:/src` is host `C`, path `/src`). A bare
+/// `C:` counts only with an uppercase letter, since `h:` is a plausible
+/// host.
+fn check_remote_host_colon(command: &str) -> Option<String> {
+    let mut paths: Vec<String> = Vec::new();
+    let mut tar = false;
+    for words in simple_commands(command) {
+        let Some(args) = program_args(&words, REMOTE_COLON_TOOLS) else {
+            continue;
+        };
+        let program = program_index(&words).map(|i| program_name(&words[i].text));
+        for w in args {
+            let value = arg_value(&w.text);
+            let b = value.as_bytes();
+            let drive = match b {
+                [letter, b':'] => letter.is_ascii_uppercase(),
+                [letter, b':', b'\\', ..] => letter.is_ascii_alphabetic(),
+                _ => false,
+            };
+            if drive && !value.contains("//") && !paths.iter().any(|p| p == value) {
+                tar |= program == Some("tar");
+                paths.push(value.to_string());
+            }
+        }
+    }
+    if paths.is_empty() {
+        return None;
+    }
+    let tar_hint = if tar {
+        " For tar, add `--force-local` so it treats names with a colon as local files."
+    } else {
+        ""
+    };
+    let msys: Vec<String> = paths.iter().map(|p| msys_path(p)).collect();
+    Some(format!(
+        "Drive path(s) {} are read as remote `host:path` specs: tar, scp and rsync take the \
+         `C:` prefix as a host named `C` and fail to connect, and forward slashes don't \
+         change that. Use MSYS paths ({}) or a relative path.{}",
+        format_paths(&paths),
+        format_paths(&msys),
+        tar_hint
+    ))
+}
+
+/// Variables that name directories tools write into.
+fn is_dir_var(name: &str) -> bool {
+    const DIR_VARS: &[&str] = &["HOME", "TMPDIR", "TMP", "TEMP", "CARGO_HOME", "GOPATH"];
+    DIR_VARS.contains(&name) || name.starts_with("XDG_")
+}
+
+/// `FOO=C:\bar cmd`, `A=1 B=C:\x cmd`, `env FOO=C:\bar cmd`: the value
+/// reaches the command as `C:bar`. Directory variables are left to
+/// `dir-env-vars`; plain assignments with no command after them are not
+/// judged.
+fn check_env_prefix_paths(command: &str) -> Option<String> {
+    let mut hits: Vec<String> = Vec::new();
+    for words in simple_commands(command) {
+        let mut assignments: Vec<&Word> = Vec::new();
+        let mut rest = &words[..];
+        loop {
+            let n = rest.iter().take_while(|w| is_assignment(&w.text)).count();
+            assignments.extend(&rest[..n]);
+            rest = &rest[n..];
+            // `env [-i] [-u NAME] NAME=value… cmd` adds a second run of them.
+            match rest.first() {
+                Some(w) if program_name(&w.text) == "env" => {
+                    rest = skip_options(&rest[1..], &["-u", "--unset", "-C", "--chdir", "-S"]);
+                }
+                _ => break,
+            }
+        }
+        if rest.is_empty() {
+            continue;
+        }
+        for w in assignments {
+            let (name, value) = w.text.split_once('=').unwrap_or_default();
+            if !is_dir_var(name) && !w.quoted && is_backslash_drive_path(value) && !hits.contains(&w.text) {
+                hits.push(w.text.clone());
+            }
+        }
+    }
+    if hits.is_empty() {
+        return None;
+    }
+    let (name, value) = hits[0].split_once('=').unwrap_or_default();
+    Some(format!(
+        "Command-prefix assignment(s) {} use unquoted backslash drive paths; bash strips the \
+         backslashes before the command sees the value, so `{}` arrives as `{}`. Use forward \
+         slashes ({}) or quote the value (`{}=\"{}\"`).",
+        format_paths(&hits),
+        hits[0],
+        bash_unquote(&hits[0]),
+        fixed_paths(&hits),
+        name,
+        value
+    ))
+}
+
+/// `args` from the first non-option word onward, skipping leading options and
+/// the value following any option in `value_opts`.
+fn skip_options<'a>(args: &'a [Word], value_opts: &[&str]) -> &'a [Word] {
+    let mut i = 0;
+    while i < args.len() {
+        let text = args[i].text.as_str();
+        if text == "--" {
+            return &args[i + 1..];
+        }
+        if !text.starts_with('-') || text.len() == 1 {
+            break;
+        }
+        i += if value_opts.contains(&text) { 2 } else { 1 };
+    }
+    &args[i.min(args.len())..]
+}
+
+/// `{a,b}` or `{1..3}` somewhere in the word.
+fn has_brace_expansion(text: &str) -> bool {
+    let Some(open) = text.find('{') else {
+        return false;
+    };
+    let Some(close) = text[open..].find('}') else {
+        return false;
+    };
+    let inner = &text[open + 1..open + close];
+    inner.contains(',') || inner.contains("..")
+}
+
+/// Backslash drive paths appearing as unquoted arguments (or `--flag=`
+/// values) of any invocation of one of `programs`. Quoted ones keep their
+/// backslashes.
+fn drive_path_args(command: &str, programs: &[&str]) -> Vec<String> {
+    let mut paths = Vec::new();
+    for words in simple_commands(command) {
+        let Some(args) = program_args(&words, programs) else {
+            continue;
+        };
+        for w in args.iter().filter(|w| !w.quoted) {
+            let value = arg_value(&w.text);
+            if is_backslash_drive_path(value) && !paths.iter().any(|p| p == value) {
+                paths.push(value.to_string());
+            }
+        }
+    }
+    paths
+}
+
+fn format_paths(paths: &[String]) -> String {
+    paths
+        .iter()
+        .map(|p| format!("`{}`", p))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// `paths` with forward slashes, formatted like [`format_paths`]: the
+/// suggestion a note makes for the paths it reports.
+fn fixed_paths(paths: &[String]) -> String {
+    format_paths(&paths.iter().map(|p| forward_slashes(p)).collect::<Vec<_>>())
+}
+
+/// Value part of `--flag=value` / `-f=value`; the whole text otherwise.
+fn arg_value(text: &str) -> &str {
+    if text.starts_with('-') {
+        if let Some(eq) = text.find('=') {
+            return &text[eq + 1..];
+        }
+    }
+    text
+}
+
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Notes from the default check set.
+    fn notes(command: &str) -> Vec<String> {
+        analyze(command).into_iter().map(|f| f.message).collect()
+    }
+
+    // -- Fix 1: /dev/stdin ---------------------------------------------------
+
+    #[test]
+    fn fixes_dev_stdin_single_quotes() {
+        let cmd =
+            r#"cat data.json | node -e "JSON.parse(require('fs').readFileSync('/dev/stdin','utf8'))""#;
+        let fixed = fix_command(cmd, &Rewrites::default()).unwrap();
+        assert!(fixed.command.contains("readFileSync(0,"));
+        assert!(!fixed.command.contains("/dev/stdin"));
+    }
+
+    #[test]
+    fn fixes_dev_stdin_double_quotes() {
+        let cmd =
+            r#"curl -s url | node -e 'JSON.parse(require("fs").readFileSync("/dev/stdin","utf8"))'"#;
+        let fixed = fix_command(cmd, &Rewrites::default()).unwrap();
+        assert!(fixed.command.contains("readFileSync(0,"));
+    }
+
+    #[test]
+    fn ignores_dev_stdin_without_node() {
+        // curl -D /dev/stderr works in MSYS2, don't touch it
+        let cmd = "curl -s -D /dev/stderr http://localhost:3000/api";
+        assert!(fix_command(cmd, &Rewrites::default()).is_none());
+    }
+
+    #[test]
+    fn dev_stdin_is_fixed_only_in_the_node_segment() {
+        let cmd = r#"cd C:/ok && node -e "require('fs').readFileSync('/dev/stdin','utf8')""#;
+        let fixed = fix_command(cmd, &Rewrites::default()).unwrap();
+        assert_eq!(fixed, r#"cd C:/ok && node -e "require('fs').readFileSync(0,'utf8')""#);
+
+        // curl's `/dev/stderr` works in MSYS2; only node's is rewritten.
+        let cmd = r#"curl -D '/dev/stderr' url | node -e "x('/dev/stdin')" ; tee '/dev/stderr' < f"#;
+        let fixed = fix_command(cmd, &Rewrites::default()).unwrap();
+        assert_eq!(fixed, r#"curl -D '/dev/stderr' url | node -e "x(0)" ; tee '/dev/stderr' < f"#);
+
+        let cmd = r#"node build.js && python -c "open('/dev/stdin')""#;
+        assert!(fix_command(cmd, &Rewrites::default()).is_none());
+        let cmd = r#"echo hi | npx node -e "x('/dev/stdin')""#;
+        assert!(fix_command(cmd, &Rewrites::default()).unwrap().command.contains("x(0)"));
+    }
+
+    #[test]
+    fn node_is_only_matched_as_the_program() {
+        for cmd in [
+            "cat '/dev/stdin' > node_modules/x",
+            "cat /dev/stdin > node_modules/x",
+            r#"nodemon -e "x('/dev/stdin')""#,
+            "tee '/dev/stderr' < node",
+            r#"anode -e "x('/dev/stdin')""#,
+        ] {
+            assert!(fix_command(cmd, &Rewrites::default()).is_none(), "rewrote: {}", cmd);
+        }
+        let cmd = r#"cat x | node -e "require('fs').readFileSync('/dev/stdin')""#;
+        assert!(fix_command(cmd, &Rewrites::default()).unwrap().command.contains("readFileSync(0)"));
+        assert!(runs_node("env -i NODE_ENV=test node app.js"));
+        assert!(runs_node("C:/tools/node.exe app.js"));
+    }
+
+    // -- Fix 2: Drive paths --------------------------------------------------
+
+    #[test]
+    fn context_lists_every_rewritten_path() {
+        let cmd = r#"node -e "readFileSync('C:\\a'); writeFileSync('D:\\b')""#;
+        let fixed = fix_command(cmd, &Rewrites::default()).unwrap();
+        assert_eq!(fixed, r#"node -e "readFileSync('C:/a'); writeFileSync('D:/b')""#);
+        assert!(fixed.context.contains(r"found: C:\\a, D:\\b)"), "got: {}", fixed.context);
+
+        // A path written twice is listed once.
+        let (_, paths, _) = fix_drive_paths(r"cp C:\tmp\a C:\tmp\a.bak && cat C:\tmp\a");
+        assert_eq!(paths, vec![r"C:\tmp\a", r"C:\tmp\a.bak"]);
+    }
+
+    #[test]
+    fn rewrites_host_colon_tool_and_profile_paths_to_msys_form() {
+        let rewrites = Rewrites::default();
+        assert_eq!(fix_command(r"rsync -a C:\src\ dst/", &rewrites).unwrap(), "rsync -a /c/src/ dst/");
+        assert_eq!(fix_command(r"scp D:\\f.txt h:", &rewrites).unwrap(), "scp /d/f.txt h:");
+        assert_eq!(
+            fix_command(r"tar -cf C:\out\a.tar src", &rewrites).unwrap(),
+            "tar -cf /c/out/a.tar src"
+        );
+        assert_eq!(
+            fix_command(r"echo 'export PATH=C:\tools\bin:$PATH' >> ~/.bashrc", &rewrites).unwrap(),
+            "echo 'export PATH=/c/tools/bin:$PATH' >> ~/.bashrc"
+        );
+
+        // Only the simple command that needs it; the rest keep `C:/`.
+        let fixed = fix_command(r"cd C:\src && rsync -a C:\src\a\ /d/b", &rewrites).unwrap();
+        assert_eq!(fixed, "cd C:/src && rsync -a /c/src/a/ /d/b");
+        assert!(fixed.context.contains(r"forward slashes (avoids bash escape issues; found: C:\src)"));
+        assert!(fixed.context.contains(r"MSYS paths (`/c/…`)"), "got: {}", fixed.context);
+        assert!(fixed.context.contains(r"found: C:\src\a\)"), "got: {}", fixed.context);
+
+        assert_eq!(
+            suggest_fix(r"cp C:\a x && rsync -a C:\a dst").as_deref(),
+            Some("cp C:/a x && rsync -a /c/a dst")
+        );
+    }
+
+    #[test]
+    fn fixes_unquoted_path() {
+        let cmd = r"ls -la C:\src\codeflow";
+        assert_eq!(fix_command(cmd, &Rewrites::default()).unwrap(), "ls -la C:/src/codeflow");
+    }
+
+    #[test]
+    fn fixes_unquoted_rm_multiple_paths() {
+        let cmd = r"rm C:\src\a\file.json C:\src\b\file.json";
+        assert_eq!(
+            fix_command(cmd, &Rewrites::default()).unwrap(),
+            "rm C:/src/a/file.json C:/src/b/file.json"
+        );
+    }
+
+    #[test]
+    fn fixes_double_quoted_path() {
+        let cmd = r#"ls -la "C:\src\project""#;
+        assert_eq!(fix_command(cmd, &Rewrites::default()).unwrap(), r#"ls -la "C:/src/project""#);
+    }
+
+    #[test]
+    fn fixes_trailing_backslash_quote() {
+        // "C:\path\" is broken in bash (\" eats quote).
+        // After fix: "C:/path/" — properly closed string.
+        let cmd = r#"ls -la "C:\src\el400\main\.github\workflows\""#;
+        assert_eq!(
+            fix_command(cmd, &Rewrites::default()).unwrap(),
+            r#"ls -la "C:/src/el400/main/.github/workflows/""#
+        );
+    }
+
+    #[test]
+    fn fixes_trailing_backslash_quote_in_grep() {
+        let cmd = r#"grep -r "pattern" "C:\src\codjiflo\C\src\styles\" --include="*.css""#;
+        assert_eq!(
+            fix_command(cmd, &Rewrites::default()).unwrap(),
+            r#"grep -r "pattern" "C:/src/codjiflo/C/src/styles/" --include="*.css""#
+        );
+    }
+
+    #[test]
+    fn fixes_double_backslash_path() {
+        // C:\\ in raw command → C:\ in bash (correct but fragile).
+        // Converting to C:/ is equally correct and more portable.
+        let cmd = r"grep pattern C:\\src\\codjiflo\\AGENTS.md";
+        assert_eq!(
+            fix_command(cmd, &Rewrites::default()).unwrap(),
+            "grep pattern C:/src/codjiflo/AGENTS.md"
+        );
+    }
+
+    #[test]
+    fn fixes_quad_backslash_in_node_e() {
+        // C:\\\\ in raw command → after bash: C:\\ → after JS: C:\ (correct
+        // but fragile). Forward slashes avoid the entire escaping chain.
+        let cmd = r#"node -e "require('fs').readFileSync('C:\\\\src\\\\file.json','utf8')""#;
+        assert_eq!(
+            fix_command(cmd, &Rewrites::default()).unwrap(),
+            r#"node -e "require('fs').readFileSync('C:/src/file.json','utf8')""#
+        );
+    }
+
+    #[test]
+    fn fixes_double_backslash_in_node_e() {
+        let cmd = r#"node -e "require('fs').readFileSync('C:\\tmp\\kv-ns.json','utf8')""#;
+        assert_eq!(
+            fix_command(cmd, &Rewrites::default()).unwrap(),
+            r#"node -e "require('fs').readFileSync('C:/tmp/kv-ns.json','utf8')""#
+        );
+    }
+
+    #[test]
+    fn fixes_combined_dev_stdin_and_path() {
+        let cmd = r#"cat C:\\tmp\\data.json | node -e "JSON.parse(require('fs').readFileSync('/dev/stdin','utf8'))""#;
+        let fixed = fix_command(cmd, &Rewrites::default()).unwrap();
+        assert!(fixed.command.contains("C:/tmp/data.json"));
+        assert!(fixed.command.contains("readFileSync(0,"));
+    }
+
+    // -- No-op cases ---------------------------------------------------------
+
+    #[test]
+    fn ignores_forward_slash_path() {
+        let cmd = "ls -la C:/src/project";
+        assert!(fix_command(cmd, &Rewrites::default()).is_none());
+    }
+
+    #[test]
+    fn ignores_unix_path() {
+        let cmd = "cd /c/src/project && ls";
+        assert!(fix_command(cmd, &Rewrites::default()).is_none());
+    }
+
+    #[test]
+    fn ignores_clean_node_e() {
+        let cmd = r#"node -e "console.log('hello')""#;
+        assert!(fix_command(cmd, &Rewrites::default()).is_none());
+    }
+
+    #[test]
+    fn ignores_url_with_colon() {
+        let cmd = "curl https://example.com:8080/api";
+        assert!(fix_command(cmd, &Rewrites::default()).is_none());
+    }
+
+    #[test]
+    fn does_not_match_mid_word_colon() {
+        // "Error:" has 'r' before ':' which is alphanumeric → no match
+        let cmd = r#"echo "Error: something failed""#;
+        assert!(fix_command(cmd, &Rewrites::default()).is_none());
+    }
+
+    // -- Edge cases ----------------------------------------------------------
+
+    #[test]
+    fn fixes_path_with_dots() {
+        let cmd = r"ls C:\src\el400\main\.github";
+        assert_eq!(fix_command(cmd, &Rewrites::default()).unwrap(), "ls C:/src/el400/main/.github");
+    }
+
+    #[test]
+    fn preserves_non_path_backslashes() {
+        // \n in echo is NOT a drive path — should not be touched
+        let cmd = r#"echo "line1\nline2""#;
+        assert!(fix_command(cmd, &Rewrites::default()).is_none());
+    }
+
+    #[test]
+    fn fixes_path_after_equals() {
+        let cmd = r"VAR=C:\src\project echo test";
+        assert_eq!(
+            fix_command(cmd, &Rewrites::default()).unwrap(),
+            "VAR=C:/src/project echo test"
+        );
+    }
+
+    #[test]
+    fn line_continuations_are_not_path_separators() {
+        let all: Vec<&Check> = CHECKS.iter().collect();
+        for cmd in ["ls \\\n  C:/ok", "cp C:/a\\\n  D:/b", "ls C:\\\n  -la"] {
+            assert!(analyze_with(&all, cmd).is_empty(), "{:?} fired {:?}", cmd, analyze_with(&all, cmd));
+            assert!(fix_command(cmd, &Rewrites::default()).is_none(), "{:?} was rewritten", cmd);
+            assert_eq!(suggest_fix(cmd), None);
+        }
+        // The path before the continuation is still fixed; the continuation stays.
+        let cmd = "ls C:\\src\\\n  -la";
+        assert_eq!(fix_command(cmd, &Rewrites::default()).unwrap(), "ls C:/src\\\n  -la");
+        assert_eq!(suggest_fix(cmd).as_deref(), Some("ls C:/src\\\n  -la"));
+        assert!(check_inline_eval_paths("node \\\n  -e \\\n  'f(\"C:\\t\")'").is_some());
+    }
+
+    // -- Context messages -----------------------------------------------------
+
+    #[test]
+    fn context_mentions_backslash() {
+        let cmd = r"ls C:\src\project";
+        let fixed = fix_command(cmd, &Rewrites::default()).unwrap();
+        assert!(fixed.context.contains("backslash"));
+        assert!(fixed.context.contains("forward slash"));
+    }
+
+    #[test]
+    fn context_mentions_dev_stdin() {
+        let cmd = r#"node -e "require('fs').readFileSync('/dev/stdin','utf8')""#;
+        let fixed = fix_command(cmd, &Rewrites::default()).unwrap();
+        assert!(fixed.context.contains("/dev/stdin"));
+    }
+
+    #[test]
+    fn context_mentions_both_fixes() {
+        let cmd = r#"cat C:\\tmp\\data.json | node -e "JSON.parse(require('fs').readFileSync('/dev/stdin','utf8'))""#;
+        let fixed = fix_command(cmd, &Rewrites::default()).unwrap();
+        assert!(fixed.context.contains("/dev/stdin"));
+        assert!(fixed.context.contains("backslash"));
+    }
+
+    // -- Check: rsync ---------------------------------------------------------
+
+    #[test]
+    fn flags_rsync_backslash_source() {
+        let msg = check_rsync_paths(r"rsync -a C:\src\ dst/").unwrap();
+        assert!(msg.contains(r"`C:\src\`"), "got: {}", msg);
+        assert!(msg.contains("trailing-slash"));
+    }
+
+    #[test]
+    fn flags_rsync_exclude_from_file() {
+        let msg = check_rsync_paths(r"rsync -a --exclude-from=C:\filters.txt src/ dst/").unwrap();
+        assert!(msg.contains(r"`C:\filters.txt`"), "got: {}", msg);
+    }
+
+    #[test]
+    fn ignores_rsync_forward_slash_paths() {
+        assert!(check_rsync_paths("rsync -a C:/src/ C:/dst/").is_none());
+        assert!(check_rsync_paths("rsync -a --exclude-from=C:/filters.txt src/ dst/").is_none());
+    }
+
+    #[test]
+    fn ignores_quoted_rsync_paths() {
+        assert!(check_rsync_paths(r#"rsync -a "C:\src\a" 'D:\dst' --exclude-from="C:\f.txt" x/"#).is_none());
+    }
+
+    #[test]
+    fn ignores_drive_paths_outside_rsync() {
+        assert!(check_rsync_paths(r"cp C:\src\a dst && rsync -a src/ dst/").is_none());
+    }
+
+    // -- Check: brace expansion ---------------------------------------------
+
+    #[test]
+    fn flags_brace_expansion_in_backslash_path() {
+        let msg = check_brace_expansion(r"ls C:\src\{a,b}").unwrap();
+        assert!(msg.contains(r"`C:\src\{a,b}`"), "got: {}", msg);
+        assert!(msg.contains("C:/src/{a,b}"));
+        let msg = check_brace_expansion(r"cat D:\logs\{x,y}.log").unwrap();
+        assert!(msg.contains("(`D:/logs/{x,y}.log`)"), "got: {}", msg);
+    }
+
+    #[test]
+    fn flags_range_brace_expansion() {
+        assert!(check_brace_expansion(r"cat C:\logs\app{1..3}.log").is_some());
+    }
+
+    #[test]
+    fn ignores_brace_expansion_in_forward_slash_paths() {
+        assert!(check_brace_expansion("ls ./src/{a,b}").is_none());
+        assert!(check_brace_expansion("ls C:/src/{a,b}").is_none());
+    }
+
+    #[test]
+    fn ignores_quoted_brace_paths() {
+        assert!(check_brace_expansion(r#"ls "C:\src\{a,b}" 'D:\x\{1..3}'"#).is_none());
+    }
+
+    #[test]
+    fn ignores_backslash_path_without_braces() {
+        assert!(check_brace_expansion(r"ls C:\src\a").is_none());
+    }
+
+    // -- Check: git clone target --------------------------------------------
+
+    #[test]
+    fn flags_git_clone_into_backslash_path() {
+        let msg = check_git_clone_target(r"git clone https://x C:\repos\y").unwrap();
+        assert!(msg.contains(r"`C:\repos\y`"), "got: {}", msg);
+        assert!(msg.contains("(`C:reposy`)") && msg.ends_with("Use forward slashes: `C:/repos/y`."), "got: {}", msg);
+    }
+
+    #[test]
+    fn flags_git_clone_target_after_options() {
+        let cmd = r"git -C D:\work clone --depth 1 -b main https://x C:\repos\y";
+        let msg = check_git_clone_target(cmd).unwrap();
+        assert!(msg.contains(r"`C:\repos\y`"));
+        assert!(!msg.contains(r"D:\work"));
+    }
+
+    #[test]
+    fn allows_git_clone_into_relative_path() {
+        assert!(check_git_clone_target("git clone https://x ./y").is_none());
+        assert!(check_git_clone_target("git clone https://x").is_none());
+    }
+
+    #[test]
+    fn allows_quoted_clone_target() {
+        assert!(check_git_clone_target(r#"git clone url "C:\repos\x""#).is_none());
+        assert!(check_git_clone_target(r"git clone url 'C:\repos\x'").is_none());
+    }
+
+    #[test]
+    fn ignores_backslash_clone_source() {
+        assert!(check_git_clone_target(r"git clone C:\repos\src").is_none());
+        assert!(check_git_clone_target(r"git clone --depth 1 C:\repos\src").is_none());
+    }
+
+    #[test]
+    fn ignores_other_git_subcommands() {
+        assert!(check_git_clone_target(r"git add x C:\repos\y").is_none());
+    }
+
+    // -- Check: git global paths ------------------------------------------------
+
+    #[test]
+    fn flags_git_dash_c_drive_path() {
+        let msg = check_git_global_paths(r"git -C C:\repo status").unwrap();
+        assert!(msg.contains(r"`C:\repo`"), "got: {}", msg);
+        let msg = check_git_global_paths(r"git -c core.pager= --git-dir=D:\r\.git log").unwrap();
+        assert!(msg.contains(r"`D:\r\.git`"), "got: {}", msg);
+        assert!(msg.contains("(`D:r.git`)") && msg.ends_with("Use forward slashes: `D:/r/.git`."), "got: {}", msg);
+    }
+
+    #[test]
+    fn allows_relative_git_dash_c() {
+        assert!(check_git_global_paths("git -C ./repo status").is_none());
+        assert!(check_git_global_paths("git -C C:/repo status").is_none());
+        assert!(check_git_global_paths(r#"git -C "C:\repo" status"#).is_none());
+        assert!(check_git_global_paths(r#"git --work-tree="C:\repo" status"#).is_none());
+    }
+
+    #[test]
+    fn ignores_subcommand_dash_c() {
+        assert!(check_git_global_paths("git commit -C HEAD").is_none());
+        assert!(check_git_global_paths(r"git diff -C C:\a\x.txt").is_none());
+    }
+
+    // -- Check: python -m ----------------------------------------------------
+
+    #[test]
+    fn flags_python_module_directory_flag() {
+        let msg =
+            check_python_module_paths(r"python -m http.server --directory C:\web").unwrap();
+        assert!(msg.starts_with("`python -m http.server` got"), "got: {}", msg);
+        assert!(msg.contains(r"`C:\web`"));
+        let msg = check_python_module_paths(r"py -m venv D:\envs\x").unwrap();
+        assert!(msg.starts_with("`py -m venv` got"), "got: {}", msg);
+        assert!(msg.ends_with("Use forward slashes: `D:/envs/x`."), "got: {}", msg);
+    }
+
+    #[test]
+    fn flags_python_module_flag_value_and_positional() {
+        assert!(check_python_module_paths(r"python3 -m pytest --rootdir=C:\proj").is_some());
+        assert!(check_python_module_paths(r"py.exe -m venv C:\envs\x").is_some());
+    }
+
+    #[test]
+    fn allows_python_module_forward_slash_paths() {
+        assert!(check_python_module_paths("python -m http.server --directory C:/web").is_none());
+        assert!(check_python_module_paths(r#"python -m http.server --directory "C:\web""#).is_none());
+    }
+
+    #[test]
+    fn ignores_python_without_module() {
+        assert!(check_python_module_paths(r"python C:\scripts\run.py").is_none());
+    }
+
+    // -- Check: pattern/script files -------------------------------------------
+
+    #[test]
+    fn flags_grep_pattern_file() {
+        let msg = check_pattern_file_paths(r"grep -f C:\p.txt x").unwrap();
+        assert!(msg.contains(r"`C:\p.txt`"), "got: {}", msg);
+        assert!(msg.contains("so `grep` can't") && msg.ends_with("Use forward slashes: `C:/p.txt`."), "got: {}", msg);
+    }
+
+    #[test]
+    fn allows_quoted_pattern_files() {
+        assert!(check_pattern_file_paths(r#"grep -f "C:\p.txt" x"#).is_none());
+        assert!(check_pattern_file_paths(r#"sed --file="C:\s.sed" x"#).is_none());
+    }
+
+    #[test]
+    fn flags_attached_and_long_file_flags() {
+        assert!(check_pattern_file_paths(r"grep -fC:\p.txt x").is_some());
+        assert!(check_pattern_file_paths(r"sed --file=C:\s.sed in.txt").is_some());
+        assert!(check_pattern_file_paths(r"awk -f C:\prog.awk data.csv").is_some());
+    }
+
+    #[test]
+    fn allows_forward_slash_pattern_file() {
+        assert!(check_pattern_file_paths("grep -f C:/p.txt x").is_none());
+    }
+
+    #[test]
+    fn ignores_force_flags_on_other_tools() {
+        assert!(check_pattern_file_paths(r"rm -f C:\tmp\x").is_none());
+        assert!(check_pattern_file_paths(r"cp -f a C:\tmp\x").is_none());
+    }
+
+    // -- Check: dd -------------------------------------------------------------
+
+    #[test]
+    fn flags_dd_operands() {
+        let msg = check_dd_paths(r"dd if=C:\a of=C:\b").unwrap();
+        assert!(msg.contains(r"`C:\a`, `C:\b`"), "got: {}", msg);
+        assert!(msg.contains("Use forward slashes: `C:/a`, `C:/b`."), "got: {}", msg);
+        assert!(msg.contains("no confirmation"));
+    }
+
+    #[test]
+    fn allows_dd_forward_slash_operands() {
+        assert!(check_dd_paths("dd if=C:/a of=C:/b bs=4M").is_none());
+        assert!(check_dd_paths(r#"dd if="C:\img\disk.iso" of=/dev/sdb"#).is_none());
+    }
+
+    // -- Check: go -------------------------------------------------------------
+
+    #[test]
+    fn flags_go_build_output_path() {
+        let msg = check_go_paths(r"go build -o C:\bin\app .").unwrap();
+        assert!(msg.starts_with("`go build`"), "got: {}", msg);
+        assert!(msg.contains(r"`C:\bin\app`"), "got: {}", msg);
+        assert!(msg.ends_with("Use forward slashes: `C:/bin/app`."), "got: {}", msg);
+    }
+
+    #[test]
+    fn flags_go_workdir_and_package_paths() {
+        let msg = check_go_paths(r"go run -C=C:\proj . && go test C:\proj\pkg").unwrap();
+        assert!(msg.contains("`go run`, `go test`"), "got: {}", msg);
+        assert!(msg.contains(r"`C:\proj`, `C:\proj\pkg`"), "got: {}", msg);
+    }
+
+    #[test]
+    fn allows_go_forward_slash_paths() {
+        assert!(check_go_paths("go build -o C:/bin/app .").is_none());
+        assert!(check_go_paths("go test ./...").is_none());
+        assert!(check_go_paths(r#"go build -o "C:\bin\app.exe" ."#).is_none());
+    }
+
+    // -- Check: script paths ---------------------------------------------------
+
+    #[test]
+    fn flags_bash_script_drive_path() {
+        let msg = check_script_paths(r"bash C:\scripts\b.sh --verbose").unwrap();
+        assert!(msg.contains(r"`C:\scripts\b.sh`"), "got: {}", msg);
+        assert!(msg.contains("runs (or fails to find) `C:scriptsb.sh`"), "got: {}", msg);
+        assert!(msg.ends_with("Use forward slashes: `C:/scripts/b.sh`."), "got: {}", msg);
+        assert!(check_script_paths(r"sh -e C:\scripts\b.sh").is_some());
+    }
+
+    #[test]
+    fn flags_direct_script_execution() {
+        let msg = check_script_paths(r"./C:\scripts\build.sh release").unwrap();
+        assert!(msg.ends_with("`C:/scripts/build.sh`, without a leading `./`."), "got: {}", msg);
+        assert!(check_script_paths(r"CI=1 C:\scripts\build.sh").is_some());
+    }
+
+    #[test]
+    fn allows_relative_and_forward_slash_scripts() {
+        assert!(check_script_paths("bash ./b.sh").is_none());
+        assert!(check_script_paths("bash C:/scripts/b.sh").is_none());
+        assert!(check_script_paths(r"bash -c 'ls C:\tmp'").is_none());
+        assert!(check_script_paths(r#"bash "C:\scripts\b.sh""#).is_none());
+        assert!(check_script_paths(r"'C:\scripts\build.sh' release").is_none());
+    }
+
+    // -- Check: copy paths -----------------------------------------------------
+
+    #[test]
+    fn flags_both_cp_operands() {
+        let msg = check_copy_paths(r"cp -r C:\a C:\b").unwrap();
+        assert!(msg.contains(r"`C:\a`, `C:\b`"), "got: {}", msg);
+        assert!(msg.contains("Use forward slashes (`C:/a`, `C:/b`)"), "got: {}", msg);
+        // The program is found past `sudo`/`env` prefixes.
+        assert!(check_copy_paths(r"sudo env LANG=C cp C:\a D:\b").is_some());
+    }
+
+    #[test]
+    fn flags_every_mv_operand_and_target_directory() {
+        let msg = check_copy_paths(r"mv C:\x\1.txt C:\x\2.txt --target-directory=C:\out").unwrap();
+        for path in [r"`C:\x\1.txt`", r"`C:\x\2.txt`", r"`C:\out`"] {
+            assert!(msg.contains(path), "missing {} in: {}", path, msg);
+        }
+        assert!(msg.starts_with("`mv` got") && msg.contains(r"`C:\x\1.txt` becomes `C:x1.txt`"), "got: {}", msg);
+    }
+
+    #[test]
+    fn allows_quoted_and_forward_slash_operands() {
+        assert!(check_copy_paths(r"cp -r 'C:\a' 'C:\b'").is_none());
+        assert!(check_copy_paths("cp -r C:/a C:/b").is_none());
+        let msg = check_copy_paths(r#"cp "C:\a" C:\b"#).unwrap();
+        assert!(!msg.contains(r"`C:\a`"), "got: {}", msg);
+    }
+
+    // -- Check: directory env vars -------------------------------------------
+
+    #[test]
+    fn flags_dir_var_with_drive_path() {
+        let msg = check_dir_env_vars(r"TMPDIR=C:\temp cargo build").unwrap();
+        assert!(msg.contains(r"`TMPDIR=C:\temp`"), "got: {}", msg);
+        let msg = check_dir_env_vars(r"export HOME=C:\Users\me XDG_CACHE_HOME=C:\cache PATH=x").unwrap();
+        assert!(msg.contains(r"`HOME=C:\Users\me`"), "got: {}", msg);
+        assert!(msg.contains(r"`XDG_CACHE_HOME=C:\cache`"), "got: {}", msg);
+        assert!(msg.contains("like `C:Usersme`"), "got: {}", msg);
+        assert!(msg.ends_with("Use forward slashes: `HOME=C:/Users/me`, `XDG_CACHE_HOME=C:/cache`."), "got: {}", msg);
+    }
+
+    #[test]
+    fn allows_forward_slash_quoted_and_other_vars() {
+        assert!(check_dir_env_vars("TMPDIR=C:/temp cargo build").is_none());
+        assert!(check_dir_env_vars(r"TMPDIR='C:\temp' cargo build").is_none());
+        assert!(check_dir_env_vars(r"FOO=C:\temp cargo build").is_none());
+        // Only assignments count, not a program's own `NAME=value` arguments.
+        assert!(check_dir_env_vars(r"make HOME=C:\x").is_none());
+    }
+
+    // -- Check: env-prefix paths ----------------------------------------------
+
+    #[test]
+    fn flags_prefix_assignment_with_drive_path() {
+        let msg = check_env_prefix_paths(r"FOO=C:\bar cmd").unwrap();
+        assert!(msg.contains(r"`FOO=C:\bar`"), "got: {}", msg);
+        let msg = check_env_prefix_paths(r"A=1 CONF=C:\etc\app.toml B=D:\x cmd arg").unwrap();
+        assert!(msg.contains(r"so `CONF=C:\etc\app.toml` arrives as `CONF=C:etcapp.toml`"), "got: {}", msg);
+        assert!(msg.contains(r#"(`CONF=C:/etc/app.toml`, `B=D:/x`) or quote the value (`CONF="C:\etc\app.toml"`)"#), "got: {}", msg);
+        assert!(msg.contains(r"`CONF=C:\etc\app.toml`"), "got: {}", msg);
+        assert!(msg.contains(r"`B=D:\x`"), "got: {}", msg);
+        assert!(!msg.contains("`A=1`"), "got: {}", msg);
+        let msg = check_env_prefix_paths(r"env -u X FOO=C:\bar somecmd").unwrap();
+        assert!(msg.contains(r"`FOO=C:\bar`"), "got: {}", msg);
+        assert!(check_env_prefix_paths(r"A=C:\a env B=C:\b cmd").unwrap().contains(r"`B=C:\b`"));
+    }
+
+    #[test]
+    fn allows_quoted_forward_slash_and_bare_assignments() {
+        assert!(check_env_prefix_paths(r#"FOO="C:\bar" cmd"#).is_none());
+        assert!(check_env_prefix_paths("FOO=C:/bar cmd").is_none());
+        // No command: a plain shell variable, not a prefix.
+        assert!(check_env_prefix_paths(r"FOO=C:\bar").is_none());
+        // Directory variables are reported by `dir-env-vars` instead.
+        assert!(check_env_prefix_paths(r"TMPDIR=C:\temp cargo build").is_none());
+        assert!(check_env_prefix_paths(r"make FOO=C:\x").is_none());
+    }
+
+    // -- Check: remote host colon ----------------------------------------------
+
+    #[test]
+    fn flags_drive_paths_read_as_remote_hosts() {
+        let msg = check_remote_host_colon(r"tar -cf a.tar C:\src").unwrap();
+        assert!(msg.contains(r"`C:\src`"), "got: {}", msg);
+        assert!(msg.contains("Use MSYS paths (`/c/src`)"), "got: {}", msg);
+        assert!(msg.contains("--force-local"), "got: {}", msg);
+
+        let msg = check_remote_host_colon(r"scp C:\f.txt h:").unwrap();
+        assert!(msg.contains(r"`C:\f.txt`"), "got: {}", msg);
+        assert!(!msg.contains("`h:`"), "got: {}", msg);
+        assert!(!msg.contains("--force-local"), "got: {}", msg);
+
+        assert!(check_remote_host_colon(r"tar --file=D:\b.tar -x").is_some());
+        assert!(check_remote_host_colon("rsync -a C: /backup").is_some());
+    }
+
+    #[test]
+    fn allows_msys_paths_and_real_hosts() {
+        assert!(check_remote_host_colon("scp /c/f.txt h:").is_none());
+        assert!(check_remote_host_colon("scp f.txt user@host:/tmp").is_none());
+        assert!(check_remote_host_colon("tar -cf a.tar src").is_none());
+        assert!(check_remote_host_colon(r"cp C:\src x").is_none());
+    }
+
+    // -- Check: CRLF scripts ------------------------------------------------------
+
+    #[test]
+    fn flags_local_shell_scripts() {
+        let msg = check_crlf_script("bash deploy.sh").unwrap();
+        assert!(msg.contains("`deploy.sh`"), "got: {}", msg);
+        assert!(msg.contains(r"sed -i 's/\r$//' deploy.sh"), "got: {}", msg);
+        assert!(msg.contains("core.autocrlf input"), "got: {}", msg);
+        assert!(check_crlf_script("./run.sh --fast").unwrap().contains("`./run.sh`"));
+        assert!(check_crlf_script("cd app && sh -e scripts/build.sh").is_some());
+    }
+
+    #[test]
+    fn ignores_commands_strings_and_non_local_scripts() {
+        assert!(check_crlf_script(r#"bash -c "echo hi""#).is_none());
+        assert!(check_crlf_script("bash /opt/tools/setup.sh").is_none());
+        assert!(check_crlf_script("deploy.sh").is_none());
+        assert!(check_crlf_script("./configure").is_none());
+        assert!(check_crlf_script("python run.py").is_none());
+    }
+
+    // -- Check: UNC paths -----------------------------------------------------------
+
+    #[test]
+    fn flags_unquoted_unc_paths() {
+        let msg = check_unc_paths(r"cat \\srv\share\f.txt").unwrap();
+        assert!(msg.contains(r"`\\srv\share\f.txt`"), "got: {}", msg);
+        assert!(msg.contains("//server/share"), "got: {}", msg);
+        assert!(check_unc_paths(r"ls \\fileserver.corp\share\docs").is_some());
+        assert!(check_unc_paths(r"tool --out=\\srv\share\x").is_some());
+    }
+
+    #[test]
+    fn allows_quoted_unc_paths_and_escapes() {
+        assert!(check_unc_paths(r#"cat "\\srv\share""#).is_none());
+        assert!(check_unc_paths(r"grep '\\n' f.txt").is_none());
+        assert!(check_unc_paths(r"printf \\n").is_none());
+        assert!(check_unc_paths("cat //srv/share/f.txt").is_none());
+    }
+
+    // -- Check: permission paths ------------------------------------------------
+
+    #[test]
+    fn flags_chmod_on_drive_paths() {
+        let msg = check_permission_paths(r"chmod -R 755 C:\project").unwrap();
+        assert!(msg.contains(r"`C:\project`"), "got: {}", msg);
+        assert!(msg.contains("becomes `C:project`"), "got: {}", msg);
+        assert!(msg.contains("icacls"), "got: {}", msg);
+
+        // Forward slashes fix the path but not the permission model.
+        let msg = check_permission_paths("chmod -R 755 C:/project").unwrap();
+        assert!(!msg.contains("becomes"), "got: {}", msg);
+        assert!(msg.contains("NTFS ACLs"), "got: {}", msg);
+
+        assert!(check_permission_paths(r"chown -R me:me D:\data").is_some());
+        let msg = check_permission_paths(r"chmod 644 'C:\project\a.txt'").unwrap();
+        assert!(!msg.contains("becomes"), "got: {}", msg);
+    }
+
+    #[test]
+    fn allows_chmod_on_relative_and_posix_paths() {
+        assert!(check_permission_paths("chmod +x run.sh").is_none());
+        assert!(check_permission_paths("chmod -R 755 /c/project").is_none());
+        assert!(check_permission_paths("chown me:me file").is_none());
+    }
+
+    // -- Check: drive-relative paths ----------------------------------------------
+
+    #[test]
+    fn flags_drive_relative_paths() {
+        let msg = check_drive_relative_path("cat C:foo.txt").unwrap();
+        assert!(msg.contains("`C:foo.txt`"), "got: {}", msg);
+        assert!(msg.contains("`C:/foo` or `/c/foo`"), "got: {}", msg);
+        assert!(check_drive_relative_path("node build.js --out=D:dist").is_some());
+    }
+
+    #[test]
+    fn allows_absolute_paths_labels_and_remote_specs() {
+        assert!(check_drive_relative_path("echo time: 5s").is_none());
+        assert!(check_drive_relative_path("cat C:/abs").is_none());
+        assert!(check_drive_relative_path(r"cat C:\abs").is_none());
+        assert!(check_drive_relative_path("cat 'C:foo'").is_none());
+        assert!(check_drive_relative_path("scp f.txt h:backup").is_none());
+    }
+
+    // -- Check: mixed separators ---------------------------------------------------
+
+    #[test]
+    fn flags_mixed_separators() {
+        let msg = check_mixed_separators(r"cat C:/src\foo").unwrap();
+        assert!(msg.contains(r"`C:/src\foo`"), "got: {}", msg);
+        assert!(msg.contains("`C:/src/foo`"), "got: {}", msg);
+        assert!(check_mixed_separators(r"ls C:\src/foo").is_some());
+        assert!(check_mixed_separators(r"cp a.txt --target-directory=D:/out\logs").is_some());
+    }
+
+    #[test]
+    fn allows_uniform_separators_and_escaped_spaces() {
+        assert!(check_mixed_separators("cat C:/src/foo").is_none());
+        assert!(check_mixed_separators(r"cat C:\src\foo").is_none());
+        assert!(check_mixed_separators(r"cat C:/my\ dir/f.txt").is_none());
+        assert!(check_mixed_separators(r"cat 'C:/src\foo'").is_none());
+        assert!(check_mixed_separators(r"cat src/a\b").is_none());
+    }
+
+    // -- Check: here-string paths -------------------------------------------------
+
+    #[test]
+    fn flags_unquoted_here_string_paths() {
+        let msg = check_here_string_paths(r"cmd <<< C:\data\x").unwrap();
+        assert!(msg.contains(r"`C:\data\x`"), "got: {}", msg);
+        assert!(msg.contains("`C:datax`"), "got: {}", msg);
+        let msg = check_here_string_paths(r"node read.js <<<D:\in.txt").unwrap();
+        assert!(msg.contains("(`<<< 'D:\\in.txt'`) or use forward slashes (`<<< D:/in.txt`)"), "got: {}", msg);
+    }
+
+    #[test]
+    fn allows_quoted_or_forward_slash_here_strings() {
+        assert!(check_here_string_paths(r#"cmd <<< "C:\data\x""#).is_none());
+        assert!(check_here_string_paths(r"cmd <<< 'C:\data\x'").is_none());
+        assert!(check_here_string_paths("cmd <<< C:/data/x").is_none());
+        assert!(check_here_string_paths(r"cmd < C:\data\x").is_none());
+    }
+
+    // -- Check: reserved filenames ------------------------------------------------
+
+    #[test]
+    fn flags_reserved_filenames() {
+        let msg = check_reserved_filenames("echo hi > nul").unwrap();
+        assert!(msg.contains("`nul`"), "got: {}", msg);
+        assert!(msg.contains("`/dev/null`"), "got: {}", msg);
+        assert!(check_reserved_filenames("cargo build 2>NUL").unwrap().contains("`/dev/null`"));
+
+        let msg = check_reserved_filenames("touch aux.txt").unwrap();
+        assert!(msg.contains("`aux.txt`"), "got: {}", msg);
+        assert!(!msg.contains("/dev/null"), "got: {}", msg);
+        assert!(check_reserved_filenames("cp a.log logs/COM1.log").is_some());
+    }
+
+    #[test]
+    fn allows_ordinary_names_and_echoed_text() {
+        assert!(check_reserved_filenames("cat console.log").is_none());
+        assert!(check_reserved_filenames("touch running").is_none());
+        assert!(check_reserved_filenames("cmd > /dev/null 2>&1").is_none());
+        assert!(check_reserved_filenames("echo aux con").is_none());
+        assert!(check_reserved_filenames("touch com0.txt").is_none());
+    }
+
+    // -- Check: colon filenames ---------------------------------------------------
+
+    #[test]
+    fn flags_colons_in_file_names() {
+        let msg = check_invalid_colon_filename("touch report:final.txt").unwrap();
+        assert!(msg.contains("`report:final.txt`"), "got: {}", msg);
+        assert!(msg.contains("Remove the colon"), "got: {}", msg);
+        assert!(check_invalid_colon_filename("git checkout -- report:final.txt").is_some());
+        assert!(check_invalid_colon_filename("node gen.js > out/build:1.log").is_some());
+        assert!(check_invalid_colon_filename("touch notes:v2").is_some());
+    }
+
+    #[test]
+    fn allows_drives_urls_and_non_file_colons() {
+        assert!(check_invalid_colon_filename(r"cp a.txt C:\src").is_none());
+        assert!(check_invalid_colon_filename("touch C:foo").is_none());
+        assert!(check_invalid_colon_filename("curl http://x").is_none());
+        assert!(check_invalid_colon_filename("curl -o out.txt http://x:8080/a").is_none());
+        assert!(check_invalid_colon_filename("git show HEAD:src/main.rs").is_none());
+        assert!(check_invalid_colon_filename("docker run node:20").is_none());
+    }
+
+    // -- Check: Windows env vars ---------------------------------------------------
+
+    #[test]
+    fn flags_percent_env_vars() {
+        let msg = check_windows_env_var(r"cat %TEMP%\x").unwrap();
+        assert!(msg.contains("`%TEMP%`"), "got: {}", msg);
+        assert!(msg.contains(r#"`"$TEMP"`"#), "got: {}", msg);
+        assert!(check_windows_env_var("echo %PATH%").is_some());
+        let msg = check_windows_env_var(r#"node -e "run()" "%USERPROFILE%/a" %APPDATA%"#).unwrap();
+        assert!(msg.contains("`%USERPROFILE%`, `%APPDATA%`"), "got: {}", msg);
+    }
+
+    #[test]
+    fn allows_format_strings_and_single_quotes() {
+        assert!(check_windows_env_var(r#"printf "%s\n" x"#).is_none());
+        assert!(check_windows_env_var("date +%Y%m%d").is_none());
+        assert!(check_windows_env_var("echo '%TEMP%'").is_none());
+        assert!(check_windows_env_var(r#"echo "it's 50%" 'or %PATH%'"#).is_none());
+        assert!(check_windows_env_var("echo 100% done %").is_none());
+    }
+
+    // -- Check: watch paths -------------------------------------------------------
+
+    #[test]
+    fn flags_watcher_drive_paths() {
+        let msg = check_watch_paths(r"cargo watch -w C:\src -x test").unwrap();
+        assert!(msg.starts_with("`cargo watch` got"), "got: {}", msg);
+        assert!(msg.contains(r"`C:\src`"), "got: {}", msg);
+        assert!(check_watch_paths(r"nodemon --watch=D:\app\src server.js").is_some());
+        let msg = check_watch_paths(r"watch -n 5 ls C:\build").unwrap();
+        assert!(msg.starts_with("`watch` got") && msg.ends_with("Use forward slashes: `C:/build`."), "got: {}", msg);
+        assert!(!msg.contains("-w"), "got: {}", msg);
+        assert!(check_watch_paths(r"ls *.rs | entr cat C:\logs\out.txt").is_some());
+    }
+
+    #[test]
+    fn allows_forward_slash_or_quoted_watch_paths() {
+        assert!(check_watch_paths("cargo watch -w C:/src -x test").is_none());
+        assert!(check_watch_paths(r"nodemon --watch 'C:\app\src'").is_none());
+        assert!(check_watch_paths(r"cargo build --target-dir C:\out").is_none());
+    }
+
+    // -- Check: search paths ------------------------------------------------------
+
+    #[test]
+    fn flags_search_root_drive_paths() {
+        let msg = check_search_paths(r"rg foo C:\src").unwrap();
+        assert!(msg.starts_with("`rg` got"), "got: {}", msg);
+        assert!(msg.contains(r"`C:\src`"), "got: {}", msg);
+        let msg = check_search_paths(r"fd -e rs . C:\src\app").unwrap();
+        assert!(msg.starts_with("`fd` got") && msg.ends_with("Use forward slashes: `C:/src/app`."), "got: {}", msg);
+        assert!(!msg.contains("rg"), "got: {}", msg);
+        assert!(check_search_paths(r"ag TODO D:\work\app").is_some());
+        assert!(check_search_paths(r"ctags -R --exclude=node_modules C:\src\app").is_some());
+        assert!(check_search_paths(r"rg --ignore-file=C:\cfg\ignore foo .").is_some());
+    }
+
+    #[test]
+    fn allows_relative_forward_slash_or_quoted_search_roots() {
+        assert!(check_search_paths("rg foo ./src").is_none());
+        assert!(check_search_paths("fd -e rs . C:/src").is_none());
+        assert!(check_search_paths(r"rg foo 'C:\src'").is_none());
+        assert!(check_search_paths(r"grep -r foo C:\src").is_none());
+    }
+
+    // -- Check: database paths -----------------------------------------------------
+
+    #[test]
+    fn flags_database_cli_drive_paths() {
+        let msg = check_database_paths(r"sqlite3 C:\db\app.db").unwrap();
+        assert!(msg.starts_with(r"`sqlite3` got backslash drive path(s) `C:\db\app.db`"), "got: {}", msg);
+        assert!(msg.contains("creates an empty database at the mangled path (`C:dbapp.db`)"), "got: {}", msg);
+        let msg = check_database_paths(r"psql -U app -f C:\scripts\migrate.sql").unwrap();
+        assert!(!msg.contains("sqlite3"), "got: {}", msg);
+        assert!(msg.ends_with("Use forward slashes: `C:/scripts/migrate.sql`."), "got: {}", msg);
+        assert!(check_database_paths(r"psql --file=C:\scripts\migrate.sql").is_some());
+        assert!(check_database_paths(r"mysql app < C:\dump.sql").is_some());
+        assert!(check_database_paths(r"mysql app <C:\dump.sql").is_some());
+        assert!(check_database_paths(r#"sqlite3 C:\db\app.db ".tables""#).is_some());
+    }
+
+    #[test]
+    fn allows_forward_slash_or_quoted_database_paths() {
+        assert!(check_database_paths("sqlite3 C:/db/app.db").is_none());
+        assert!(check_database_paths("psql -f C:/scripts/migrate.sql").is_none());
+        assert!(check_database_paths(r"sqlite3 'C:\db\app.db'").is_none());
+        assert!(check_database_paths(r"mysql app < ./dump.sql").is_none());
+        assert!(check_database_paths(r"cat C:\dump.sql").is_none());
+    }
+
+    // -- Check: download paths -----------------------------------------------------
+
+    #[test]
+    fn flags_download_output_drive_paths() {
+        let msg = check_download_output_path(r"curl -o C:\d\f https://x").unwrap();
+        assert!(msg.starts_with(r"`curl` writes to backslash drive path(s) `C:\d\f`"), "got: {}", msg);
+        assert!(check_download_output_path(r"curl -sL --output C:\d\f https://x").is_some());
+        assert!(check_download_output_path(r"curl --output=C:\d\f https://x").is_some());
+        assert!(check_download_output_path(r"curl -oC:\d\f https://x").is_some());
+        assert!(check_download_output_path(r"curl -O --output-dir C:\d https://x/f.zip").is_some());
+        let msg = check_download_output_path(r"wget -O C:\dl\x.zip https://x").unwrap();
+        assert!(msg.starts_with("`wget` writes") && msg.contains("(`C:dlx.zip`:"), "got: {}", msg);
+        assert!(msg.ends_with("Use forward slashes: `C:/dl/x.zip`."), "got: {}", msg);
+        assert!(!msg.contains("curl"), "got: {}", msg);
+        assert!(check_download_output_path(r"wget -P C:\downloads https://x/f.zip").is_some());
+    }
+
+    #[test]
+    fn allows_quoted_or_forward_slash_download_outputs() {
+        assert!(check_download_output_path("curl -o C:/d/f https://x").is_none());
+        assert!(check_download_output_path(r"curl -o 'C:\d\f' https://x").is_none());
+        assert!(check_download_output_path(r#"wget -O "C:\d\f" https://x"#).is_none());
+        assert!(check_download_output_path("curl -O https://x/f.zip").is_none());
+        assert!(check_download_output_path(r"curl -d C:\x https://x").is_none());
+        assert!(check_download_output_path(r"scp -o C:\x host:f .").is_none());
+    }
+
+    // -- Check: regex backslashes --------------------------------------------------
+
+    #[test]
+    fn flags_unquoted_backslashes_in_regex_patterns() {
+        let msg = check_regex_tool_backslash(r"grep C:\x file").unwrap();
+        assert!(msg.starts_with(r"`grep` pattern(s) `C:\x` hold a Windows path"), "got: {}", msg);
+        assert!(msg.contains("grep -F"), "got: {}", msg);
+        let msg = check_regex_tool_backslash(r"grep \bword\b file").unwrap();
+        assert!(msg.contains(r"`\bword\b` have unquoted backslashes"), "got: {}", msg);
+        assert!(!msg.contains("Windows path"), "got: {}", msg);
+        assert!(check_regex_tool_backslash(r"grep -i -e C:\Users\me log.txt").is_some());
+        assert!(check_regex_tool_backslash(r"sed -n s/C:\\x/y/p file").is_some());
+        assert!(check_regex_tool_backslash(r"cat log | awk /C:\tmp/ ").is_some());
+        assert!(check_regex_tool_backslash(r"grep -A 2 \d+ log").is_some());
+        let msg = check_regex_tool_backslash(r"grep C:\x a && sed s/\s// b").unwrap();
+        assert!(msg.contains(r"`grep` pattern(s) `C:\x`") && msg.contains(r"`sed` pattern(s) `s/\s//`"), "got: {}", msg);
+    }
+
+    #[test]
+    fn allows_quoted_patterns_and_backslashes_outside_the_pattern() {
+        assert!(check_regex_tool_backslash(r"grep '\bword\b' file").is_none());
+        assert!(check_regex_tool_backslash(r#"grep -F "C:\x" file"#).is_none());
+        assert!(check_regex_tool_backslash(r"sed 's/\foo/bar/' file").is_none());
+        assert!(check_regex_tool_backslash(r"grep foo C:\x").is_none());
+        assert!(check_regex_tool_backslash(r"grep -e foo C:\x").is_none());
+        assert!(check_regex_tool_backslash(r"sed -f C:\s.sed file").is_none());
+        assert!(check_regex_tool_backslash(r"rg C:\x file").is_none());
+    }
+
+    // -- Check: media paths -------------------------------------------------------
+
+    #[test]
+    fn flags_media_tool_drive_paths() {
+        let msg = check_media_paths(r"ffmpeg -i C:\a.mp4 C:\b.mp4").unwrap();
+        assert!(msg.starts_with("`ffmpeg` got"), "got: {}", msg);
+        assert!(msg.contains(r"`C:\a.mp4`, `C:\b.mp4`"), "got: {}", msg);
+        assert!(check_media_paths(r"convert C:\in.png -resize 50% out.png").is_some());
+        let msg = check_media_paths(r"magick in.png D:\out\small.png").unwrap();
+        assert!(msg.starts_with("`magick` got") && msg.contains("(`D:outsmall.png`)"), "got: {}", msg);
+        assert!(msg.ends_with("Use forward slashes: `D:/out/small.png`."), "got: {}", msg);
+        assert!(!msg.contains("ffmpeg"), "got: {}", msg);
+        assert!(check_media_paths(r"sox C:\a.wav b.wav").is_some());
+        assert!(check_media_paths(r"sudo ffmpeg -i x.mp4 -y --output=C:\o.mp4").is_some());
+    }
+
+    #[test]
+    fn allows_forward_slash_quoted_or_non_media_paths() {
+        assert!(check_media_paths("ffmpeg -i C:/a.mp4 C:/b.mp4").is_none());
+        assert!(check_media_paths("convert ./in.png -resize 50% out/small.png").is_none());
+        assert!(check_media_paths(r"ffmpeg -i 'C:\a.mp4' 'C:\b.mp4'").is_none());
+        assert!(check_media_paths(r"cp C:\a.mp4 b.mp4").is_none());
+    }
+
+    // -- Check: profile paths -----------------------------------------------------
+
+    #[test]
+    fn flags_drive_paths_and_semicolons_appended_to_profiles() {
+        let msg = check_profile_paths(r"echo 'export PATH=C:\tools\bin:$PATH' >> ~/.bashrc").unwrap();
+        assert!(msg.contains("`~/.bashrc`"), "got: {}", msg);
+        assert!(msg.contains(r"`C:\tools\bin`"), "got: {}", msg);
+        assert!(msg.contains(r"(`C:\tools\bin` becomes `C:toolsbin`)"), "got: {}", msg);
+        assert!(msg.contains("`export PATH=/c/tools/bin:$PATH`"), "got: {}", msg);
+        assert!(msg.contains("`C:/tools/bin` doesn't work"), "got: {}", msg);
+
+        let msg = check_profile_paths(r#"printf 'export PATH="C:/a;C:/b"\n' >>"$HOME/.zshrc""#).unwrap();
+        assert!(msg.contains("`C:/a;C:/b` (`;`-separated)"), "got: {}", msg);
+        assert!(msg.contains("`export PATH=/c/a:/c/b:$PATH`"), "got: {}", msg);
+        assert!(check_profile_paths(r"echo export GOPATH=D:\go > ~/.profile").is_some());
+        assert!(check_profile_paths(r"cd /tmp && echo 'alias w=C:\w.exe' >> .bash_profile").is_some());
+    }
+
+    #[test]
+    fn allows_clean_profile_appends_and_other_files() {
+        assert!(check_profile_paths("echo 'export PATH=/c/tools/bin:$PATH' >> ~/.bashrc").is_none());
+        assert!(check_profile_paths(r"echo 'export PATH=C:\x:$PATH' >> notes.txt").is_none());
+        assert!(check_profile_paths("cat ~/.bashrc").is_none());
+        assert!(check_profile_paths("echo 'a; b' >> ~/.bashrc").is_none());
+    }
+
+    // -- Check: inline eval paths ---------------------------------------------------
+
+    #[test]
+    fn flags_node_eval_paths_js_reads_as_escapes() {
+        // Single quotes: JS gets `C:\t` as typed, a tab.
+        let msg = check_inline_eval_paths(r#"node -e 'readFileSync("C:\t")'"#).unwrap();
+        assert!(msg.contains(r"`C:\t`"), "got: {}", msg);
+        // Double quotes: `\\` becomes `\`, so JS still sees `\s`; a lone `\s` is kept by bash.
+        let msg = check_inline_eval_paths(r#"node -e "readFileSync('C:\\src\\a.json')""#).unwrap();
+        assert!(msg.contains(r"`C:\src\a.json`"), "got: {}", msg);
+        assert!(check_inline_eval_paths(r#"node -e "readFileSync('C:\src')""#).is_some());
+        assert!(check_inline_eval_paths(r#"cd C:/ok && node --eval='f("D:\x")'"#).is_some());
+        // `=` forms: the code starts right after the `=`.
+        let msg = check_inline_eval_paths(r#"node --eval="readFileSync('C:\\a')""#).unwrap();
+        assert!(msg.contains(r"`C:\a`"), "got: {}", msg);
+        assert!(check_inline_eval_paths(r#"node -e='f("C:\t")'"#).is_some());
+        assert!(check_inline_eval_paths(r#"node -p="require('C:\\x.json')""#).is_some());
+    }
+
+    #[test]
+    fn allows_node_eval_paths_that_survive_both_quotings() {
+        // Single quotes keep `\\`, which JS reads as one backslash.
+        assert!(check_inline_eval_paths(r#"node -e 'readFileSync("C:\\src")'"#).is_none());
+        // Double quotes turn `\\\\` into `\\`, likewise one backslash in JS.
+        assert!(check_inline_eval_paths(r#"node -e "readFileSync('C:\\\\src\\\\a.json')""#).is_none());
+        assert!(check_inline_eval_paths(r#"node -e "readFileSync('C:/src')""#).is_none());
+        assert!(check_inline_eval_paths(r#"node app.js 'C:\t'"#).is_none());
+        assert!(check_inline_eval_paths(r#"node --evaluate-flag='C:\t' app.js"#).is_none());
+        assert!(check_inline_eval_paths(r#"python -c='open("C:\t")'"#).is_none());
+    }
+
+    #[test]
+    fn flags_inline_eval_paths_in_other_interpreters() {
+        // `\\` in bash double quotes reaches Python as `\`: a tab and a bad hex escape.
+        let msg = check_inline_eval_paths(r#"python -c "open('C:\\tmp\\x')""#).unwrap();
+        assert!(msg.contains(r"`python -c` code path(s) `C:\tmp\x` reach Python"), "got: {}", msg);
+        assert!(msg.contains("raw string"), "got: {}", msg);
+        assert!(check_inline_eval_paths(r#"python3 -c 'open("C:\t")'"#).is_some());
+        assert!(check_inline_eval_paths(r#"ruby -e 'File.read("C:\tmp")'"#).is_some());
+        assert!(check_inline_eval_paths(r#"perl -e 'open(F, "C:\tmp")'"#).is_some());
+        let msg = check_inline_eval_paths(r#"php -r 'echo file_get_contents("C:\tmp");'"#).unwrap();
+        assert!(msg.contains("single-quoted PHP string"), "got: {}", msg);
+    }
+
+    #[test]
+    fn allows_inline_eval_paths_in_literals_without_escapes() {
+        assert!(check_inline_eval_paths(r"python script.py C:\tmp\x").is_none());
+        assert!(check_inline_eval_paths(r#"python -c "open(r'C:\tmp')""#).is_none());
+        assert!(check_inline_eval_paths(r#"ruby -e "File.read('C:\tmp')""#).is_none());
+        assert!(check_inline_eval_paths(r#"perl -e "open(F, 'C:\tmp')""#).is_none());
+        assert!(check_inline_eval_paths(r#"python -c 'open("C:/tmp")'"#).is_none());
+        assert!(check_inline_eval_paths(r#"pythonista -c 'open("C:\t")'"#).is_none());
+    }
+
+    // -- Combined notes ---------------------------------------------------------
+
+    #[test]
+    fn analyze_names_each_fired_check() {
+        let findings = analyze(r"dd if=C:\img\a.iso of=D:\b.img && ./run.sh");
+        let got: Vec<(&str, Severity)> = findings.iter().map(|f| (f.rule.as_str(), f.severity)).collect();
+        assert_eq!(got, vec![("dd-paths", Severity::Warn), ("crlf-script", Severity::Info)]);
+        assert!(findings[0].message.contains(r"`C:\img\a.iso`"), "got: {}", findings[0].message);
+        assert!(analyze("ls C:/src").is_empty());
+    }
+
+    #[test]
+    fn compound_commands_are_judged_per_segment() {
+        // Only the broken segment of each chain is named.
+        let notes = notes("cd C:/ok && cat C:foo.txt | sort");
+        assert_eq!(notes.len(), 1);
+        assert!(notes[0].contains("`C:foo.txt`") && !notes[0].contains("C:/ok"), "got: {}", notes[0]);
+        let cmd = r#"cd C:\src && node -e "require('./a')" ; dd if=C:\a.img of=D:\b.img"#;
+        let fired = analyze(cmd);
+        assert_eq!(fired.iter().map(|f| f.rule.as_str()).collect::<Vec<_>>(), vec!["dd-paths"]);
+        assert!(!fired[0].message.contains(r"C:\src"), "got: {}", fired[0].message);
+        // A `;` inside the node script doesn't split it.
+        let fixed = fix_command(cmd, &Rewrites::default()).unwrap();
+        assert_eq!(fixed, r#"cd C:/src && node -e "require('./a')" ; dd if=C:/a.img of=D:/b.img"#);
+    }
+
+    #[test]
+    fn overlapping_checks_are_reported_once() {
+        let names = |cmd: &str| -> Vec<String> { analyze(cmd).into_iter().map(|f| f.rule).collect() };
+        assert_eq!(names(r"cp C:\src\a/b.txt D:\dst"), vec!["copy-paths"]);
+        assert_eq!(names(r"rsync -av C:\src\dir/ host:/x"), vec!["rsync-paths", "remote-host-colon"]);
+        // Alone, or for a path no other check names, the mixed-separator note stays.
+        assert_eq!(names(r"node x.js < C:\tmp\in/put.json"), vec!["mixed-separators"]);
+        assert_eq!(
+            names(r"cp C:\src\a.txt D:\dst && cat C:\tmp\in/put.json"),
+            vec!["copy-paths", "mixed-separators"]
+        );
+    }
+
+    // -- Snapshots ------------------------------------------------------------
+    //
+    // The full text Claude sees is part of the hook's behavior, so wording
+    // changes should show up in review. After an intentional change, run
+    // `cargo insta review` (or `INSTA_UPDATE=always cargo test`) from this
+    // crate and commit the updated files under `src/snapshots/`.
+
+    #[test]
+    fn snapshot_drive_path_fix_context() {
+        let r = fix_command(r"ls C:\Users\me\src", &Rewrites::default()).unwrap();
+        insta::assert_snapshot!(r.context);
+    }
+
+    #[test]
+    fn snapshot_dev_stdin_fix_context() {
+        let r = fix_command("node -e \"require('fs').readFileSync('/dev/stdin')\"", &Rewrites::default()).unwrap();
+        insta::assert_snapshot!(r.context);
+    }
+
+    #[test]
+    fn snapshot_check_copy_paths() {
+        insta::assert_snapshot!(check_copy_paths(r"cp -r C:\src\a C:\dst\b").unwrap());
+    }
+
+    #[test]
+    fn snapshot_check_rsync_paths() {
+        insta::assert_snapshot!(check_rsync_paths(r"rsync -a C:\src\ C:\dst\").unwrap());
+    }
+
+    #[test]
+    fn snapshot_check_brace_expansion() {
+        insta::assert_snapshot!(check_brace_expansion(r"ls C:\src\{a,b}").unwrap());
+    }
+
+    #[test]
+    fn snapshot_check_git_clone_target() {
+        insta::assert_snapshot!(check_git_clone_target(r"git clone https://x C:\repos\y").unwrap());
+    }
+
+    #[test]
+    fn snapshot_check_git_global_paths() {
+        insta::assert_snapshot!(check_git_global_paths(r"git -C C:\repo status").unwrap());
+    }
+
+    #[test]
+    fn snapshot_check_python_module_paths() {
+        insta::assert_snapshot!(
+            check_python_module_paths(r"python -m http.server --directory C:\web").unwrap()
+        );
+    }
+
+    #[test]
+    fn snapshot_check_pattern_file_paths() {
+        insta::assert_snapshot!(check_pattern_file_paths(r"grep -f C:\p.txt x").unwrap());
+    }
+
+    #[test]
+    fn snapshot_check_dir_env_vars() {
+        insta::assert_snapshot!(check_dir_env_vars(r"TMPDIR=C:\temp cargo build").unwrap());
+    }
+
+    #[test]
+    fn snapshot_check_env_prefix_paths() {
+        insta::assert_snapshot!(check_env_prefix_paths(r"FOO=C:\bar cmd").unwrap());
+    }
+
+    #[test]
+    fn snapshot_check_remote_host_colon() {
+        insta::assert_snapshot!(check_remote_host_colon(r"tar -cf a.tar C:\src").unwrap());
+    }
+
+    #[test]
+    fn snapshot_check_crlf_script() {
+        insta::assert_snapshot!(check_crlf_script("bash deploy.sh").unwrap());
+    }
+
+    #[test]
+    fn snapshot_check_unc_paths() {
+        insta::assert_snapshot!(check_unc_paths(r"cat \\srv\share\f.txt").unwrap());
+    }
+
+    #[test]
+    fn snapshot_check_permission_paths() {
+        insta::assert_snapshot!(check_permission_paths(r"chmod -R 755 C:\project").unwrap());
+    }
+
+    #[test]
+    fn snapshot_check_drive_relative_path() {
+        insta::assert_snapshot!(check_drive_relative_path("cat C:foo.txt").unwrap());
+    }
+
+    #[test]
+    fn snapshot_check_mixed_separators() {
+        insta::assert_snapshot!(check_mixed_separators(r"cat C:/src\foo\bar").unwrap());
+    }
+
+    #[test]
+    fn snapshot_check_here_string_paths() {
+        insta::assert_snapshot!(check_here_string_paths(r"cmd <<< C:\data\x").unwrap());
+    }
+
+    #[test]
+    fn snapshot_check_reserved_filenames() {
+        insta::assert_snapshot!(check_reserved_filenames("echo hi > nul").unwrap());
+    }
+
+    #[test]
+    fn snapshot_check_invalid_colon_filename() {
+        insta::assert_snapshot!(check_invalid_colon_filename("touch report:final.txt").unwrap());
+    }
+
+    #[test]
+    fn snapshot_check_windows_env_var() {
+        insta::assert_snapshot!(check_windows_env_var(r"cat %TEMP%\x").unwrap());
+    }
+
+    #[test]
+    fn snapshot_check_watch_paths() {
+        insta::assert_snapshot!(check_watch_paths(r"cargo watch -w C:\src -x test").unwrap());
+    }
+
+    #[test]
+    fn snapshot_check_download_output_path() {
+        insta::assert_snapshot!(check_download_output_path(r"curl -o C:\d\f https://x").unwrap());
+    }
+
+    #[test]
+    fn snapshot_check_database_paths() {
+        insta::assert_snapshot!(check_database_paths(r"sqlite3 C:\db\app.db").unwrap());
+    }
+
+    #[test]
+    fn snapshot_check_regex_tool_backslash() {
+        insta::assert_snapshot!(check_regex_tool_backslash(r"grep C:\Users log && grep \bid\b log").unwrap());
+    }
+
+    #[test]
+    fn snapshot_check_media_paths() {
+        insta::assert_snapshot!(check_media_paths(r"ffmpeg -i C:\a.mp4 C:\b.mp4").unwrap());
+    }
+
+    #[test]
+    fn snapshot_check_search_paths() {
+        insta::assert_snapshot!(check_search_paths(r"rg foo C:\src").unwrap());
+    }
+
+    #[test]
+    fn snapshot_check_profile_paths() {
+        insta::assert_snapshot!(check_profile_paths(r"echo 'export PATH=C:\x:$PATH' >> ~/.bashrc").unwrap());
+    }
+
+    #[test]
+    fn snapshot_check_inline_eval_paths() {
+        insta::assert_snapshot!(check_inline_eval_paths(r#"node -e 'readFileSync("C:\tmp\x")'"#).unwrap());
+    }
+
+    #[test]
+    fn snapshot_check_dd_paths() {
+        insta::assert_snapshot!(check_dd_paths(r"dd if=C:\a of=C:\b").unwrap());
+    }
+
+    #[test]
+    fn snapshot_check_go_paths() {
+        insta::assert_snapshot!(check_go_paths(r"go build -o C:\bin\app .").unwrap());
+    }
+
+    #[test]
+    fn snapshot_check_script_paths() {
+        insta::assert_snapshot!(check_script_paths(r"bash C:\scripts\b.sh").unwrap());
+    }
+}
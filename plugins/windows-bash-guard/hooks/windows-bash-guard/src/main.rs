@@ -1,40 +1,261 @@
 //! PreToolUse hook that auto-fixes common Windows+bash path pitfalls in Bash
 //! commands before execution, avoiding a wasted round-trip.
 //!
-//! Fixes:
-//! 1. `/dev/stdin` → fd `0` in node commands (doesn't exist on Windows)
-//! 2. Backslash drive paths → forward slashes everywhere (fixes unquoted paths,
-//!    node -e escape bugs, and trailing `\"` in one pass)
+//! The rewrites and checks live in the library (`lib.rs`); this binary reads
+//! the tool call, applies the configuration and reports the result.
 //!
 //! Returns JSON with `updatedInput` so Claude Code executes the corrected
-//! command transparently.
-
-use serde_json::{json, Value};
+//! command transparently. `[no-rewrite]` in the description skips the rewrite
+//! but not the check notes.
+//!
+//! `Write`, `Edit` and `MultiEdit` calls have their `file_path` put through
+//! the drive-relative and mixed-separator tests; a path failing either is
+//! blocked, since the tool would write exactly that path.
+//!
+//! Checks are registered by name in [`CHECKS`]; `.claude/windows-bash-guard.toml`
+//! can disable, enable or reorder them, and `--list-checks` prints the
+//! registry with the effective configuration. `--diagnose '<command>'` runs
+//! every registered check on a command, whatever the config says, and prints
+//! which matched and what they would note. `--explain '<command>'` prints
+//! what the hook would say under the config: the rewrite and each note, or
+//! `no issues`.
+//!
+//! A `warn` check that fires in `escalate_after` consecutive turns of a
+//! session (default 3) stops being a note: the command is blocked until it no
+//! longer trips the check. A turn runs from one user message to the next, read
+//! from the transcript; several calls firing in one turn count once, and a
+//! turn with Bash calls but no firing ends the streak. Without a transcript
+//! each call counts as a turn. Streaks live in the state directory. With
+//! `WINDOWS_BASH_GUARD_MODE=warn` nothing blocks: the escalation reason goes
+//! to stderr and the command proceeds, rewritten and annotated as usual.
+//!
+//! A block ends with a hash: the user setting `WINDOWS_BASH_GUARD_ALLOW_ONCE`
+//! to it lets that command (or `file_path`) through once. Re-submitting it
+//! without the approval is blocked again.
+//!
+//! `WINDOWS_BASH_GUARD_ALLOW` holds regexes, separated by newlines or `;`: a
+//! command matching any of them is left alone entirely (no rewrite, no
+//! checks). Invalid patterns are reported on stderr and skipped.
+
+use hook_common::allow_once::{self, AllowOnce};
+use hook_common::config;
+use hook_common::dead_letter;
+use hook_common::doctor::{self, Doctor};
+use hook_common::decision::{
+    more_note, top_findings, Decision, Emission, Event, Finding, Severity, DEFAULT_MAX_FINDINGS,
+};
+use hook_common::redact::redact;
+use hook_common::state;
+use hook_common::stop::find_turn_start;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
 use std::io::{self, Read};
+use std::mem;
+use std::path::Path;
 use std::process;
+use windows_bash_guard::{
+    analyze_with, fix_command, forward_slashes, has_mixed_separators, is_drive_relative, path_fix,
+    suggest_fix, unquoted_drive_paths, Check, FixResult, Rewrites, CHECKS,
+};
+
+const HOOK: &str = "windows-bash-guard";
+
+/// Consecutive turns one check fires in after which it blocks.
+const DEFAULT_ESCALATE_AFTER: u32 = 3;
+
+/// What an escalated check does, from `WINDOWS_BASH_GUARD_MODE`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Mode {
+    /// Exit 2: the command doesn't run (default).
+    Block,
+    /// Print the reason to stderr and exit 0: the command runs.
+    Warn,
+}
+
+impl Mode {
+    fn from_env() -> Mode {
+        Mode::parse(env::var("WINDOWS_BASH_GUARD_MODE").ok().as_deref())
+    }
+
+    /// `warn` (any case) is [`Mode::Warn`]; anything else, or unset, blocks.
+    fn parse(value: Option<&str>) -> Mode {
+        match value.map(str::trim) {
+            Some(v) if v.eq_ignore_ascii_case("warn") => Mode::Warn,
+            _ => Mode::Block,
+        }
+    }
+}
+
+/// How a block is reported, from `WINDOWS_BASH_GUARD_OUTPUT`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Output {
+    /// The reason on stderr and exit 2 (default).
+    Text,
+    /// `{"decision":"block","reason":…,"check":…}` on stdout and exit 0.
+    Json,
+}
+
+impl Output {
+    fn from_env() -> Output {
+        Output::parse(env::var("WINDOWS_BASH_GUARD_OUTPUT").ok().as_deref())
+    }
+
+    /// `json` (any case) is [`Output::Json`]; anything else, or unset, is text.
+    fn parse(value: Option<&str>) -> Output {
+        match value.map(str::trim) {
+            Some(v) if v.eq_ignore_ascii_case("json") => Output::Json,
+            _ => Output::Text,
+        }
+    }
+}
+
+/// Optional `.claude/windows-bash-guard.toml`.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Config {
+    /// Checks to turn off, by name.
+    disabled_checks: Vec<String>,
+    /// Checks that are off by default to turn on, by name.
+    enabled_checks: Vec<String>,
+    /// Checks to run first, in this order; the rest follow in registry order.
+    check_order: Vec<String>,
+    /// Consecutive Bash calls a check may fire on before it blocks; `0` never
+    /// escalates.
+    escalate_after: u32,
+    /// Which rewrites [`fix_command`] applies.
+    rewrites: Rewrites,
+    /// Notes (and escalated checks) listed per command, most severe first;
+    /// `0` lists them all.
+    max_findings: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            disabled_checks: Vec::new(),
+            enabled_checks: Vec::new(),
+            check_order: Vec::new(),
+            escalate_after: DEFAULT_ESCALATE_AFTER,
+            rewrites: Rewrites::default(),
+            max_findings: DEFAULT_MAX_FINDINGS,
+        }
+    }
+}
+
+impl Config {
+    /// The checks to run, in order.
+    fn active_checks(&self) -> Vec<&'static Check> {
+        let listed = |names: &[String], c: &Check| names.iter().any(|n| n == c.name);
+        let mut checks: Vec<&Check> = self
+            .check_order
+            .iter()
+            .filter_map(|name| CHECKS.iter().find(|c| c.name == name))
+            .collect();
+        for c in CHECKS {
+            if !checks.iter().any(|k| k.name == c.name) {
+                checks.push(c);
+            }
+        }
+        checks.retain(|c| {
+            !listed(&self.disabled_checks, c)
+                && (c.enabled_by_default || listed(&self.enabled_checks, c))
+        });
+        checks
+    }
+
+    /// Names in the config that match no registered check.
+    fn unknown_checks(&self) -> Vec<&str> {
+        self.disabled_checks
+            .iter()
+            .chain(&self.enabled_checks)
+            .chain(&self.check_order)
+            .map(String::as_str)
+            .filter(|n| !CHECKS.iter().any(|c| c.name == *n))
+            .collect()
+    }
+}
 
 fn main() {
+    if doctor::requested() {
+        let mut d = Doctor::new(HOOK);
+        d.os_gate("windows");
+        let cfg: Config = d.config();
+        d.count("checks", format!("{} active of {}", cfg.active_checks().len(), CHECKS.len()));
+        d.exit();
+    }
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    if !args.is_empty() {
+        if let Some(out) = cli(&args, &config::load(HOOK)) {
+            print!("{}", out);
+            return;
+        }
+    }
+
     if std::env::consts::OS != "windows" {
         process::exit(0);
     }
 
     let mut input = String::new();
-    if io::stdin().read_to_string(&mut input).is_err() {
+    if io::stdin().read_to_string(&mut input).is_err() || !config::enabled_here() {
         process::exit(0);
     }
+    dead_letter::guard(HOOK, &input, || run(&input));
+}
 
-    let data: Value = match serde_json::from_str(&input) {
+fn run(input: &str) {
+    let data: Value = match serde_json::from_str(input) {
         Ok(v) => v,
         Err(_) => process::exit(0),
     };
+    let cfg: Config = config::load(HOOK);
+    let session_id = data["session_id"].as_str().unwrap_or("unknown");
+    let streaks_path = state::session_path(HOOK, session_id, "streaks.json");
+    let mut allow = AllowOnce::load(HOOK, session_id);
+    respond(&data, &cfg, &streaks_path, &mut allow, Mode::from_env(), Output::from_env()).exit();
+}
 
-    if data.get("tool_name").and_then(|v| v.as_str()) != Some("Bash") {
-        process::exit(0);
+/// What the hook does with the tool call in `data`. A block the user approved
+/// through [`AllowOnce`] is let through once: the file path of a
+/// `Write`/`Edit`, the command of an escalated Bash call.
+fn respond(
+    data: &Value,
+    cfg: &Config,
+    streaks_path: &Path,
+    allow: &mut AllowOnce,
+    mode: Mode,
+    output: Output,
+) -> Emission {
+    let nothing = Emission { code: 0, stdout: String::new(), stderr: String::new(), report: None };
+    let session_id = data["session_id"].as_str().unwrap_or("unknown");
+
+    let tool_name = data.get("tool_name").and_then(|v| v.as_str()).unwrap_or("");
+    if matches!(tool_name, "Write" | "Edit" | "MultiEdit") {
+        let path = data["tool_input"]["file_path"].as_str().unwrap_or("");
+        let mut decision = Decision::new(HOOK, Event::PreToolUse).with_session(session_id);
+        for f in file_path_findings(data, &cfg.active_checks()) {
+            decision.push(f);
+        }
+        let blocks = mode == Mode::Block && decision.level() == Some(Severity::Block);
+        if blocks && allow.take(path) {
+            allow.save();
+            return nothing;
+        }
+        let out = decision.emit(|findings| {
+            format!("{}\n\n{}", file_path_reason(findings, tool_name), allow_once::hint(HOOK, path))
+        });
+        return render(&decision, out, mode, output);
+    }
+    if tool_name != "Bash" {
+        return nothing;
     }
 
-    let tool_input = match data.get("tool_input") {
-        Some(v) => v,
-        None => process::exit(0),
+    let Some(tool_input) = data.get("tool_input") else {
+        return nothing;
     };
 
     let description = tool_input
@@ -42,190 +263,408 @@ fn main() {
         .and_then(|v| v.as_str())
         .unwrap_or("");
 
-    if description.contains("[no-rewrite]") {
-        process::exit(0);
-    }
+    let bypass_rewrite = description.contains("[no-rewrite]");
 
     let command = match tool_input.get("command").and_then(|v| v.as_str()) {
         Some(c) if !c.is_empty() => c,
-        _ => process::exit(0),
+        _ => return nothing,
     };
 
-    if let Some(fixed) = fix_command(command) {
-        let mut updated = tool_input.as_object().cloned().unwrap_or_default();
-        updated.insert("command".into(), Value::String(fixed.command));
+    if let Ok(allow) = env::var("WINDOWS_BASH_GUARD_ALLOW") {
+        if allowlisted(command, &allow) {
+            return nothing;
+        }
+    }
+
+    for name in cfg.unknown_checks() {
+        eprintln!("{}: unknown check `{}` in config (see --list-checks)", HOOK, name);
+    }
 
-        let output = json!({
-            "hookSpecificOutput": {
-                "hookEventName": "PreToolUse",
-                "updatedInput": updated,
-                "additionalContext": fixed.context
+    let fired = analyze_with(&cfg.active_checks(), command);
+    let mut streaks = Streaks::load(streaks_path);
+    let names = escalating(&fired);
+    let escalated = streaks.record(&names, current_turn(data).as_deref(), cfg.escalate_after);
+    streaks.save(streaks_path);
+
+    let mut out = nothing;
+    if !escalated.is_empty() {
+        let mut decision = Decision::new(HOOK, Event::PreToolUse)
+            .with_session(session_id)
+            .with_max_findings(cfg.max_findings);
+        for f in fired.iter().filter(|f| escalated.contains(&f.rule.as_str())) {
+            decision.push(escalation_finding(&f.rule, &f.message, command));
+        }
+        if mode == Mode::Block && allow.take(command) {
+            allow.save();
+        } else {
+            let suggestion = suggest_fix(command);
+            let hint = allow_once::hint(HOOK, command);
+            out = escalate(&decision, cfg.escalate_after, suggestion.as_deref(), Some(&hint), mode, output);
+            if mode == Mode::Block {
+                return out;
             }
-        });
-        println!("{}", output);
+        }
+    }
+
+    let fixed = if bypass_rewrite { None } else { fix_command(command, &cfg.rewrites) };
+    let (warnings, hidden) = listed_notes(&fired, cfg.max_findings);
+
+    let Some(context) = build_context(fixed.as_ref(), &warnings, hidden) else {
+        return out;
+    };
+
+    let mut hook_output = Map::new();
+    hook_output.insert("hookEventName".into(), Value::String("PreToolUse".into()));
+    if let Some(fixed) = fixed {
+        let mut updated = tool_input.as_object().cloned().unwrap_or_default();
+        updated.insert("command".into(), Value::String(fixed.command));
+        hook_output.insert("updatedInput".into(), Value::Object(updated));
     }
+    hook_output.insert("additionalContext".into(), Value::String(context));
 
-    process::exit(0);
+    out.stdout = format!("{}\n", json!({ "hookSpecificOutput": hook_output }));
+    out
 }
 
-// ---------------------------------------------------------------------------
-// Top-level fix orchestrator
-// ---------------------------------------------------------------------------
+/// Whether `command` matches one of the regexes in `allow` (the value of
+/// `WINDOWS_BASH_GUARD_ALLOW`, separated by newlines or `;`). Invalid
+/// patterns are reported and skipped.
+fn allowlisted(command: &str, allow: &str) -> bool {
+    allow
+        .split(['\n', ';'])
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .filter_map(|p| match Regex::new(p) {
+            Ok(re) => Some(re),
+            Err(_) => {
+                eprintln!("{}: skipping invalid WINDOWS_BASH_GUARD_ALLOW pattern `{}`", HOOK, p);
+                None
+            }
+        })
+        .any(|re| re.is_match(command))
+}
 
-struct FixResult {
-    command: String,
-    context: String,
+/// The notes of the `fired` checks to list, at most `max` of them (see
+/// [`top_findings`]), and how many were left out.
+fn listed_notes(fired: &[Finding], max: usize) -> (Vec<String>, usize) {
+    let (listed, hidden) = top_findings(fired.iter().collect(), max);
+    (listed.into_iter().map(|f| f.message.clone()).collect(), hidden)
 }
 
-impl PartialEq<&str> for FixResult {
-    fn eq(&self, other: &&str) -> bool {
-        self.command == *other
+/// Combine the rewrite summary and any check warnings into one
+/// `additionalContext` string, secrets masked. `hidden` counts the warnings
+/// left out by `max_findings`. `None` when there is nothing to say.
+fn build_context(fixed: Option<&FixResult>, warnings: &[String], hidden: usize) -> Option<String> {
+    let mut parts: Vec<String> = Vec::new();
+    if let Some(fixed) = fixed {
+        parts.push(fixed.context.clone());
+    }
+    if !warnings.is_empty() {
+        let list = warnings
+            .iter()
+            .map(|w| format!("- {}", w))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let more = if hidden > 0 { format!("\n{}", more_note(hidden)) } else { String::new() };
+        parts.push(format!("windows-bash-guard notes:\n{}{}", list, more));
+    }
+    if parts.is_empty() {
+        None
+    } else {
+        Some(redact(&parts.join("\n\n")))
     }
 }
 
-impl std::fmt::Debug for FixResult {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("FixResult")
-            .field("command", &self.command)
-            .field("context", &self.context)
-            .finish()
-    }
+/// The fired checks that count towards escalation: Info checks (hints that
+/// fire on commands that may well be fine) never block.
+fn escalating(fired: &[Finding]) -> Vec<&'static str> {
+    fired
+        .iter()
+        .filter(|f| f.severity >= Severity::Warn)
+        .filter_map(|f| CHECKS.iter().find(|c| c.name == f.rule))
+        .map(|c| c.name)
+        .collect()
 }
 
-/// Apply all fixes to the command. Returns `Some(FixResult)` if anything changed.
-fn fix_command(command: &str) -> Option<FixResult> {
-    let mut result = command.to_string();
-    let mut fixes: Vec<&str> = Vec::new();
+/// The turn a hook call in `data` belongs to: the `uuid` of the user message
+/// starting it in the transcript (its line number if it has none), `None`
+/// without a readable transcript.
+fn current_turn(data: &Value) -> Option<String> {
+    let transcript = fs::read_to_string(data["transcript_path"].as_str()?).ok()?;
+    let lines: Vec<&str> = transcript.lines().collect();
+    let start = find_turn_start(&lines);
+    let uuid = lines
+        .get(start)
+        .and_then(|line| serde_json::from_str::<Value>(line).ok())
+        .and_then(|entry| entry["uuid"].as_str().map(str::to_string));
+    Some(uuid.unwrap_or_else(|| format!("line-{}", start)))
+}
 
-    // Fix 1: /dev/stdin → fd number in node commands
-    if fix_dev_stdin(&mut result) {
-        fixes.push("/dev/stdin replaced with fd number (doesn't exist on Windows)");
-    }
+/// How many turns in a row each check has fired in, per session.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Streaks {
+    /// The turn of the last recorded call, and the turn before it.
+    turn: String,
+    previous: String,
+    /// Calls recorded without a turn, each numbered as a turn of its own.
+    calls: u64,
+    checks: BTreeMap<String, Streak>,
+}
 
-    // Fix 2: backslash drive paths → forward slashes
-    let (fixed, path_changed) = fix_drive_paths(&result);
-    if path_changed {
-        result = fixed;
-        fixes.push("backslash paths converted to forward slashes (avoids bash escape issues)");
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Streak {
+    turns: u32,
+    /// The last turn the check fired in.
+    last: String,
+}
+
+impl Streaks {
+    fn load(path: &Path) -> Streaks {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
     }
 
-    if fixes.is_empty() {
-        return None;
+    fn save(&self, path: &Path) {
+        if let Ok(s) = serde_json::to_string(self) {
+            let _ = fs::write(path, s);
+        }
     }
 
-    let context = format!(
-        "windows-bash-guard hook rewrote this command: {}. Use forward-slash paths on Windows to avoid this. To bypass rewriting, add [no-rewrite] to the Bash tool description.",
-        fixes.join("; ")
-    );
+    /// Count one Bash call in `turn` (`None`: a turn of its own). Checks in
+    /// `fired` extend their streak the first time they fire in a turn;
+    /// streaks that skipped the previous turn have ended. Returns the fired
+    /// checks whose streak has reached `after` (none when `after` is 0).
+    fn record(&mut self, fired: &[&'static str], turn: Option<&str>, after: u32) -> Vec<&'static str> {
+        let turn = match turn {
+            Some(turn) => turn.to_string(),
+            None => {
+                self.calls += 1;
+                format!("call-{}", self.calls)
+            }
+        };
+        if turn != self.turn {
+            self.previous = mem::replace(&mut self.turn, turn);
+        }
+        let (turn, previous) = (&self.turn, &self.previous);
+        self.checks.retain(|_, s| s.last == *turn || s.last == *previous);
+
+        let mut escalated = Vec::new();
+        for &name in fired {
+            let streak = self.checks.entry(name.to_string()).or_default();
+            if streak.last != *turn {
+                streak.turns += 1;
+                streak.last = turn.clone();
+            }
+            if after > 0 && streak.turns >= after {
+                escalated.push(name);
+            }
+        }
+        escalated
+    }
+}
 
-    Some(FixResult { command: result, context })
+/// The escalation `decision` rendered for `mode` and `output` (see
+/// [`render`]). `suggestion` is the command from [`suggest_fix`], if any.
+fn escalate(
+    decision: &Decision,
+    after: u32,
+    suggestion: Option<&str>,
+    hint: Option<&str>,
+    mode: Mode,
+    output: Output,
+) -> Emission {
+    let out = decision.emit(|findings| escalation_reason(findings, after, suggestion, hint));
+    render(decision, out, mode, output)
 }
 
-// ---------------------------------------------------------------------------
-// Fix 1: /dev/stdin → file descriptor
-// ---------------------------------------------------------------------------
+/// `out`, emitted from `decision`, adjusted for `mode` and `output`: in `warn`
+/// mode the same output with exit code 0, which lets the tool call run; a
+/// block in JSON output moves to stdout, naming the check(s) that fired.
+fn render(decision: &Decision, mut out: Emission, mode: Mode, output: Output) -> Emission {
+    if mode == Mode::Warn {
+        out.code = 0;
+    } else if output == Output::Json && out.code == 2 {
+        let checks: Vec<&str> = decision.findings().iter().map(|f| f.rule.as_str()).collect();
+        let why: Vec<Value> = decision
+            .findings()
+            .iter()
+            .flat_map(|f| {
+                f.fixes.iter().map(|fix| {
+                    json!({
+                        "check": f.rule,
+                        "span": [fix.span.start, fix.span.end],
+                        "matched": fix.matched,
+                        "suggestion": fix.suggestion,
+                    })
+                })
+            })
+            .collect();
+        out.stdout = format!(
+            "{}\n",
+            json!({
+                "decision": "block",
+                "reason": out.stderr.trim_end(),
+                "check": checks.first(),
+                "checks": checks,
+                "why": why,
+            })
+        );
+        out.stderr.clear();
+        out.code = 0;
+    }
+    out
+}
 
-/// Replace `'/dev/stdin'` → `0`, `'/dev/stdout'` → `1`, `'/dev/stderr'` → `2`
-/// in commands that involve node. These paths don't exist on Windows;
-/// `readFileSync(0)` reads from fd 0 (stdin) and works cross-platform.
-fn fix_dev_stdin(command: &mut String) -> bool {
-    if !command.contains("node") {
-        return false;
-    }
-
-    let mut changed = false;
-    for (quoted, fd) in [
-        ("'/dev/stdin'", "0"),
-        ("\"/dev/stdin\"", "0"),
-        ("'/dev/stdout'", "1"),
-        ("\"/dev/stdout\"", "1"),
-        ("'/dev/stderr'", "2"),
-        ("\"/dev/stderr\"", "2"),
-    ] {
-        if command.contains(quoted) {
-            *command = command.replace(quoted, fd);
-            changed = true;
+/// The blocking finding for an escalated check, with a [`Fix`] for each
+/// unquoted drive path of `command` that its note names.
+fn escalation_finding(name: &str, note: &str, command: &str) -> Finding {
+    let mut finding = Finding::block(name, note);
+    for span in unquoted_drive_paths(command) {
+        let matched = &command[span.clone()];
+        if note.contains(&format!("`{}`", matched)) {
+            let fixed = path_fix(command, &span);
+            finding = finding.with_fix(span, matched, fixed);
         }
     }
-
-    changed
+    finding
 }
 
-// ---------------------------------------------------------------------------
-// Fix 2: Backslash drive paths → forward slashes
-// ---------------------------------------------------------------------------
-
-/// Find all Windows drive paths (`X:\...`) and convert backslashes to forward
-/// slashes. This fixes multiple failure modes in one pass:
-///
-/// - Unquoted `C:\src` → bash eats `\s` → `C:src` (fix: `C:/src`)
-/// - `"C:\path\"` → `\"` eats closing quote → EOF (fix: `"C:/path/"`)
-/// - `node -e "..C:\\src.."` → JS interprets `\s` as escape (fix: `C:/src`)
-/// - `node -e "..C:\\\\tmp.."` → multi-layer escaping hell (fix: `C:/tmp`)
-///
-/// Forward slashes work everywhere: bash, Node.js, and Windows APIs.
-fn fix_drive_paths(command: &str) -> (String, bool) {
-    let bytes = command.as_bytes();
-    let mut out = Vec::with_capacity(bytes.len());
-    let mut i = 0;
-    let mut changed = false;
-
-    while i < bytes.len() {
-        // Match drive letter path: [A-Za-z]:\ at a word boundary
-        if i + 2 < bytes.len()
-            && bytes[i].is_ascii_alphabetic()
-            && bytes[i + 1] == b':'
-            && bytes[i + 2] == b'\\'
-            && (i == 0 || !bytes[i - 1].is_ascii_alphanumeric())
-        {
-            // Emit drive letter and colon
-            out.push(bytes[i]);
-            out.push(b':');
-            i += 2;
-
-            // Walk the path, converting backslash runs to single /
-            loop {
-                if i >= bytes.len() {
-                    break;
-                }
-
-                if bytes[i] == b'\\' {
-                    // Consume all consecutive backslashes (1, 2, or 4)
-                    while i < bytes.len() && bytes[i] == b'\\' {
-                        i += 1;
-                    }
-                    // Emit a single forward slash
-                    out.push(b'/');
-                    changed = true;
-
-                    // If next char isn't a path char, path ended
-                    // (the / is a trailing separator, which is fine)
-                    if i >= bytes.len() || !is_path_char(bytes[i]) {
-                        break;
-                    }
-                } else if is_path_char(bytes[i]) {
-                    out.push(bytes[i]);
-                    i += 1;
-                } else {
-                    break;
-                }
-            }
-            continue;
-        }
+fn escalation_reason(
+    findings: &[&Finding],
+    after: u32,
+    suggestion: Option<&str>,
+    hint: Option<&str>,
+) -> String {
+    let list = findings
+        .iter()
+        .map(|f| format!("- {}: {}", f.rule, f.message))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut reason = format!(
+        "windows-bash-guard blocked this command: these checks fired in {} turns in a row \
+         without the notes being acted on. Change the command so they no longer apply.\n{}",
+        after, list
+    );
+    if let Some(suggestion) = suggestion {
+        reason.push_str(&format!("\nSuggested: {}", suggestion));
+    }
+    if let Some(hint) = hint {
+        reason.push_str(&format!("\n\n{}", hint));
+    }
+    reason
+}
 
-        out.push(bytes[i]);
-        i += 1;
+/// Blocking findings for the `file_path` of a `Write`, `Edit` or `MultiEdit`
+/// call in `data`, from the same path tests as the `drive-relative-paths` and
+/// `mixed-separators` checks, if those are in `checks`. No shell sits between
+/// the tool and the path, so nothing rewrites it: a bad path is written to
+/// as is.
+fn file_path_findings(data: &Value, checks: &[&Check]) -> Vec<Finding> {
+    let active = |name: &str| checks.iter().any(|c| c.name == name);
+    let Some(path) = data["tool_input"]["file_path"].as_str() else {
+        return Vec::new();
+    };
+    let mut findings = Vec::new();
+    if active("drive-relative-paths") && is_drive_relative(path) {
+        findings.push(Finding::block(
+            "drive-relative-paths",
+            format!(
+                "`{}` is drive-relative: with no slash after the colon, it resolves against the \
+                 current directory of drive {}. A path like `C:srcfile.txt` is usually \
+                 `C:\\src\\file.txt` with its backslashes lost to shell quoting on the way. \
+                 Give the absolute path, with forward slashes (`C:/src/file.txt`).",
+                path,
+                &path[..1]
+            ),
+        ));
     }
+    if active("mixed-separators") && has_mixed_separators(path) {
+        let fixed = forward_slashes(path);
+        findings.push(
+            Finding::block(
+                "mixed-separators",
+                format!(
+                    "`{}` mixes `/` and `\\` separators. Use forward slashes throughout: `{}`.",
+                    path, fixed
+                ),
+            )
+            .with_fix(0..path.len(), path, fixed),
+        );
+    }
+    findings
+}
 
-    (
-        String::from_utf8(out).unwrap_or_else(|_| command.to_string()),
-        changed,
+fn file_path_reason(findings: &[&Finding], tool: &str) -> String {
+    let list = findings
+        .iter()
+        .map(|f| format!("- {}: {}", f.rule, f.message))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "windows-bash-guard blocked this {} call: its `file_path` is not a usable Windows path. \
+         Retry with the path fixed.\n{}",
+        tool, list
     )
 }
 
-/// Characters that can appear within a path component (between separators).
-fn is_path_char(b: u8) -> bool {
-    b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b'~' | b'+' | b'@' | b'#')
+/// One line per registered check: name, category, severity and whether the
+/// config leaves it on. Active checks come first, in run order.
+fn list_checks(cfg: &Config) -> String {
+    let active = cfg.active_checks();
+    let inactive = CHECKS.iter().filter(|c| !active.iter().any(|a| a.name == c.name));
+    active
+        .iter()
+        .map(|c| (*c, "on"))
+        .chain(inactive.map(|c| (c, "off")))
+        .map(|(c, state)| {
+            format!("{:<20} {:<14} {:<5} {}\n", c.name, c.category, c.severity.as_str(), state)
+        })
+        .collect()
+}
+
+/// One line per registered check, in registry order: name, `matched` or
+/// `-`, and the note it produces on `command`.
+fn diagnose(command: &str) -> String {
+    CHECKS
+        .iter()
+        .map(|c| match (c.run)(command) {
+            Some(note) => format!("{:<20} {:<8} {}\n", c.name, "matched", note.replace('\n', " ")),
+            None => format!("{:<20} -\n", c.name),
+        })
+        .collect()
+}
+
+/// What windows-bash-guard would do with `command` under `cfg`: the rewritten
+/// command, if any, then one line per note of the active checks, with its
+/// check name and severity. `no issues` when there is neither.
+fn explain(command: &str, cfg: &Config) -> String {
+    let mut out = String::new();
+    if let Some(fixed) = fix_command(command, &cfg.rewrites) {
+        out.push_str(&format!("rewritten: {}\n", fixed.command));
+    }
+    for f in analyze_with(&cfg.active_checks(), command) {
+        out.push_str(&format!("{} ({}): {}\n", f.rule, f.severity.as_str(), f.message.replace('\n', " ")));
+    }
+    if out.is_empty() {
+        out.push_str("no issues\n");
+    }
+    out
+}
+
+/// The output of a command-line mode, given the arguments after the program
+/// name: `--list-checks`, `--diagnose <command>` or `--explain <command>`
+/// (the command's words may also be passed separately). `None` for anything
+/// else, in which case the hook reads its JSON from stdin as usual.
+fn cli(args: &[String], cfg: &Config) -> Option<String> {
+    let command = || args[1..].join(" ");
+    match args.first()?.as_str() {
+        "--list-checks" => Some(list_checks(cfg)),
+        "--diagnose" => Some(diagnose(&command())),
+        "--explain" => Some(explain(&command(), cfg)),
+        _ => None,
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -236,193 +675,521 @@ fn is_path_char(b: u8) -> bool {
 mod tests {
     use super::*;
 
-    // -- Fix 1: /dev/stdin ---------------------------------------------------
+    /// Notes from the default check set.
+    fn notes(command: &str) -> Vec<String> {
+        analyze_with(&Config::default().active_checks(), command)
+            .into_iter()
+            .map(|f| f.message)
+            .collect()
+    }
+
+    // -- Write/Edit file_path --------------------------------------------------
+
+    fn file_path_block(tool: &str, path: &str, cfg: &Config, output: Output) -> Emission {
+        let data = json!({"tool_name": tool, "tool_input": {"file_path": path, "content": "x"}});
+        let unused = std::env::temp_dir().join("wbg-file-path-unused.json");
+        respond(&data, cfg, &unused, &mut AllowOnce::at(unused.clone(), None), Mode::Block, output)
+    }
 
     #[test]
-    fn fixes_dev_stdin_single_quotes() {
-        let cmd =
-            r#"cat data.json | node -e "JSON.parse(require('fs').readFileSync('/dev/stdin','utf8'))""#;
-        let fixed = fix_command(cmd).unwrap();
-        assert!(fixed.command.contains("readFileSync(0,"));
-        assert!(!fixed.command.contains("/dev/stdin"));
+    fn write_to_drive_relative_file_path_is_blocked() {
+        let out = file_path_block("Write", "C:srcfile.txt", &Config::default(), Output::Text);
+        assert_eq!(out.code, 2);
+        assert!(
+            out.stderr.starts_with("windows-bash-guard blocked this Write call"),
+            "got: {}",
+            out.stderr
+        );
+        assert!(out.stderr.contains("- drive-relative-paths: `C:srcfile.txt` is drive-relative"));
+        assert!(out.stderr.contains("`C:/src/file.txt`"));
     }
 
     #[test]
-    fn fixes_dev_stdin_double_quotes() {
-        let cmd =
-            r#"curl -s url | node -e 'JSON.parse(require("fs").readFileSync("/dev/stdin","utf8"))'"#;
-        let fixed = fix_command(cmd).unwrap();
-        assert!(fixed.command.contains("readFileSync(0,"));
+    fn edit_of_mixed_separator_file_path_is_blocked_with_fix() {
+        let path = r"C:/src\lib\main.rs";
+        let out = file_path_block("Edit", path, &Config::default(), Output::Text);
+        assert_eq!(out.code, 2);
+        assert!(out.stderr.contains("Use forward slashes throughout: `C:/src/lib/main.rs`"));
+
+        let out = file_path_block("Edit", path, &Config::default(), Output::Json);
+        let v: Value = serde_json::from_str(out.stdout.trim()).unwrap();
+        assert_eq!(v["checks"], json!(["mixed-separators"]));
+        assert_eq!(v["why"][0]["span"], json!([0, path.len()]));
+        assert_eq!(v["why"][0]["suggestion"], "C:/src/lib/main.rs");
     }
 
     #[test]
-    fn ignores_dev_stdin_without_node() {
-        // curl -D /dev/stderr works in MSYS2, don't touch it
-        let cmd = "curl -s -D /dev/stderr http://localhost:3000/api";
-        assert!(fix_command(cmd).is_none());
+    fn usable_file_paths_pass() {
+        for path in ["C:/src/file.txt", r"C:\src\file.txt", "/c/src/file.txt", "src/lib.rs", "notes:v2.md"] {
+            let out = file_path_block("Write", path, &Config::default(), Output::Text);
+            assert_eq!(out.code, 0, "{}: {}", path, out.stderr);
+            assert!(out.stderr.is_empty() && out.stdout.is_empty(), "{}", path);
+        }
+        let cfg: Config = toml::from_str("disabled_checks = [\"drive-relative-paths\"]").unwrap();
+        assert_eq!(file_path_block("Write", "C:srcfile.txt", &cfg, Output::Text).code, 0);
+        assert_eq!(file_path_block("Write", r"C:/src\x", &cfg, Output::Text).code, 2);
     }
 
-    // -- Fix 2: Drive paths --------------------------------------------------
+    // -- Combined notes ---------------------------------------------------------
 
     #[test]
-    fn fixes_unquoted_path() {
-        let cmd = r"ls -la C:\src\codeflow";
-        assert_eq!(fix_command(cmd).unwrap(), "ls -la C:/src/codeflow");
+    fn context_includes_check_notes() {
+        let cmd = r"rsync -a C:\src\ dst/";
+        let context = build_context(fix_command(cmd, &Rewrites::default()).as_ref(), &notes(cmd), 0).unwrap();
+        assert!(context.contains("forward slashes"));
+        assert!(context.contains("rsync"));
     }
 
     #[test]
-    fn fixes_unquoted_rm_multiple_paths() {
-        let cmd = r"rm C:\src\a\file.json C:\src\b\file.json";
-        assert_eq!(
-            fix_command(cmd).unwrap(),
-            "rm C:/src/a/file.json C:/src/b/file.json"
-        );
+    fn every_fired_check_and_rewrite_is_reported() {
+        let cmd = r#"node -e "require('fs').readFileSync('/dev/stdin')" | cp C:\src\a.txt D:\dst"#;
+        let context = build_context(fix_command(cmd, &Rewrites::default()).as_ref(), &notes(cmd), 0).unwrap();
+        assert!(context.contains("/dev/stdin replaced with fd number"), "got: {}", context);
+        assert!(context.contains("backslash paths converted"), "got: {}", context);
+        assert!(context.contains("`cp` got unquoted backslash drive path(s)"), "got: {}", context);
+
+        let cmd = r"dd if=C:\img\disk.iso of=D:\out.img; go build -o C:\bin\app.exe";
+        let fired: Vec<String> = analyze_with(&Config::default().active_checks(), cmd).into_iter().map(|f| f.rule).collect();
+        assert_eq!(fired, vec!["dd-paths", "go-paths"]);
     }
 
     #[test]
-    fn fixes_double_quoted_path() {
-        let cmd = r#"ls -la "C:\src\project""#;
-        assert_eq!(fix_command(cmd).unwrap(), r#"ls -la "C:/src/project""#);
+    fn notes_are_capped_at_max_findings_with_a_more_note() {
+        // Three warn checks and crlf-script (info), in registry order.
+        let cmd = r"bash deploy.sh && dd if=C:\img\a.iso of=D:\b.img && go build -o C:\bin\x.exe && cat C:foo";
+        let fired = analyze_with(&Config::default().active_checks(), cmd);
+        let order: Vec<&str> = fired.iter().map(|f| f.rule.as_str()).collect();
+        assert_eq!(order, vec!["dd-paths", "go-paths", "crlf-script", "drive-relative-paths"]);
+
+        let (listed, hidden) = listed_notes(&fired, 2);
+        assert_eq!(hidden, 2);
+        assert!(listed[0].contains("dd") && listed[1].contains("go"), "got: {:?}", listed);
+        let context = build_context(None, &listed, hidden).unwrap();
+        assert!(context.ends_with("\n+2 more (raise `max_findings` to list them)"), "got: {}", context);
+
+        // The info note sorts after every warning, and no cap lists all four.
+        let (listed, hidden) = listed_notes(&fired, 3);
+        assert!(listed[2].starts_with("Drive-relative"), "got: {:?}", listed);
+        assert_eq!(hidden, 1);
+        assert_eq!(listed_notes(&fired, 0).0.len(), 4);
+        let cfg: Config = toml::from_str("max_findings = 0").unwrap();
+        assert_eq!(cfg.max_findings, 0);
+        assert_eq!(Config::default().max_findings, DEFAULT_MAX_FINDINGS);
     }
 
+    // -- Check registry ---------------------------------------------------------
+
     #[test]
-    fn fixes_trailing_backslash_quote() {
-        // "C:\path\" is broken in bash (\" eats quote).
-        // After fix: "C:/path/" — properly closed string.
-        let cmd = r#"ls -la "C:\src\el400\main\.github\workflows\""#;
+    fn registry_lists_every_check_once() {
+        let names: Vec<&str> = CHECKS.iter().map(|c| c.name).collect();
         assert_eq!(
-            fix_command(cmd).unwrap(),
-            r#"ls -la "C:/src/el400/main/.github/workflows/""#
+            names,
+            vec![
+                "rsync-paths",
+                "brace-expansion",
+                "git-clone-target",
+                "git-global-paths",
+                "python-module-paths",
+                "pattern-file-paths",
+                "dd-paths",
+                "go-paths",
+                "script-paths",
+                "copy-paths",
+                "dir-env-vars",
+                "env-prefix-paths",
+                "remote-host-colon",
+                "crlf-script",
+                "unc-paths",
+                "permission-paths",
+                "drive-relative-paths",
+                "mixed-separators",
+                "here-string-paths",
+                "reserved-filenames",
+                "colon-filenames",
+                "windows-env-vars",
+                "watch-paths",
+                "search-paths",
+                "profile-paths",
+                "inline-eval-paths",
+                "media-paths",
+                "regex-backslashes",
+                "database-paths",
+                "download-paths",
+            ]
         );
+        let listing = list_checks(&Config::default());
+        assert_eq!(listing.lines().count(), CHECKS.len());
+        assert!(listing.lines().all(|l| l.ends_with(" on")), "got:\n{}", listing);
     }
 
     #[test]
-    fn fixes_trailing_backslash_quote_in_grep() {
-        let cmd = r#"grep -r "pattern" "C:\src\codjiflo\C\src\styles\" --include="*.css""#;
-        assert_eq!(
-            fix_command(cmd).unwrap(),
-            r#"grep -r "pattern" "C:/src/codjiflo/C/src/styles/" --include="*.css""#
-        );
+    fn diagnose_lists_every_check_with_its_outcome() {
+        let table = diagnose(r"dd if=C:\a of=C:\b");
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), CHECKS.len());
+        for (line, check) in lines.iter().zip(CHECKS) {
+            assert!(line.starts_with(&format!("{} ", check.name)), "got: {}", line);
+        }
+        let dd = lines.iter().find(|l| l.starts_with("dd-paths ")).unwrap();
+        assert!(dd.contains(" matched "), "got: {}", dd);
+        assert!(dd.contains("of="), "got: {}", dd);
+        assert!(lines.iter().filter(|l| l.ends_with(" -")).count() >= CHECKS.len() - 2);
     }
 
     #[test]
-    fn fixes_double_backslash_path() {
-        // C:\\ in raw command → C:\ in bash (correct but fragile).
-        // Converting to C:/ is equally correct and more portable.
-        let cmd = r"grep pattern C:\\src\\codjiflo\\AGENTS.md";
-        assert_eq!(
-            fix_command(cmd).unwrap(),
-            "grep pattern C:/src/codjiflo/AGENTS.md"
-        );
+    fn explain_prints_each_finding_or_no_issues() {
+        let args = |a: &[&str]| -> Vec<String> { a.iter().map(|s| s.to_string()).collect() };
+        let cfg = Config::default();
+        let out = cli(&args(&["--explain", r"dd if=C:\a of=C:\b"]), &cfg).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines[0], "rewritten: dd if=C:/a of=C:/b");
+        assert!(lines[1].starts_with("dd-paths (warn): "), "got: {}", lines[1]);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(cli(&args(&["--explain", "ls", "C:/src"]), &cfg).unwrap(), "no issues\n");
+
+        // Disabled checks stay quiet, unlike `--diagnose`.
+        let cfg: Config = toml::from_str("disabled_checks = [\"dd-paths\"]").unwrap();
+        let out = cli(&args(&["--explain", r"dd if=C:\a of=C:\b"]), &cfg).unwrap();
+        assert_eq!(out, "rewritten: dd if=C:/a of=C:/b\n");
+        assert!(cli(&args(&["--bogus"]), &cfg).is_none());
+        assert!(cli(&[], &cfg).is_none());
     }
 
     #[test]
-    fn fixes_quad_backslash_in_node_e() {
-        // C:\\\\ in raw command → after bash: C:\\ → after JS: C:\ (correct
-        // but fragile). Forward slashes avoid the entire escaping chain.
-        let cmd = r#"node -e "require('fs').readFileSync('C:\\\\src\\\\file.json','utf8')""#;
-        assert_eq!(
-            fix_command(cmd).unwrap(),
-            r#"node -e "require('fs').readFileSync('C:/src/file.json','utf8')""#
-        );
+    fn disabled_checks_do_not_run() {
+        let cfg: Config = toml::from_str("disabled_checks = [\"dd-paths\"]").unwrap();
+        let active = cfg.active_checks();
+        assert_eq!(active.len(), CHECKS.len() - 1);
+        assert!(analyze_with(&active, r"dd if=C:\a of=C:\b").is_empty());
+        assert!(list_checks(&cfg).lines().last().unwrap().starts_with("dd-paths "));
+        assert!(list_checks(&cfg).lines().last().unwrap().ends_with(" off"));
     }
 
     #[test]
-    fn fixes_double_backslash_in_node_e() {
-        let cmd = r#"node -e "require('fs').readFileSync('C:\\tmp\\kv-ns.json','utf8')""#;
-        assert_eq!(
-            fix_command(cmd).unwrap(),
-            r#"node -e "require('fs').readFileSync('C:/tmp/kv-ns.json','utf8')""#
-        );
+    fn check_order_runs_listed_checks_first() {
+        let cfg: Config = toml::from_str("check_order = [\"go-paths\", \"dd-paths\"]").unwrap();
+        let names: Vec<&str> = cfg.active_checks().iter().map(|c| c.name).collect();
+        assert_eq!(&names[..3], &["go-paths", "dd-paths", "rsync-paths"]);
+        assert_eq!(names.len(), CHECKS.len());
     }
 
     #[test]
-    fn fixes_combined_dev_stdin_and_path() {
-        let cmd = r#"cat C:\\tmp\\data.json | node -e "JSON.parse(require('fs').readFileSync('/dev/stdin','utf8'))""#;
-        let fixed = fix_command(cmd).unwrap();
-        assert!(fixed.command.contains("C:/tmp/data.json"));
-        assert!(fixed.command.contains("readFileSync(0,"));
+    fn rewrites_can_be_turned_off() {
+        let cmd = r#"node -e "require('fs').readFileSync('/dev/stdin')" C:\src\a.js"#;
+        let both = fix_command(cmd, &Rewrites::default()).unwrap();
+        assert_eq!(both, r#"node -e "require('fs').readFileSync(0)" C:/src/a.js"#);
+
+        let cfg: Config = toml::from_str("[rewrites]\ndrive_paths = false\n").unwrap();
+        assert!(cfg.rewrites.dev_stdin);
+        let fixed = fix_command(cmd, &cfg.rewrites).unwrap();
+        assert_eq!(fixed, r#"node -e "require('fs').readFileSync(0)" C:\src\a.js"#);
+        assert!(!fixed.context.contains("forward slashes"), "got: {}", fixed.context);
+
+        let cfg: Config = toml::from_str("[rewrites]\ndev_stdin = false\ndrive_paths = false\n").unwrap();
+        assert!(fix_command(cmd, &cfg.rewrites).is_none());
+        assert!(toml::from_str::<Config>("[rewrites]\nbackslashes = false\n").is_err());
     }
 
-    // -- No-op cases ---------------------------------------------------------
+    #[test]
+    fn failure_mode_keys_turn_off_the_drive_path_rewrite() {
+        for key in ["trailing_backslash_quote", "node_eval_backslash", "unquoted_backslash"] {
+            let cfg: Config = toml::from_str(&format!("[rewrites]\n{} = false\n", key)).unwrap();
+            assert!(cfg.rewrites.dev_stdin, "{}", key);
+            assert!(!cfg.rewrites.drive_paths, "{}", key);
+        }
+        let cfg: Config = toml::from_str("[rewrites]\nunquoted_backslash = true\n").unwrap();
+        assert!(cfg.rewrites.drive_paths);
+        let cfg: Config = toml::from_str("[rewrites]\ndev_stdin = false\n").unwrap();
+        assert!(!cfg.rewrites.dev_stdin && cfg.rewrites.drive_paths);
+    }
 
     #[test]
-    fn ignores_forward_slash_path() {
-        let cmd = "ls -la C:/src/project";
-        assert!(fix_command(cmd).is_none());
+    fn reports_unknown_check_names() {
+        let cfg: Config =
+            toml::from_str("disabled_checks = [\"dd-path\"]\ncheck_order = [\"go-paths\"]").unwrap();
+        assert_eq!(cfg.unknown_checks(), vec!["dd-path"]);
     }
 
+    // -- Allowlist ---------------------------------------------------------------
+
     #[test]
-    fn ignores_unix_path() {
-        let cmd = "cd /c/src/project && ls";
-        assert!(fix_command(cmd).is_none());
+    fn allowlisted_command_bypasses_rewrite_and_checks() {
+        let command = r"cp C:\src\a.txt C:\dst";
+        assert!(fix_command(command, &Rewrites::default()).is_some());
+        assert!(!analyze_with(&Config::default().active_checks(), command).is_empty());
+
+        assert!(allowlisted(command, r"^cp C:\\src\\"));
+        assert!(allowlisted(command, "^rsync ;\n^cp "));
+        assert!(!allowlisted(command, "^rsync ; ^scp "));
+        assert!(!allowlisted(command, ""));
     }
 
     #[test]
-    fn ignores_clean_node_e() {
-        let cmd = r#"node -e "console.log('hello')""#;
-        assert!(fix_command(cmd).is_none());
+    fn invalid_allow_patterns_are_skipped() {
+        assert!(allowlisted("node wrap.js /dev/stdin", "([unclosed;/dev/stdin"));
+        assert!(!allowlisted("node wrap.js /dev/stdin", "([unclosed"));
     }
 
+    // -- Escalation -------------------------------------------------------------
+
     #[test]
-    fn ignores_url_with_colon() {
-        let cmd = "curl https://example.com:8080/api";
-        assert!(fix_command(cmd).is_none());
+    fn repeated_check_escalates_to_block() {
+        let dir = std::env::temp_dir().join(format!("wbg-streaks-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("streaks.json");
+        let cfg = Config::default();
+        let command = r"rsync -a C:\src\ dst/";
+
+        let mut escalated = Vec::new();
+        for _ in 0..cfg.escalate_after {
+            let fired = analyze_with(&cfg.active_checks(), command);
+            let names = escalating(&fired);
+            let mut streaks = Streaks::load(&path);
+            escalated = streaks.record(&names, None, cfg.escalate_after);
+            streaks.save(&path);
+        }
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(escalated, vec!["rsync-paths", "remote-host-colon"]);
+
+        let mut decision = Decision::new(HOOK, Event::PreToolUse).with_min_block(Severity::Block);
+        decision.push(Finding::block("rsync-paths", "rsync got backslash drive path(s)"));
+        let out = escalate(&decision, cfg.escalate_after, None, None, Mode::Block, Output::Text);
+        assert_eq!(out.code, 2);
+        assert!(out.stderr.contains("fired in 3 turns in a row"), "got: {}", out.stderr);
+        assert!(out.stderr.contains("- rsync-paths: rsync got"), "got: {}", out.stderr);
     }
 
     #[test]
-    fn does_not_match_mid_word_colon() {
-        // "Error:" has 'r' before ':' which is alphanumeric → no match
-        let cmd = r#"echo "Error: something failed""#;
-        assert!(fix_command(cmd).is_none());
+    fn suggests_command_with_unquoted_drive_paths_fixed() {
+        assert_eq!(suggest_fix(r"ls C:\src\foo").as_deref(), Some("ls C:/src/foo"));
+        assert_eq!(
+            suggest_fix(r"cp C:\src\a.txt D:\\dst\\ && cat C:\my\ dir\notes.md").as_deref(),
+            Some(r"cp C:/src/a.txt D:/dst/ && cat C:/my\ dir/notes.md")
+        );
+        // Quoted regions stay as written.
+        assert_eq!(
+            suggest_fix(r#"cp "C:\keep \"me\"" 'D:\also\kept' C:\out\x"#).as_deref(),
+            Some(r#"cp "C:\keep \"me\"" 'D:\also\kept' C:/out/x"#)
+        );
+        assert_eq!(suggest_fix(r"ls 'C:\src' C:/src ABC:\x"), None);
     }
 
-    // -- Edge cases ----------------------------------------------------------
+    #[test]
+    fn block_message_includes_suggestion() {
+        let mut decision = Decision::new(HOOK, Event::PreToolUse).with_min_block(Severity::Block);
+        decision.push(Finding::block("dd-paths", "dd got backslash drive path(s)"));
+        let out = escalate(&decision, 3, Some("dd if=C:/a of=C:/b"), None, Mode::Block, Output::Text);
+        assert!(out.stderr.trim_end().ends_with("\nSuggested: dd if=C:/a of=C:/b"), "got: {}", out.stderr);
+    }
 
     #[test]
-    fn fixes_path_with_dots() {
-        let cmd = r"ls C:\src\el400\main\.github";
-        assert_eq!(fix_command(cmd).unwrap(), "ls C:/src/el400/main/.github");
+    fn warn_mode_reports_escalation_without_blocking() {
+        for (value, mode) in [
+            (None, Mode::Block),
+            (Some("block"), Mode::Block),
+            (Some("bogus"), Mode::Block),
+            (Some("warn"), Mode::Warn),
+            (Some(" WARN "), Mode::Warn),
+        ] {
+            assert_eq!(Mode::parse(value), mode, "WINDOWS_BASH_GUARD_MODE={:?}", value);
+        }
+
+        let mut decision = Decision::new(HOOK, Event::PreToolUse).with_min_block(Severity::Block);
+        decision.push(Finding::block("dd-paths", "dd got backslash drive path(s)"));
+        let out = escalate(&decision, 3, None, None, Mode::parse(Some("warn")), Output::Json);
+        assert_eq!(out.code, 0);
+        assert!(out.stderr.contains("- dd-paths: dd got"), "got: {}", out.stderr);
+        assert!(out.stdout.is_empty(), "got: {}", out.stdout);
     }
 
     #[test]
-    fn preserves_non_path_backslashes() {
-        // \n in echo is NOT a drive path — should not be touched
-        let cmd = r#"echo "line1\nline2""#;
-        assert!(fix_command(cmd).is_none());
+    fn json_output_reports_block_on_stdout_with_check_name() {
+        assert_eq!(Output::parse(None), Output::Text);
+        assert_eq!(Output::parse(Some(" JSON ")), Output::Json);
+
+        let mut decision = Decision::new(HOOK, Event::PreToolUse).with_min_block(Severity::Block);
+        let command = r"dd if=C:\img\disk.iso of=C:\out.img";
+        for f in analyze_with(&Config::default().active_checks(), command) {
+            decision.push(escalation_finding(&f.rule, &f.message, command));
+        }
+        let text = escalate(&decision, 3, None, None, Mode::Block, Output::Text);
+        assert_eq!(text.code, 2);
+        assert!(text.stdout.is_empty());
+
+        let out = escalate(&decision, 3, None, None, Mode::Block, Output::Json);
+        assert_eq!(out.code, 0);
+        assert!(out.stderr.is_empty(), "got: {}", out.stderr);
+        let v: Value = serde_json::from_str(out.stdout.trim()).unwrap();
+        assert_eq!(v["decision"], "block");
+        assert_eq!(v["check"], "dd-paths");
+        assert_eq!(v["checks"], json!(["dd-paths"]));
+        assert_eq!(v["reason"].as_str().unwrap(), text.stderr.trim_end());
+        assert_eq!(v.as_object().unwrap().len(), 5);
     }
 
     #[test]
-    fn fixes_path_after_equals() {
-        let cmd = r"VAR=C:\src\project echo test";
-        assert_eq!(
-            fix_command(cmd).unwrap(),
-            "VAR=C:/src/project echo test"
+    fn json_output_explains_each_path_with_span_and_suggestion() {
+        let command = r#"ls "C:\quoted\dir" && rsync -av C:\src\dir/ D:\\backup\\x"#;
+        let mut decision = Decision::new(HOOK, Event::PreToolUse).with_min_block(Severity::Block);
+        for f in analyze_with(&Config::default().active_checks(), command) {
+            decision.push(escalation_finding(&f.rule, &f.message, command));
+        }
+        let out = escalate(&decision, 3, suggest_fix(command).as_deref(), None, Mode::Block, Output::Json);
+        let v: Value = serde_json::from_str(out.stdout.trim()).unwrap();
+        let why = v["why"].as_array().unwrap();
+        let rsync: Vec<&Value> = why.iter().filter(|w| w["check"] == "rsync-paths").collect();
+        assert_eq!(rsync.len(), 2, "got: {:#}", v["why"]);
+        for (w, matched, suggestion) in [
+            (rsync[0], r"C:\src\dir/", "/c/src/dir/"),
+            (rsync[1], r"D:\\backup\\x", "/d/backup/x"),
+        ] {
+            let span = |i: usize| w["span"][i].as_u64().unwrap() as usize;
+            assert_eq!(&command[span(0)..span(1)], matched);
+            assert_eq!(w["matched"], matched);
+            assert_eq!(w["suggestion"], suggestion);
+        }
+        assert!(why.iter().all(|w| w["matched"] != r"C:\quoted\dir"), "got: {:#}", v["why"]);
+        let reason = v["reason"].as_str().unwrap();
+        assert!(
+            reason.ends_with(r#"Suggested: ls "C:\quoted\dir" && rsync -av /c/src/dir/ /d/backup/x"#),
+            "got: {}",
+            reason
         );
     }
 
-    // -- Context messages -----------------------------------------------------
+    #[test]
+    fn streak_ends_when_check_does_not_fire() {
+        let mut streaks = Streaks::default();
+        assert!(streaks.record(&["dd-paths"], Some("t1"), 2).is_empty());
+        assert!(streaks.record(&["go-paths"], Some("t2"), 2).is_empty());
+        assert!(streaks.record(&["dd-paths"], Some("t3"), 2).is_empty());
+        assert_eq!(streaks.record(&["dd-paths", "go-paths"], Some("t4"), 2), vec!["dd-paths"]);
+
+        // Without a transcript, each call is a turn.
+        let mut streaks = Streaks::default();
+        assert!(streaks.record(&["dd-paths"], None, 2).is_empty());
+        assert!(streaks.record(&[], None, 2).is_empty());
+        assert!(streaks.record(&["dd-paths"], None, 2).is_empty());
+        assert_eq!(streaks.record(&["dd-paths"], None, 2), vec!["dd-paths"]);
+    }
 
     #[test]
-    fn context_mentions_backslash() {
-        let cmd = r"ls C:\src\project";
-        let fixed = fix_command(cmd).unwrap();
-        assert!(fixed.context.contains("backslash"));
-        assert!(fixed.context.contains("forward slash"));
+    fn calls_within_one_turn_count_once() {
+        let mut streaks = Streaks::default();
+        for _ in 0..5 {
+            assert!(streaks.record(&["dd-paths"], Some("t1"), 3).is_empty());
+        }
+        // A clean call doesn't end the streak while the turn still counts.
+        assert!(streaks.record(&[], Some("t2"), 3).is_empty());
+        assert!(streaks.record(&["dd-paths"], Some("t2"), 3).is_empty());
+        assert_eq!(streaks.record(&["dd-paths"], Some("t3"), 3), vec!["dd-paths"]);
+        assert_eq!(streaks.record(&["dd-paths"], Some("t3"), 3), vec!["dd-paths"]);
     }
 
     #[test]
-    fn context_mentions_dev_stdin() {
-        let cmd = r#"node -e "require('fs').readFileSync('/dev/stdin','utf8')""#;
-        let fixed = fix_command(cmd).unwrap();
-        assert!(fixed.context.contains("/dev/stdin"));
+    fn turn_comes_from_the_last_user_message_in_the_transcript() {
+        let dir = scratch("turns");
+        let path = dir.join("transcript.jsonl");
+        let user = |uuid: &str| json!({"type": "user", "uuid": uuid, "message": {"content": "go"}});
+        let result = json!({"type": "user", "uuid": "r", "message": {"content": [{"type": "tool_result"}]}});
+        let data = json!({"transcript_path": path.to_str().unwrap()});
+
+        assert_eq!(current_turn(&data), None);
+        fs::write(&path, format!("{}\n{}\n", user("u1"), result)).unwrap();
+        assert_eq!(current_turn(&data).as_deref(), Some("u1"));
+        fs::write(&path, format!("{}\n{}\n{}\n", user("u1"), result, user("u2"))).unwrap();
+        assert_eq!(current_turn(&data).as_deref(), Some("u2"));
+        let _ = fs::remove_dir_all(&dir);
     }
 
     #[test]
-    fn context_mentions_both_fixes() {
-        let cmd = r#"cat C:\\tmp\\data.json | node -e "JSON.parse(require('fs').readFileSync('/dev/stdin','utf8'))""#;
-        let fixed = fix_command(cmd).unwrap();
-        assert!(fixed.context.contains("/dev/stdin"));
-        assert!(fixed.context.contains("backslash"));
+    fn zero_never_escalates() {
+        let cfg: Config = toml::from_str("escalate_after = 0").unwrap();
+        let mut streaks = Streaks::default();
+        for _ in 0..10 {
+            assert!(streaks.record(&["dd-paths"], None, cfg.escalate_after).is_empty());
+        }
+    }
+
+    #[test]
+    fn crlf_hint_never_escalates() {
+        let fired = analyze_with(&Config::default().active_checks(), "./run.sh");
+        assert_eq!(fired.len(), 1);
+        assert!(escalating(&fired).is_empty());
+    }
+
+    // -- Allow once ---------------------------------------------------------------
+
+    fn scratch(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("wbg-allow-once-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn blocked_file_path_runs_once_after_user_approval() {
+        let dir = scratch("file-path");
+        let (streaks, approvals) = (dir.join("streaks.json"), dir.join("allow-once.json"));
+        let path = "C:srcfile.txt";
+        let data = json!({"tool_name": "Write", "tool_input": {"file_path": path, "content": "x"}});
+        let cfg = Config::default();
+        let call = |approved: Option<String>| {
+            respond(&data, &cfg, &streaks, &mut AllowOnce::at(approvals.clone(), approved), Mode::Block, Output::Text)
+        };
+
+        let out = call(None);
+        assert_eq!(out.code, 2);
+        let hint = format!("WINDOWS_BASH_GUARD_ALLOW_ONCE={}", allow_once::hash(path));
+        assert!(out.stderr.contains(&hint), "got: {}", out.stderr);
+        assert_eq!(call(None).code, 2, "re-submitting alone must not get through");
+
+        let out = call(Some(allow_once::hash(path)));
+        assert_eq!((out.code, out.stdout.as_str(), out.stderr.as_str()), (0, "", ""));
+        assert_eq!(call(Some(allow_once::hash(path))).code, 2, "the approval is used up");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn escalated_command_runs_once_after_user_approval() {
+        let dir = scratch("escalation");
+        let (streaks, approvals) = (dir.join("streaks.json"), dir.join("allow-once.json"));
+        let command = r"dd if=C:\img\disk.iso of=C:\out.img";
+        let data = json!({"tool_name": "Bash", "session_id": "s1", "tool_input": {"command": command}});
+        let cfg = Config::default();
+        let call = |approved: Option<String>| {
+            respond(&data, &cfg, &streaks, &mut AllowOnce::at(approvals.clone(), approved), Mode::Block, Output::Text)
+        };
+
+        for _ in 1..cfg.escalate_after {
+            assert_eq!(call(None).code, 0);
+        }
+        let out = call(None);
+        assert_eq!(out.code, 2);
+        let hint = format!("WINDOWS_BASH_GUARD_ALLOW_ONCE={}", allow_once::hash(command));
+        assert!(out.stderr.contains(&hint), "got: {}", out.stderr);
+        assert_eq!(call(None).code, 2, "re-submitting alone must not get through");
+
+        let out = call(Some(allow_once::hash(command)));
+        assert_eq!(out.code, 0, "got: {}", out.stderr);
+        let v: Value = serde_json::from_str(out.stdout.trim()).unwrap();
+        assert_eq!(v["hookSpecificOutput"]["updatedInput"]["command"], "dd if=C:/img/disk.iso of=C:/out.img");
+        assert_eq!(call(Some(allow_once::hash(command))).code, 2, "the approval is used up");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    // -- Snapshots ------------------------------------------------------------
+    //
+    // The full text Claude sees is part of the hook's behavior, so wording
+    // changes should show up in review. After an intentional change, run
+    // `cargo insta review` (or `INSTA_UPDATE=always cargo test`) from this
+    // crate and commit the updated files under `src/snapshots/`.
+
+    #[test]
+    fn snapshot_combined_context() {
+        let command = r"rsync -a C:\src\ C:\dst\";
+        let fixed = fix_command(command, &Rewrites::default());
+        let ctx = build_context(fixed.as_ref(), &notes(command), 0).unwrap();
+        insta::assert_snapshot!(ctx);
     }
 }
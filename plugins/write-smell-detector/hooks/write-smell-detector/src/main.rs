@@ -0,0 +1,1885 @@
+//! PostToolUse hook that reviews what Claude just wrote (Write/Edit/MultiEdit)
+//! for risky shortcuts that are cheap to catch at write time.
+//!
+//! Checks:
+//! 1. Permissive config — CORS wildcards (`Access-Control-Allow-Origin: *`,
+//!    `allow_all_origins = true`, …) and `0.0.0.0` binds. The banlist is
+//!    configurable via `.claude/write-smell-detector.toml`.
+//! 2. Dynamic eval — shell `eval "$var"`, JS `eval(x)` / `new Function(x)`,
+//!    Python `exec(x)` / `eval(x)` on anything other than a constant literal.
+//! 3. Weakened type — an edit that swaps a concrete type for `any`, `unknown`,
+//!    `Any`, `Object`, `interface{}` or a `dyn` trait object in an otherwise
+//!    unchanged line (`x: string` → `x: any`).
+//! 4. Masked flakiness — an edit to a test file that raises a timeout
+//!    (`timeout: 1000` → `timeout: 30000`) or adds a retry/flaky annotation
+//!    (`jest.retryTimes(3)`, `@pytest.mark.flaky`, `this.retries(2)`).
+//! 5. File-level suppression — `// @ts-nocheck`, `#![allow(warnings)]`,
+//!    `#![allow(clippy::all)]`, a module-top `# type: ignore`, or a bare
+//!    `/* eslint-disable */`. Blocks: scoped suppressions are the alternative.
+//! 6. Unbalanced delimiters — a write to a brace-language file (Rust, JS/TS,
+//!    C-family, Java, Go, JSON) that leaves `()`, `[]` or `{}` unbalanced or a
+//!    string unterminated, usually a truncated write. Strings, comments and
+//!    regex literals are skipped; anything the scan can't read with
+//!    confidence (raw strings, text blocks, an open block comment) is skipped.
+//! 7. Hardcoded localhost — a `localhost` or `127.0.0.1` added outside test
+//!    files and dev-only files (`*.dev.*`, `.env*`, docs, …; configurable),
+//!    where it breaks as soon as the code runs on another host. A line that
+//!    also reads the environment (`env::var("HOST").unwrap_or("localhost")`)
+//!    is a configured default and is not flagged.
+//! 8. Placeholder URL — an `http(s)://` URL whose host is a placeholder
+//!    (`example.com` and its subdomains, `TODO`, `your-domain.com`, …;
+//!    configurable) added outside test and dev-only files, where it fails as
+//!    soon as the code makes a real request.
+//! 9. Hidden error log — an edit that downgrades an error log
+//!    (`log.error(…)` → `log.debug(…)`, `console.error` → `console.log`),
+//!    comments one out, or puts it behind `if (false)`.
+//! 10. Tautological assertion — an assertion that can't fail (`assert(true)`,
+//!     `assertTrue(true)`, `expect(true).toBe(true)`, `assert_eq!(1, 1)`)
+//!     added to a test file or a Rust file. Blocks: it silences a test.
+//!
+//! Only text *added* by the tool call is scanned: a pattern already present in
+//! an Edit's `old_string` is not reported again. Findings from every check but
+//! 5 and 10 are advisory and reach Claude as `additionalContext`.
+
+use hook_common::config;
+use hook_common::dead_letter;
+use hook_common::doctor::{self, Doctor};
+use hook_common::decision::{Decision, Event, Finding};
+use hook_common::edits::{is_test_path, WriteOp};
+use hook_common::false_positive;
+use serde::Deserialize;
+use serde_json::Value;
+use std::io::{self, Read};
+use std::process;
+
+const HOOK: &str = "write-smell-detector";
+
+/// Insecure-config banlist. Matched against added text after both sides are
+/// normalized (see [`normalize`]), so `Access-Control-Allow-Origin: *` also
+/// catches `"access-control-allow-origin":"*"`.
+const INSECURE_PATTERNS: &[&str] = &[
+    // CORS wildcards
+    "Access-Control-Allow-Origin: *",
+    "Access-Control-Allow-Origin, *",
+    "allow_all_origins = true",
+    "AllowAllOrigins: true",
+    "CORS_ORIGIN_ALLOW_ALL = True",
+    "CORS_ALLOW_ALL_ORIGINS = True",
+    "allow_origins=[*]",
+    "origin: *",
+    // Listen on every interface
+    "host = 0.0.0.0",
+    "host: 0.0.0.0",
+    "bind = 0.0.0.0",
+    "bind: 0.0.0.0",
+    "listen 0.0.0.0",
+];
+
+/// Files where a hardcoded localhost or a placeholder URL is expected:
+/// local-only config, env files, examples and docs. Test files are always
+/// exempt.
+const DEV_FILE_GLOBS: &[&str] = &[
+    "*.dev.*",
+    "*-dev.*",
+    "*_dev.*",
+    "*.development.*",
+    "*.local.*",
+    ".env*",
+    "*.md",
+    "docs/**",
+    "dev/**",
+    "examples/**",
+    "fixtures/**",
+];
+
+/// Hosts that stand in for a real endpoint. A subdomain of one
+/// (`api.example.com`) is a placeholder too.
+const PLACEHOLDER_HOSTS: &[&str] = &[
+    "example.com",
+    "example.org",
+    "example.net",
+    "todo",
+    "todo_url",
+    "changeme",
+    "placeholder",
+    "your-domain.com",
+    "yourdomain.com",
+    "your-api.com",
+];
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Config {
+    /// Replaces the built-in insecure-config banlist.
+    insecure_patterns: Option<Vec<String>>,
+    /// Appended to the (built-in or replaced) banlist.
+    extra_insecure_patterns: Vec<String>,
+    /// Only files matching one of these are reviewed (all when empty).
+    include_globs: Vec<String>,
+    /// Files matching one of these are never reviewed.
+    exclude_globs: Vec<String>,
+    /// Replaces the built-in globs of files where localhost is allowed.
+    dev_file_globs: Option<Vec<String>>,
+    /// Appended to the (built-in or replaced) dev-file globs.
+    extra_dev_file_globs: Vec<String>,
+    /// Replaces the built-in placeholder URL hosts.
+    placeholder_hosts: Option<Vec<String>>,
+    /// Appended to the (built-in or replaced) placeholder hosts.
+    extra_placeholder_hosts: Vec<String>,
+}
+
+impl Config {
+    fn insecure_patterns(&self) -> Vec<String> {
+        let mut patterns = match &self.insecure_patterns {
+            Some(p) => p.clone(),
+            None => INSECURE_PATTERNS.iter().map(|s| s.to_string()).collect(),
+        };
+        patterns.extend(self.extra_insecure_patterns.iter().cloned());
+        patterns
+    }
+
+    fn dev_file_globs(&self) -> Vec<String> {
+        let mut globs = match &self.dev_file_globs {
+            Some(g) => g.clone(),
+            None => DEV_FILE_GLOBS.iter().map(|s| s.to_string()).collect(),
+        };
+        globs.extend(self.extra_dev_file_globs.iter().cloned());
+        globs
+    }
+
+    fn placeholder_hosts(&self) -> Vec<String> {
+        let mut hosts = match &self.placeholder_hosts {
+            Some(h) => h.clone(),
+            None => PLACEHOLDER_HOSTS.iter().map(|s| s.to_string()).collect(),
+        };
+        hosts.extend(self.extra_placeholder_hosts.iter().cloned());
+        hosts
+    }
+}
+
+fn main() {
+    if doctor::requested() {
+        let mut d = Doctor::new(HOOK);
+        let cfg: Config = d.config();
+        d.count("insecure patterns", cfg.insecure_patterns().len());
+        d.count("dev file globs", cfg.dev_file_globs().len());
+        d.count("placeholder hosts", cfg.placeholder_hosts().len());
+        d.exit();
+    }
+
+    let mut input = String::new();
+    if io::stdin().read_to_string(&mut input).is_err() || !config::enabled_here() {
+        process::exit(0);
+    }
+    dead_letter::guard(HOOK, &input, || run(&input));
+}
+
+fn run(input: &str) {
+    let data: Value = match serde_json::from_str(input) {
+        Ok(v) => v,
+        Err(_) => process::exit(0),
+    };
+
+    let tool_name = data["tool_name"].as_str().unwrap_or("");
+    let Some(write) = WriteOp::from_tool_input(tool_name, &data["tool_input"]) else {
+        process::exit(0);
+    };
+
+    let cfg: Config = config::load(HOOK);
+    let session_id = data["session_id"].as_str().unwrap_or("unknown");
+    let mut decision = Decision::new(HOOK, Event::PostToolUse).with_session(session_id);
+    for f in review(&write, &cfg) {
+        if write.file_path.is_empty() {
+            decision.push(f);
+        } else {
+            decision.push(f.with_location(&write.file_path, None));
+        }
+    }
+
+    false_positive::report_if_requested(HOOK, input, decision.findings());
+    decision.emit(build_reason).exit();
+}
+
+/// Run every check on `write`, unless its file is outside the configured
+/// `include_globs`/`exclude_globs`.
+fn review(write: &WriteOp, cfg: &Config) -> Vec<Finding> {
+    if !write.file_path.is_empty()
+        && !config::path_in_scope(&write.file_path, &cfg.include_globs, &cfg.exclude_globs)
+    {
+        return Vec::new();
+    }
+    let mut findings = check_permissive_config(write, &cfg.insecure_patterns());
+    findings.extend(check_dynamic_eval(write));
+    findings.extend(check_weakened_type(write));
+    findings.extend(check_masked_flakiness(write));
+    findings.extend(check_file_suppression(write));
+    findings.extend(check_unbalanced(write));
+    findings.extend(check_hardcoded_localhost(write, &cfg.dev_file_globs()));
+    findings.extend(check_placeholder_url(write, &cfg.placeholder_hosts(), &cfg.dev_file_globs()));
+    findings.extend(check_hidden_error_log(write));
+    findings.extend(check_tautological_assertion(write));
+    findings
+}
+
+/// Whether `needle` (already normalized) appears in new text but not in the
+/// text it replaced.
+fn adds(write: &WriteOp, needle: &str) -> bool {
+    write
+        .hunks
+        .iter()
+        .any(|(old, new)| normalize(new).contains(needle) && !normalize(old).contains(needle))
+}
+
+// ---------------------------------------------------------------------------
+// Check 1: Permissive config
+// ---------------------------------------------------------------------------
+
+fn check_permissive_config(write: &WriteOp, patterns: &[String]) -> Vec<Finding> {
+    let hits: Vec<&str> = patterns
+        .iter()
+        .filter(|p| {
+            let needle = normalize(p);
+            !needle.is_empty() && adds(write, &needle)
+        })
+        .map(String::as_str)
+        .collect();
+
+    if hits.is_empty() {
+        return Vec::new();
+    }
+
+    let list = hits
+        .iter()
+        .map(|p| format!("`{}`", p))
+        .collect::<Vec<_>>()
+        .join(", ");
+    vec![Finding::warn(
+        "permissive-config",
+        format!(
+            "Permissive config written to {}: {}. Wildcard CORS and 0.0.0.0 binds are \
+             security shortcuts once this runs outside your machine. Scope it (explicit \
+             allowed origins, bind to 127.0.0.1 or a configured host) or confirm with the \
+             user that it is intended.",
+            display_path(&write.file_path),
+            list
+        ),
+    )]
+}
+
+/// Lowercase and drop whitespace and quotes so formatting differences between
+/// languages (`key: "*"`, `"key":"*"`, `key = '*'`) don't matter.
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| !c.is_whitespace() && !matches!(c, '"' | '\'' | '`'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn display_path(path: &str) -> String {
+    if path.is_empty() {
+        "this file".to_string()
+    } else {
+        format!("`{}`", path)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Check 2: Dynamic eval
+// ---------------------------------------------------------------------------
+
+fn check_dynamic_eval(write: &WriteOp) -> Vec<Finding> {
+    let hits: Vec<&str> = added_lines(write)
+        .into_iter()
+        .filter(|l| is_dynamic_eval(l))
+        .collect();
+    if hits.is_empty() {
+        return Vec::new();
+    }
+
+    let list = hits
+        .iter()
+        .map(|l| format!("`{}`", l))
+        .collect::<Vec<_>>()
+        .join(", ");
+    vec![Finding::warn(
+        "dynamic-eval",
+        format!(
+            "Dynamic eval/exec written to {}: {}. Evaluating a runtime string runs whatever \
+             it contains (an injection risk if any part comes from input) and hides the code \
+             from readers and linters. Call the intended code directly, or dispatch to an \
+             allowlisted set of actions.",
+            display_path(&write.file_path),
+            list
+        ),
+    )]
+}
+
+/// Trimmed lines of new text that aren't (trimmed) lines of the text they
+/// replace.
+fn added_lines(write: &WriteOp) -> Vec<&str> {
+    let mut lines = Vec::new();
+    for (old, new) in &write.hunks {
+        let before: Vec<&str> = old.lines().map(str::trim).collect();
+        for line in new.lines().map(str::trim) {
+            if !line.is_empty() && !before.contains(&line) && !lines.contains(&line) {
+                lines.push(line);
+            }
+        }
+    }
+    lines
+}
+
+fn is_dynamic_eval(line: &str) -> bool {
+    if line.starts_with('#') || line.starts_with("//") {
+        return false;
+    }
+    for (pos, kw) in keyword_positions(line, &["eval", "exec", "Function"]) {
+        let after = &line[pos + kw.len()..];
+        let rest = after.trim_start();
+        if let Some(args) = rest.strip_prefix('(') {
+            if kw == "Function" && !line[..pos].trim_end().ends_with("new") {
+                continue;
+            }
+            let args = call_args(args).trim();
+            if !args.is_empty() && !is_constant_literal(args) {
+                return true;
+            }
+        } else if kw == "eval" && after.starts_with([' ', '\t']) {
+            // Shell `eval words...`: dynamic once a variable is expanded.
+            if has_shell_variable(rest) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Byte offsets of `keywords` used as standalone identifiers, i.e. not part of
+/// a longer name, a method call (`model.eval()`), or a variable (`$eval`).
+fn keyword_positions<'a>(line: &str, keywords: &[&'a str]) -> Vec<(usize, &'a str)> {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    let mut out = Vec::new();
+    for &kw in keywords {
+        for (pos, _) in line.match_indices(kw) {
+            let before = line[..pos].chars().next_back();
+            let after = line[pos + kw.len()..].chars().next();
+            if before.is_some_and(|c| is_ident(c) || c == '.' || c == '$')
+                || after.is_some_and(is_ident)
+            {
+                continue;
+            }
+            out.push((pos, kw));
+        }
+    }
+    out
+}
+
+/// Text up to the `)` closing a call whose `(` was just consumed (or to the
+/// end of the line when the call spans lines).
+fn call_args(text: &str) -> &str {
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return &text[..i],
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    text
+}
+
+/// A single string literal with no interpolation: `'…'`, `"…"`, or a template
+/// literal without `${`.
+fn is_constant_literal(text: &str) -> bool {
+    let Some(quote) = text.chars().next().filter(|c| matches!(c, '\'' | '"' | '`')) else {
+        return false;
+    };
+    text.len() >= 2
+        && text.ends_with(quote)
+        && !text[1..text.len() - 1].contains(quote)
+        && !(quote == '`' && text.contains("${"))
+}
+
+/// `$name`, `${name}`, `$1`, `$@`, `$*` — but not `$(…)` on its own.
+fn has_shell_variable(text: &str) -> bool {
+    text.as_bytes().windows(2).any(|w| {
+        w[0] == b'$' && (w[1].is_ascii_alphanumeric() || matches!(w[1], b'_' | b'{' | b'@' | b'*'))
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Check 3: Weakened type
+// ---------------------------------------------------------------------------
+
+/// Type tokens that accept (nearly) anything.
+const WEAK_TYPES: &[&str] = &["any", "unknown", "Any", "Object", "dyn", "interface{}"];
+
+fn check_weakened_type(write: &WriteOp) -> Vec<Finding> {
+    let mut hits: Vec<(String, String)> = Vec::new();
+    for (old, new) in &write.hunks {
+        let old_lines: Vec<&str> = old.lines().map(str::trim).collect();
+        for line in new.lines().map(str::trim) {
+            if old_lines.contains(&line) || line.starts_with("//") || line.starts_with('#') {
+                continue;
+            }
+            if let Some(before) = old_lines.iter().find(|o| weakens(o, line)) {
+                hits.push((before.to_string(), line.to_string()));
+            }
+        }
+    }
+    if hits.is_empty() {
+        return Vec::new();
+    }
+
+    let list = hits
+        .iter()
+        .map(|(before, after)| format!("`{}` → `{}`", before, after))
+        .collect::<Vec<_>>()
+        .join(", ");
+    vec![Finding::warn(
+        "weakened-type",
+        format!(
+            "Type weakened in {}: {}. Loosening a type until the code compiles hides the \
+             mismatch instead of fixing it. Fix the value or the caller so the concrete type \
+             holds, or confirm with the user that the wider type is intended.",
+            display_path(&write.file_path),
+            list
+        ),
+    )]
+}
+
+/// Whether `after` is `before` with a concrete type swapped for a weak one:
+/// the lines share everything but a middle run of tokens, the old run has no
+/// weak type, and the new run has one.
+fn weakens(before: &str, after: &str) -> bool {
+    let old = type_tokens(before);
+    let new = type_tokens(after);
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let max_suffix = old.len().min(new.len()) - prefix;
+    let suffix = old
+        .iter()
+        .rev()
+        .zip(new.iter().rev())
+        .take(max_suffix)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    // Require real shared context so two unrelated lines don't pair up.
+    prefix + suffix >= 2
+        && !old_mid.is_empty()
+        && old_mid.iter().all(|t| !WEAK_TYPES.contains(&t.as_str()))
+        && old_mid.iter().any(|t| t.starts_with(|c: char| c.is_alphabetic()))
+        && new_mid.iter().any(|t| WEAK_TYPES.contains(&t.as_str()))
+}
+
+/// Identifiers and single punctuation characters, with `interface{}` kept as
+/// one token.
+fn type_tokens(line: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = Vec::new();
+    let mut ident = String::new();
+    for c in line.chars() {
+        if c.is_alphanumeric() || c == '_' {
+            ident.push(c);
+            continue;
+        }
+        if !ident.is_empty() {
+            tokens.push(std::mem::take(&mut ident));
+        }
+        if c.is_whitespace() {
+            continue;
+        }
+        if c == '}' && tokens.ends_with(&["interface".to_string(), "{".to_string()]) {
+            tokens.truncate(tokens.len() - 2);
+            tokens.push("interface{}".to_string());
+            continue;
+        }
+        tokens.push(c.to_string());
+    }
+    if !ident.is_empty() {
+        tokens.push(ident);
+    }
+    tokens
+}
+
+// ---------------------------------------------------------------------------
+// Check 4: Masked flakiness
+// ---------------------------------------------------------------------------
+
+/// Retry and flaky-test annotations across the common test frameworks,
+/// matched lowercase.
+const RETRY_MARKERS: &[&str] = &[
+    "retrytimes(",
+    ".retries(",
+    "retries:",
+    "retries =",
+    "retries=",
+    "@flaky",
+    "mark.flaky",
+    "@retry",
+    "@retryingtest",
+    "#[retry",
+    "flaky(",
+];
+
+fn check_masked_flakiness(write: &WriteOp) -> Vec<Finding> {
+    if !is_test_path(&write.file_path) {
+        return Vec::new();
+    }
+    let mut hits: Vec<String> = Vec::new();
+    for (old, new) in &write.hunks {
+        let old_lines: Vec<&str> = old.lines().map(str::trim).collect();
+        for line in new.lines().map(str::trim) {
+            if old_lines.contains(&line) || line.starts_with("//") || line.starts_with("# ") {
+                continue;
+            }
+            if let Some(before) = old_lines.iter().find(|o| raises_timeout(o, line)) {
+                hits.push(format!("`{}` → `{}`", before, line));
+            } else if adds_retry(line) {
+                hits.push(format!("`{}`", line));
+            }
+        }
+    }
+    if hits.is_empty() {
+        return Vec::new();
+    }
+
+    vec![Finding::warn(
+        "masked-flakiness",
+        format!(
+            "Flakiness masked in {}: {}. A longer timeout or a retry makes a flaky test pass \
+             without fixing why it is flaky (a race, an unawaited call, a slow dependency). \
+             Find and fix the cause, or confirm with the user that the longer wait or retry \
+             is intended.",
+            display_path(&write.file_path),
+            hits.join(", ")
+        ),
+    )]
+}
+
+/// Whether `after` is `before`, a line mentioning a timeout, with only its
+/// numbers changed and at least one of them raised.
+fn raises_timeout(before: &str, after: &str) -> bool {
+    if !after.to_lowercase().contains("timeout") {
+        return false;
+    }
+    let (old_text, old_nums) = split_numbers(before);
+    let (new_text, new_nums) = split_numbers(after);
+    old_text == new_text
+        && old_nums.len() == new_nums.len()
+        && old_nums.iter().zip(&new_nums).any(|(a, b)| b > a)
+}
+
+/// A line with its numbers (`30_000` counts as one) replaced by `#`, and the
+/// numbers in order.
+fn split_numbers(line: &str) -> (String, Vec<u64>) {
+    let mut text = String::new();
+    let mut nums = Vec::new();
+    let mut digits = String::new();
+    let mut prev_ident = false;
+    for c in line.chars() {
+        if c.is_ascii_digit() && (!digits.is_empty() || !prev_ident) {
+            digits.push(c);
+            continue;
+        }
+        if c == '_' && !digits.is_empty() {
+            continue;
+        }
+        if !digits.is_empty() {
+            nums.push(std::mem::take(&mut digits).parse().unwrap_or(u64::MAX));
+            text.push('#');
+        }
+        prev_ident = c.is_alphanumeric() || c == '_';
+        text.push(c);
+    }
+    if !digits.is_empty() {
+        nums.push(digits.parse().unwrap_or(u64::MAX));
+        text.push('#');
+    }
+    (text, nums)
+}
+
+/// Whether the line adds a retry or flaky annotation. `retries: 0` and the
+/// like turn retries off and are clean.
+fn adds_retry(line: &str) -> bool {
+    let lower = line.to_lowercase();
+    RETRY_MARKERS.iter().any(|m| {
+        lower.find(m).is_some_and(|pos| {
+            let rest = &lower[pos + m.len()..];
+            let first: String = rest
+                .trim_start_matches(|c: char| !c.is_ascii_digit() && c != ')' && c != '\n')
+                .chars()
+                .take_while(char::is_ascii_digit)
+                .collect();
+            first.parse::<u64>().map_or(true, |n| n > 0)
+        })
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Check 5: File-level suppression
+// ---------------------------------------------------------------------------
+
+/// Lints that, allowed crate- or module-wide, silence (nearly) everything.
+const BLANKET_RUST_LINTS: &[&str] = &["warnings", "clippy::all"];
+
+fn check_file_suppression(write: &WriteOp) -> Vec<Finding> {
+    let mut hits: Vec<&str> = Vec::new();
+    for (old, new) in &write.hunks {
+        let old_lines: Vec<&str> = old.lines().map(str::trim).collect();
+        // Python only honors `# type: ignore` as file-wide before any code.
+        let mut before_code = true;
+        for line in new.lines().map(str::trim) {
+            let added = !old_lines.contains(&line) && !hits.contains(&line);
+            if added && is_file_suppression(line, before_code) {
+                hits.push(line);
+            }
+            if !line.is_empty() && !line.starts_with('#') {
+                before_code = false;
+            }
+        }
+    }
+    if hits.is_empty() {
+        return Vec::new();
+    }
+
+    let list = hits
+        .iter()
+        .map(|l| format!("`{}`", l))
+        .collect::<Vec<_>>()
+        .join(", ");
+    vec![Finding::block(
+        "file-suppression",
+        format!(
+            "File-level suppression added to {}: {}. It silences every current and future \
+             diagnostic in the file, not just the one in the way. Fix the diagnostics, or \
+             suppress the specific rule where it fires (`// @ts-expect-error` on the line, \
+             `#[allow(clippy::lint)]` on the item, `# type: ignore[code]`, \
+             `// eslint-disable-next-line rule`).",
+            display_path(&write.file_path),
+            list
+        ),
+    )]
+}
+
+/// Whether a trimmed line turns off checking for its whole file. `at_top`:
+/// no code precedes it in the written text.
+fn is_file_suppression(line: &str, at_top: bool) -> bool {
+    if let Some(lints) = line.strip_prefix("#![allow(").and_then(|l| l.strip_suffix(")]")) {
+        return lints.split(',').any(|l| BLANKET_RUST_LINTS.contains(&l.trim()));
+    }
+    if at_top && matches!(line, "# type: ignore" | "# mypy: ignore-errors") {
+        return true;
+    }
+    if !(line.starts_with("//") || line.starts_with("/*")) {
+        return false;
+    }
+    if line.contains("@ts-nocheck") {
+        return true;
+    }
+    // `/* eslint-disable */` with no rule list; `-line`/`-next-line` are scoped.
+    line.find("eslint-disable").is_some_and(|pos| {
+        let rest = line[pos + "eslint-disable".len()..]
+            .trim_end_matches("*/")
+            .trim();
+        rest.is_empty() || rest.starts_with("--")
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Check 6: Unbalanced delimiters
+// ---------------------------------------------------------------------------
+
+const BRACKETS: [(u8, u8); 3] = [(b'(', b')'), (b'[', b']'), (b'{', b'}')];
+
+/// How a language writes strings, as far as bracket counting cares. Every
+/// judged language has `//` and `/* */` comments.
+#[derive(Clone, Copy, Debug)]
+struct Syntax {
+    /// `'…'` is a string or char literal; in Rust it may be a lifetime.
+    single_quote_strings: bool,
+    /// `"…"` may span lines (Rust); elsewhere one left open at a line end is
+    /// unterminated.
+    multiline_strings: bool,
+    /// `/…/` regex literals (JS/TS).
+    regex_literals: bool,
+}
+
+fn syntax_for(path: &str) -> Option<Syntax> {
+    let name = path.rsplit(['/', '\\']).next()?;
+    let ext = name.rsplit_once('.')?.1.to_ascii_lowercase();
+    let c_like = Syntax {
+        single_quote_strings: true,
+        multiline_strings: false,
+        regex_literals: false,
+    };
+    match ext.as_str() {
+        "rs" => Some(Syntax {
+            single_quote_strings: false,
+            multiline_strings: true,
+            ..c_like
+        }),
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "mts" | "cts" => Some(Syntax {
+            regex_literals: true,
+            ..c_like
+        }),
+        "c" | "h" | "cc" | "cpp" | "hpp" | "cs" | "java" | "go" | "json" => Some(c_like),
+        _ => None,
+    }
+}
+
+/// Bracket and string state at the end of a piece of code.
+#[derive(Debug, Default)]
+struct Balance {
+    /// Opened minus closed, per [`BRACKETS`] entry.
+    open: [i64; 3],
+    /// 1-based lines with a string left open at the line end.
+    open_strings: Vec<usize>,
+}
+
+fn check_unbalanced(write: &WriteOp) -> Vec<Finding> {
+    let Some(syntax) = syntax_for(&write.file_path) else {
+        return Vec::new();
+    };
+    let mut problems: Vec<String> = Vec::new();
+    for (old, new) in &write.hunks {
+        let Some(after) = balance(new, syntax) else {
+            continue;
+        };
+        // An edit is judged by what it changes: replacing `if a {` with
+        // `if b {` keeps the file as balanced as it was.
+        let Some(before) = balance(old, syntax) else {
+            continue;
+        };
+        for (k, &(open, close)) in BRACKETS.iter().enumerate() {
+            let diff = after.open[k] - before.open[k];
+            let (more, than) = if diff > 0 { (open, close) } else { (close, open) };
+            if diff != 0 {
+                problems.push(format!(
+                    "{} more `{}` than `{}`",
+                    diff.abs(),
+                    more as char,
+                    than as char
+                ));
+            }
+        }
+        if before.open_strings.is_empty() {
+            for line in after.open_strings {
+                problems.push(format!("unterminated string on line {} of the new text", line));
+            }
+        }
+    }
+    if problems.is_empty() {
+        return Vec::new();
+    }
+
+    vec![Finding::warn(
+        "unbalanced",
+        format!(
+            "Unbalanced delimiters written to {}: {}. The write looks truncated or cut \
+             mid-block. Re-read the file and complete or fix the code so it parses.",
+            display_path(&write.file_path),
+            problems.join(", ")
+        ),
+    )]
+}
+
+/// Count brackets outside strings, comments and regex literals. `None` when
+/// the scan can't be trusted: raw strings and text blocks, or a block comment,
+/// multi-line string or regex left open.
+fn balance(text: &str, syntax: Syntax) -> Option<Balance> {
+    if text.contains("\"\"\"") || text.contains("r#\"") {
+        return None;
+    }
+    let b = text.as_bytes();
+    let mut bal = Balance::default();
+    let mut line = 1;
+    // Last significant byte, to tell a regex literal from a division.
+    let mut prev = b'\n';
+    let mut i = 0;
+    while i < b.len() {
+        let c = b[i];
+        match c {
+            b'\n' => {
+                line += 1;
+                i += 1;
+                continue;
+            }
+            b' ' | b'\t' | b'\r' => {
+                i += 1;
+                continue;
+            }
+            b'/' if b.get(i + 1) == Some(&b'/') => {
+                i = b[i..].iter().position(|&c| c == b'\n').map_or(b.len(), |p| i + p);
+                continue;
+            }
+            b'/' if b.get(i + 1) == Some(&b'*') => {
+                let end = text[i + 2..].find("*/")? + i + 4;
+                line += b[i..end].iter().filter(|&&c| c == b'\n').count();
+                i = end;
+            }
+            b'"' | b'`' => {
+                let multiline = c == b'`' || syntax.multiline_strings;
+                i = skip_string(b, i, multiline, &mut line, &mut bal.open_strings)?;
+            }
+            b'\'' if syntax.single_quote_strings => {
+                i = skip_string(b, i, false, &mut line, &mut bal.open_strings)?;
+            }
+            b'\'' => i = skip_rust_quote(text, i),
+            b'/' if syntax.regex_literals && b"(,=:[!&|?{};\n".contains(&prev) => {
+                i = skip_regex(b, i)?;
+            }
+            _ => {
+                if let Some(k) = BRACKETS.iter().position(|&(o, _)| o == c) {
+                    bal.open[k] += 1;
+                } else if let Some(k) = BRACKETS.iter().position(|&(_, cl)| cl == c) {
+                    bal.open[k] -= 1;
+                }
+                i += 1;
+            }
+        }
+        prev = if i > 0 { b[i - 1] } else { b'\n' };
+    }
+    Some(bal)
+}
+
+/// Given `b[i]` is a quote, return the index past its closing quote. A
+/// single-line string left open records its line and ends at the newline;
+/// an open multi-line string is `None`.
+fn skip_string(
+    b: &[u8],
+    i: usize,
+    multiline: bool,
+    line: &mut usize,
+    open_strings: &mut Vec<usize>,
+) -> Option<usize> {
+    let quote = b[i];
+    let mut j = i + 1;
+    while j < b.len() {
+        match b[j] {
+            b'\\' => {
+                if b.get(j + 1) == Some(&b'\n') {
+                    *line += 1;
+                }
+                j += 2;
+                continue;
+            }
+            b'\n' if !multiline => {
+                open_strings.push(*line);
+                return Some(j);
+            }
+            b'\n' => *line += 1,
+            c if c == quote => return Some(j + 1),
+            _ => {}
+        }
+        j += 1;
+    }
+    if multiline {
+        return None;
+    }
+    open_strings.push(*line);
+    Some(b.len())
+}
+
+/// Rust `'`: a char literal (`'{'`, `'\\''`, `'\\u{7b}'`) is skipped whole; a
+/// lifetime (`'a`) only loses its quote.
+fn skip_rust_quote(text: &str, i: usize) -> usize {
+    let rest = &text[i + 1..];
+    if let Some(escaped) = rest.strip_prefix('\\') {
+        if let Some(end) = escaped.get(1..).and_then(|e| e.find('\'')).filter(|&e| e < 10) {
+            return i + 3 + end + 1;
+        }
+    } else if let Some(c) = rest.chars().next() {
+        if rest[c.len_utf8()..].starts_with('\'') {
+            return i + 1 + c.len_utf8() + 1;
+        }
+    }
+    i + 1
+}
+
+/// Given `b[i]` starts a regex literal, return the index past its closing
+/// `/`. `None` when it doesn't close on the same line (likely not a regex).
+fn skip_regex(b: &[u8], i: usize) -> Option<usize> {
+    let mut j = i + 1;
+    let mut in_class = false;
+    while j < b.len() {
+        match b[j] {
+            b'\\' => j += 1,
+            b'\n' => return None,
+            b'[' => in_class = true,
+            b']' => in_class = false,
+            b'/' if !in_class => return Some(j + 1),
+            _ => {}
+        }
+        j += 1;
+    }
+    None
+}
+
+// ---------------------------------------------------------------------------
+// Check 7: Hardcoded localhost
+// ---------------------------------------------------------------------------
+
+const LOCAL_HOSTS: &[&str] = &["localhost", "127.0.0.1"];
+
+fn check_hardcoded_localhost(write: &WriteOp, dev_globs: &[String]) -> Vec<Finding> {
+    if is_test_path(&write.file_path) || dev_globs.iter().any(|g| config::glob_match(g, &write.file_path)) {
+        return Vec::new();
+    }
+    let hits: Vec<&str> = added_lines(write)
+        .into_iter()
+        .filter(|l| hardcodes_localhost(l))
+        .collect();
+    if hits.is_empty() {
+        return Vec::new();
+    }
+
+    let list = hits
+        .iter()
+        .map(|l| format!("`{}`", l))
+        .collect::<Vec<_>>()
+        .join(", ");
+    vec![Finding::warn(
+        "hardcoded-localhost",
+        format!(
+            "Hardcoded localhost written to {}: {}. It only works on the machine it was \
+             written on; in a container, CI or production the service is elsewhere. Read the \
+             host or URL from configuration or the environment, with localhost as the default \
+             at most.",
+            display_path(&write.file_path),
+            list
+        ),
+    )]
+}
+
+/// Whether a line names a local host as a whole word, outside a comment and
+/// without reading the environment on the same line.
+fn hardcodes_localhost(line: &str) -> bool {
+    if line.starts_with('#') || line.starts_with("//") || line.starts_with("/*") || line.starts_with('*') {
+        return false;
+    }
+    let lower = line.to_lowercase();
+    if lower.contains("env") {
+        return false;
+    }
+    let word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '.');
+    LOCAL_HOSTS.iter().any(|host| {
+        lower.match_indices(host).any(|(i, _)| {
+            !word(lower[..i].chars().next_back()) && !word(lower[i + host.len()..].chars().next())
+        })
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Check 8: Placeholder URL
+// ---------------------------------------------------------------------------
+
+fn check_placeholder_url(write: &WriteOp, hosts: &[String], dev_globs: &[String]) -> Vec<Finding> {
+    if is_test_path(&write.file_path) || dev_globs.iter().any(|g| config::glob_match(g, &write.file_path)) {
+        return Vec::new();
+    }
+    let mut urls: Vec<&str> = Vec::new();
+    for line in added_lines(write) {
+        if line.starts_with('#') || line.starts_with("//") || line.starts_with("/*") || line.starts_with('*') {
+            continue;
+        }
+        for url in placeholder_urls(line, hosts) {
+            if !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+    }
+    if urls.is_empty() {
+        return Vec::new();
+    }
+
+    let list = urls
+        .iter()
+        .map(|u| format!("`{}`", u))
+        .collect::<Vec<_>>()
+        .join(", ");
+    vec![Finding::warn(
+        "placeholder-url",
+        format!(
+            "Placeholder URL written to {}: {}. Nothing real answers there, so the first \
+             request in production fails. Use the actual endpoint, or read it from \
+             configuration and fail clearly when it is missing.",
+            display_path(&write.file_path),
+            list
+        ),
+    )]
+}
+
+/// The `http://`/`https://` URLs in `line` whose host is one of `hosts`
+/// (case-insensitive) or a subdomain of one.
+fn placeholder_urls<'a>(line: &'a str, hosts: &[String]) -> Vec<&'a str> {
+    let mut found = Vec::new();
+    let lower = line.to_ascii_lowercase();
+    for (start, scheme) in lower.match_indices("http://").chain(lower.match_indices("https://")) {
+        let rest = &line[start + scheme.len()..];
+        let url_len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '`' | ')' | '>' | ','))
+            .unwrap_or(rest.len());
+        let host_len = rest[..url_len].find(['/', ':', '?', '#']).unwrap_or(url_len);
+        let host = rest[..host_len].to_ascii_lowercase();
+        let placeholder = hosts.iter().any(|h| {
+            let h = h.to_ascii_lowercase();
+            host == h || host.ends_with(&format!(".{}", h))
+        });
+        if placeholder {
+            found.push(&line[start..start + scheme.len() + url_len]);
+        }
+    }
+    found
+}
+
+// ---------------------------------------------------------------------------
+// Check 9: Hidden error log
+// ---------------------------------------------------------------------------
+
+/// Log levels by severity, matched lowercase. `log` is `console.log`.
+const LOG_LEVELS: &[(&str, u8)] = &[
+    ("fatal", 5),
+    ("critical", 5),
+    ("error", 4),
+    ("exception", 4),
+    ("severe", 4),
+    ("warn", 3),
+    ("warning", 3),
+    ("info", 2),
+    ("notice", 2),
+    ("log", 2),
+    ("debug", 1),
+    ("verbose", 1),
+    ("fine", 1),
+    ("trace", 0),
+];
+
+/// Lowest level still counted as error logging.
+const ERROR_LEVEL: u8 = 4;
+
+/// Conditions that never hold, used to switch a block off.
+const DEAD_CONDITIONS: &[&str] = &["if (false)", "if(false)", "if false", "if False:", "if (0)", "if(0)", "if 0:"];
+
+fn check_hidden_error_log(write: &WriteOp) -> Vec<Finding> {
+    let mut hits: Vec<String> = Vec::new();
+    for (old, new) in &write.hunks {
+        let old_lines: Vec<&str> = old.lines().map(str::trim).collect();
+        let new_lines: Vec<&str> = new.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+        for (i, line) in new_lines.iter().enumerate() {
+            if old_lines.contains(line) {
+                continue;
+            }
+            if let Some(before) = old_lines.iter().find(|o| downgrades_log(o, line)) {
+                hits.push(format!("`{}` → `{}`", before, line));
+            } else if old_lines.iter().any(|o| uncommented(line) == Some(*o) && is_error_log(o)) {
+                hits.push(format!("`{}` commented out", uncommented(line).unwrap_or(line)));
+            } else if DEAD_CONDITIONS.iter().any(|c| line.starts_with(c))
+                && new_lines.get(i + 1).is_some_and(|next| is_error_log(next))
+            {
+                hits.push(format!("`{}` behind `{}`", new_lines[i + 1], line));
+            }
+        }
+    }
+    if hits.is_empty() {
+        return Vec::new();
+    }
+
+    vec![Finding::warn(
+        "hidden-error-log",
+        format!(
+            "Error logging reduced in {}: {}. Quieting an error log to clean up the output \
+             hides the failure it reports. Fix the cause of the error, or confirm with the \
+             user that it is expected before lowering its level.",
+            display_path(&write.file_path),
+            hits.join(", ")
+        ),
+    )]
+}
+
+/// The level of the log call or level constant at `tokens[i]`: a level name
+/// after `.`/`:` (`logger.error(`, `log::error!`, `logging.ERROR`) or a level
+/// macro (`error!(`).
+fn log_level(tokens: &[String], i: usize) -> Option<u8> {
+    let lower = tokens[i].to_lowercase();
+    let level = LOG_LEVELS.iter().find(|(name, _)| *name == lower)?.1;
+    let after_path = i > 0 && matches!(tokens[i - 1].as_str(), "." | ":");
+    let is_macro = tokens.get(i + 1).is_some_and(|t| t == "!") && tokens.get(i + 2).is_some_and(|t| t == "(");
+    (after_path || is_macro).then_some(level)
+}
+
+fn is_error_log(line: &str) -> bool {
+    let tokens = type_tokens(line);
+    (0..tokens.len()).any(|i| log_level(&tokens, i).is_some_and(|l| l >= ERROR_LEVEL))
+}
+
+/// Whether `after` is `before` with an error-level log call or constant
+/// swapped for a lower level, and nothing else changed.
+fn downgrades_log(before: &str, after: &str) -> bool {
+    let old = type_tokens(before);
+    let new = type_tokens(after);
+    if old.len() != new.len() {
+        return false;
+    }
+    let mut changed = (0..old.len()).filter(|&i| old[i] != new[i]);
+    let (Some(i), None) = (changed.next(), changed.next()) else {
+        return false;
+    };
+    match (log_level(&old, i), log_level(&new, i)) {
+        (Some(was), Some(now)) => was >= ERROR_LEVEL && now < was,
+        _ => false,
+    }
+}
+
+/// The code of a line-commented line, `None` if it isn't one.
+fn uncommented(line: &str) -> Option<&str> {
+    ["//", "#", "--"].iter().find_map(|m| line.strip_prefix(m)).map(str::trim)
+}
+
+// ---------------------------------------------------------------------------
+// Check 10: Tautological assertion
+// ---------------------------------------------------------------------------
+
+/// Assertions that pass when their first argument is truthy.
+const TRUTHY_ASSERTS: &[&str] = &["assert", "assert!", "debug_assert!", "assertTrue", "assert_true", "assert.ok"];
+
+/// Assertions that compare their first two arguments.
+const EQUALITY_ASSERTS: &[&str] = &[
+    "assert_eq!",
+    "debug_assert_eq!",
+    "assertEquals",
+    "assertEqual",
+    "assertSame",
+    "assert.equal",
+    "assert.strictEqual",
+    "assert.deepEqual",
+    "assert.deepStrictEqual",
+];
+
+/// `expect(a)` matchers that compare `a` with their argument.
+const EXPECT_MATCHERS: &[&str] = &["toBe", "toEqual", "toStrictEqual"];
+
+fn check_tautological_assertion(write: &WriteOp) -> Vec<Finding> {
+    // Rust tests live next to the code, so any Rust file counts.
+    if !is_test_path(&write.file_path) && !write.file_path.ends_with(".rs") {
+        return Vec::new();
+    }
+    let hits: Vec<&str> = added_lines(write)
+        .into_iter()
+        .filter(|l| !l.starts_with("//") && !l.starts_with('#') && is_tautological_assertion(l))
+        .collect();
+    if hits.is_empty() {
+        return Vec::new();
+    }
+
+    let list = hits
+        .iter()
+        .map(|l| format!("`{}`", l))
+        .collect::<Vec<_>>()
+        .join(", ");
+    vec![Finding::block(
+        "tautological-assertion",
+        format!(
+            "Tautological assertion added to {}: {}. It passes whatever the code under test \
+             does, so the test no longer checks anything. Restore the real assertion and fix \
+             the code it caught (or the expected value, if that was wrong); if the test is \
+             obsolete, delete it with the user's agreement.",
+            display_path(&write.file_path),
+            list
+        ),
+    )]
+}
+
+/// Whether the line asserts something that can't fail: `assert(true)`,
+/// `assertTrue(true)`, Python `assert True`, `assert_eq!(1, 1)`,
+/// `expect(true).toBe(true)`, `expect(1).toBeTruthy()`, `assert 1 == 1`.
+fn is_tautological_assertion(line: &str) -> bool {
+    for (pos, name) in call_positions(line, TRUTHY_ASSERTS) {
+        let rest = line[pos + name.len()..].trim_start();
+        let args = match rest.strip_prefix('(') {
+            Some(args) => call_args(args),
+            // Python's `assert` statement.
+            None if name == "assert" && line[pos + name.len()..].starts_with([' ', '\t']) => rest,
+            None => continue,
+        };
+        if split_args(args).first().is_some_and(|a| always_true(a)) {
+            return true;
+        }
+    }
+    for (pos, name) in call_positions(line, EQUALITY_ASSERTS) {
+        let Some(args) = line[pos + name.len()..].trim_start().strip_prefix('(') else {
+            continue;
+        };
+        if let [a, b, ..] = split_args(call_args(args)).as_slice() {
+            if same_literal(a, b) {
+                return true;
+            }
+        }
+    }
+    for (pos, name) in call_positions(line, &["expect"]) {
+        let Some(text) = line[pos + name.len()..].strip_prefix('(') else {
+            continue;
+        };
+        let actual = call_args(text);
+        let Some(chain) = text[actual.len()..].strip_prefix(").") else {
+            continue;
+        };
+        if chain.starts_with("toBeTruthy()") && always_true(actual) {
+            return true;
+        }
+        for matcher in EXPECT_MATCHERS {
+            if let Some(expected) = chain.strip_prefix(matcher).and_then(|c| c.strip_prefix('(')) {
+                if same_literal(actual, call_args(expected)) {
+                    return true;
+                }
+            }
+        }
+    }
+    false
+}
+
+/// Byte offsets of `names` as calls: not part of a longer identifier, though
+/// possibly a method (`self.assertTrue`).
+fn call_positions<'a>(line: &str, names: &[&'a str]) -> Vec<(usize, &'a str)> {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    let mut out = Vec::new();
+    for &name in names {
+        for (pos, _) in line.match_indices(name) {
+            let before = line[..pos].chars().next_back();
+            let after = line[pos + name.len()..].chars().next();
+            if before.is_some_and(|c| is_ident(c) || c == '$')
+                || (name.ends_with(is_ident) && after.is_some_and(|c| is_ident(c) || c == '.' || c == '!'))
+            {
+                continue;
+            }
+            out.push((pos, name));
+        }
+    }
+    out
+}
+
+/// Top-level comma-separated arguments, trimmed; commas inside brackets and
+/// strings don't split.
+fn split_args(text: &str) -> Vec<&str> {
+    let mut args = Vec::new();
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"' | '`') => quote = Some(c),
+            (None, '(' | '[' | '{') => depth += 1,
+            (None, ')' | ']' | '}') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                args.push(text[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    args.push(text[start..].trim());
+    args
+}
+
+/// `true`, `True`, `1`, or two identical literals compared with `==`/`===`.
+fn always_true(arg: &str) -> bool {
+    if matches!(arg, "true" | "True" | "1") {
+        return true;
+    }
+    let sides = arg.split_once("===").or_else(|| arg.split_once("=="));
+    sides.is_some_and(|(a, b)| same_literal(a.trim(), b.trim()))
+}
+
+/// Whether `a` and `b` are the same boolean, number, null or constant string.
+fn same_literal(a: &str, b: &str) -> bool {
+    let literal = matches!(a, "true" | "false" | "True" | "False" | "None" | "null" | "undefined")
+        || a.replace('_', "").parse::<f64>().is_ok()
+        || is_constant_literal(a);
+    literal && a == b
+}
+
+// ---------------------------------------------------------------------------
+// Reason
+// ---------------------------------------------------------------------------
+
+fn build_reason(findings: &[&Finding]) -> String {
+    let list = findings
+        .iter()
+        .map(|f| format!("  - {}", f.message))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!("write-smell-detector flagged the last write:\n{}", list)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn write(tool: &str, input: Value) -> WriteOp {
+        WriteOp::from_tool_input(tool, &input).unwrap()
+    }
+
+    fn defaults() -> Vec<String> {
+        Config::default().insecure_patterns()
+    }
+
+    // -- Focus files ------------------------------------------------------------
+
+    #[test]
+    fn excluded_path_is_not_reviewed() {
+        let cfg: Config =
+            toml::from_str("include_globs = [\"src/**\"]\nexclude_globs = [\"**/*.test.js\"]").unwrap();
+        let content = "res.setHeader(\"Access-Control-Allow-Origin\", \"*\");";
+
+        let w = write("Write", json!({"file_path": "/repo/src/server.js", "content": content}));
+        assert_eq!(review(&w, &cfg).len(), 1);
+
+        for path in ["/repo/scripts/dev.js", "/repo/src/server.test.js"] {
+            let w = write("Write", json!({"file_path": path, "content": content}));
+            assert!(review(&w, &cfg).is_empty(), "{} was reviewed", path);
+        }
+        let w = write("Write", json!({"file_path": "/repo/scripts/dev.js", "content": content}));
+        assert_eq!(review(&w, &Config::default()).len(), 1);
+    }
+
+    // -- Check 1: Permissive config -------------------------------------------
+
+    #[test]
+    fn warns_on_added_wildcard_allow_origin() {
+        let w = write(
+            "Edit",
+            json!({
+                "file_path": "nginx.conf",
+                "old_string": "location / {",
+                "new_string": "location / {\n  add_header Access-Control-Allow-Origin: *;"
+            }),
+        );
+        let findings = check_permissive_config(&w, &defaults());
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].message.contains("Access-Control-Allow-Origin: *"));
+        assert!(findings[0].message.contains("nginx.conf"));
+    }
+
+    #[test]
+    fn warns_on_header_setter_call() {
+        let w = write(
+            "Write",
+            json!({
+                "file_path": "server.js",
+                "content": "res.setHeader(\"Access-Control-Allow-Origin\", \"*\");"
+            }),
+        );
+        assert_eq!(check_permissive_config(&w, &defaults()).len(), 1);
+    }
+
+    #[test]
+    fn warns_on_zero_bind() {
+        let w = write(
+            "Write",
+            json!({"file_path": "config/prod.toml", "content": "[server]\nhost = \"0.0.0.0\"\n"}),
+        );
+        assert_eq!(check_permissive_config(&w, &defaults()).len(), 1);
+    }
+
+    #[test]
+    fn scoped_origin_is_clean() {
+        let w = write(
+            "Edit",
+            json!({
+                "file_path": "nginx.conf",
+                "old_string": "location / {",
+                "new_string": "location / {\n  add_header Access-Control-Allow-Origin: https://app.example.org;"
+            }),
+        );
+        assert!(check_permissive_config(&w, &defaults()).is_empty());
+    }
+
+    #[test]
+    fn pre_existing_pattern_is_not_reported() {
+        let w = write(
+            "Edit",
+            json!({
+                "file_path": "server.js",
+                "old_string": "cors({ origin: '*' })",
+                "new_string": "cors({ origin: '*', credentials: false })"
+            }),
+        );
+        assert!(check_permissive_config(&w, &defaults()).is_empty());
+    }
+
+    #[test]
+    fn config_extends_and_replaces_banlist() {
+        let cfg: Config = toml::from_str("extra_insecure_patterns = [\"verify_ssl = false\"]").unwrap();
+        let patterns = cfg.insecure_patterns();
+        assert!(patterns.iter().any(|p| p == "verify_ssl = false"));
+        assert!(patterns.len() > 1);
+
+        let cfg: Config = toml::from_str("insecure_patterns = [\"debug = true\"]").unwrap();
+        assert_eq!(cfg.insecure_patterns(), vec!["debug = true"]);
+
+        let w = write("Write", json!({"file_path": "app.toml", "content": "debug = true"}));
+        assert_eq!(check_permissive_config(&w, &cfg.insecure_patterns()).len(), 1);
+    }
+
+    // -- Check 2: Dynamic eval ------------------------------------------------
+
+    fn script(content: &str) -> WriteOp {
+        write("Write", json!({"file_path": "run.sh", "content": content}))
+    }
+
+    #[test]
+    fn warns_on_shell_eval_of_variable() {
+        let findings = check_dynamic_eval(&script("#!/bin/sh\neval \"$userInput\"\n"));
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].message.contains("eval \"$userInput\""));
+        assert!(findings[0].message.contains("run.sh"));
+    }
+
+    #[test]
+    fn clean_shell_script_is_not_warned() {
+        assert!(check_dynamic_eval(&script("echo \"$userInput\"\nexec \"$@\"\n")).is_empty());
+        assert!(check_dynamic_eval(&script("eval \"$(ssh-agent -s)\"")).is_empty());
+        assert!(check_dynamic_eval(&script("# eval \"$x\" is dangerous")).is_empty());
+    }
+
+    #[test]
+    fn warns_on_js_and_python_eval_of_variables() {
+        assert!(is_dynamic_eval("const result = eval(req.body.expr);"));
+        assert!(is_dynamic_eval("const fn = new Function(\"a\", source);"));
+        assert!(is_dynamic_eval("exec(compile(src, path, \"exec\"))"));
+        assert!(is_dynamic_eval("return eval(f\"{a} + {b}\")"));
+        assert!(is_dynamic_eval("eval(`${op}(x)`)"));
+    }
+
+    #[test]
+    fn constant_literals_and_method_calls_are_not_warned() {
+        assert!(!is_dynamic_eval("eval(\"1 + 2\")"));
+        assert!(!is_dynamic_eval("exec('import sys')"));
+        assert!(!is_dynamic_eval("model.eval()"));
+        assert!(!is_dynamic_eval("child.exec(cmd)"));
+        assert!(!is_dynamic_eval("const evaluate = evaluator(x);"));
+    }
+
+    #[test]
+    fn pre_existing_eval_is_not_reported() {
+        let w = write(
+            "Edit",
+            json!({
+                "file_path": "calc.js",
+                "old_string": "return eval(expr);",
+                "new_string": "// evaluate user math\nreturn eval(expr);"
+            }),
+        );
+        assert!(check_dynamic_eval(&w).is_empty());
+    }
+
+    // -- Check 3: Weakened type ------------------------------------------------
+
+    fn edit(old: &str, new: &str) -> WriteOp {
+        write("Edit", json!({"file_path": "src/user.ts", "old_string": old, "new_string": new}))
+    }
+
+    #[test]
+    fn warns_on_string_to_any() {
+        let w = edit(
+            "function greet(name: string): string {",
+            "function greet(name: any): string {",
+        );
+        let findings = check_weakened_type(&w);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].message.contains("`function greet(name: any): string {`"));
+        assert!(findings[0].message.contains("src/user.ts"));
+    }
+
+    #[test]
+    fn warns_on_other_weak_types() {
+        assert!(weakens("let v = x as User;", "let v = x as unknown;"));
+        assert!(weakens("fn run(h: &Handler) {", "fn run(h: &dyn Any) {"));
+        assert!(weakens("func Load(cfg Config) error {", "func Load(cfg interface{}) error {"));
+        assert!(weakens("def load(path: Path) -> Config:", "def load(path: Any) -> Config:"));
+    }
+
+    #[test]
+    fn unrelated_change_is_clean() {
+        let w = edit(
+            "function greet(name: string): string {\n  return 'hi ' + name;",
+            "function greet(name: string): string {\n  return `Hello, ${name}!`;",
+        );
+        assert!(check_weakened_type(&w).is_empty());
+    }
+
+    #[test]
+    fn already_weak_or_narrowed_types_are_clean() {
+        assert!(!weakens("function f(x: any) {", "function f(x: unknown) {"));
+        assert!(!weakens("function f(x: any) {", "function f(x: string) {"));
+        assert!(!weakens("const items = [];", "const anyItems = [];"));
+    }
+
+    // -- Check 4: Masked flakiness ----------------------------------------------
+
+    fn test_edit(path: &str, old: &str, new: &str) -> WriteOp {
+        write("Edit", json!({"file_path": path, "old_string": old, "new_string": new}))
+    }
+
+    #[test]
+    fn warns_on_raised_test_timeout() {
+        let w = test_edit(
+            "src/api.test.ts",
+            "it('loads', async () => {\n  await load();\n}, 1000);",
+            "it('loads', async () => {\n  await load();\n}, 30000);",
+        );
+        assert!(check_masked_flakiness(&w).is_empty(), "bare number without `timeout` is not judged");
+
+        let w = test_edit("src/api.test.ts", "test.setTimeout(1000);", "test.setTimeout(30_000);");
+        let findings = check_masked_flakiness(&w);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].message.contains("`test.setTimeout(1000);` → `test.setTimeout(30_000);`"));
+        assert!(findings[0].message.contains("src/api.test.ts"));
+
+        assert!(raises_timeout(
+            "await page.waitFor(sel, { timeout: 5000 });",
+            "await page.waitFor(sel, { timeout: 60000 });"
+        ));
+        assert!(raises_timeout("@pytest.mark.timeout(5)", "@pytest.mark.timeout(120)"));
+    }
+
+    #[test]
+    fn lowered_timeout_and_other_changes_are_clean() {
+        assert!(!raises_timeout("jest.setTimeout(30000);", "jest.setTimeout(5000);"));
+        assert!(!raises_timeout("const timeout = 1000;", "const deadline = 2000;"));
+        assert!(!raises_timeout("expect(items).toHaveLength(2);", "expect(items).toHaveLength(3);"));
+    }
+
+    #[test]
+    fn warns_on_added_retries() {
+        assert!(adds_retry("jest.retryTimes(3);"));
+        assert!(adds_retry("this.retries(2);"));
+        assert!(adds_retry("@pytest.mark.flaky(reruns=3)"));
+        assert!(adds_retry("test.describe.configure({ retries: 2 });"));
+        assert!(!adds_retry("test.describe.configure({ retries: 0 });"));
+        assert!(!adds_retry("expect(retryCount).toBe(3);"));
+
+        let w = test_edit("tests/test_sync.py", "def test_sync():", "@pytest.mark.flaky\ndef test_sync():");
+        assert_eq!(check_masked_flakiness(&w).len(), 1);
+    }
+
+    #[test]
+    fn non_test_files_are_not_judged_for_flakiness() {
+        let w = test_edit("src/client.ts", "const timeout = 1000;", "const timeout = 30000;");
+        assert!(check_masked_flakiness(&w).is_empty());
+    }
+
+    // -- Check 5: File-level suppression ---------------------------------------
+
+    #[test]
+    fn blocks_each_file_level_form() {
+        let cases = [
+            ("src/app.ts", "// @ts-nocheck\nimport x from 'x';"),
+            ("src/lib.rs", "#![allow(warnings)]\nmod a;"),
+            ("src/lib.rs", "#![allow(dead_code, clippy::all)]\nmod a;"),
+            ("pkg/mod.py", "#!/usr/bin/env python3\n# type: ignore\nimport os"),
+            ("src/app.js", "/* eslint-disable */\nconst a = 1;"),
+        ];
+        for (path, content) in cases {
+            let w = write("Write", json!({"file_path": path, "content": content}));
+            let findings = check_file_suppression(&w);
+            assert_eq!(findings.len(), 1, "not blocked: {}", content);
+            assert_eq!(findings[0].severity, hook_common::decision::Severity::Block);
+            assert!(findings[0].message.contains(path));
+        }
+    }
+
+    #[test]
+    fn scoped_suppressions_are_allowed() {
+        for line in [
+            "// @ts-expect-error legacy typings",
+            "#[allow(dead_code)]",
+            "#![allow(dead_code)]",
+            "value = obj.attr  # type: ignore[attr-defined]",
+            "/* eslint-disable no-console */",
+            "// eslint-disable-next-line no-console",
+            "const s = '@ts-nocheck';",
+        ] {
+            assert!(!is_file_suppression(line, true), "flagged: {}", line);
+        }
+        assert!(!is_file_suppression("# type: ignore", false));
+    }
+
+    #[test]
+    fn pre_existing_suppression_is_not_reported() {
+        let w = edit("// @ts-nocheck\nlet a = 1;", "// @ts-nocheck\nlet a = 2;");
+        assert!(check_file_suppression(&w).is_empty());
+    }
+
+    // -- Check 6: Unbalanced delimiters ----------------------------------------
+
+    #[test]
+    fn warns_on_truncated_write() {
+        let w = write(
+            "Write",
+            json!({
+                "file_path": "src/app.ts",
+                "content": "export function f(x: number) {\n  if (x > 0) {\n    return g([x, 1]);\n  }\n"
+            }),
+        );
+        let findings = check_unbalanced(&w);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].message.contains("1 more `{` than `}`"), "got: {}", findings[0].message);
+        assert!(findings[0].message.contains("src/app.ts"));
+    }
+
+    #[test]
+    fn warns_on_edit_dropping_a_closer_and_on_open_string() {
+        let w = write(
+            "Edit",
+            json!({"file_path": "src/lib.rs", "old_string": "fn a() {\n    b();\n}\n", "new_string": "fn a() {\n    b(\n"}),
+        );
+        let msg = &check_unbalanced(&w)[0].message;
+        assert!(msg.contains("1 more `(` than `)`"), "got: {}", msg);
+        assert!(msg.contains("1 more `{` than `}`"), "got: {}", msg);
+
+        let w = write("Write", json!({"file_path": "a.js", "content": "const s = \"abc;\nf();\n"}));
+        assert!(check_unbalanced(&w)[0].message.contains("unterminated string on line 1"));
+    }
+
+    #[test]
+    fn balanced_code_is_clean() {
+        let rust = "fn first<'a>(s: &'a str) -> Option<char> {\n    // a stray { in a comment\n    \
+                    let open = '{';\n    let esc = '\\'';\n    /* ) */\n    s.chars().find(|c| *c != open && \"}]\" != \"x\")\n}\n";
+        let w = write("Write", json!({"file_path": "src/lib.rs", "content": rust}));
+        assert!(check_unbalanced(&w).is_empty(), "flagged: {:?}", check_unbalanced(&w));
+
+        let js = "const re = /[(]\\)/g;\nconst t = `a ${b} {`;\nconst half = x / 2; // (\nif (re.test('don\\'t {')) { go(); }\n";
+        let w = write("Write", json!({"file_path": "src/app.js", "content": js}));
+        assert!(check_unbalanced(&w).is_empty(), "flagged: {:?}", check_unbalanced(&w));
+
+        // An edit that keeps the balance it found is clean, even mid-block.
+        let w = edit("if (a) {", "if (a && b) {");
+        assert!(check_unbalanced(&w).is_empty());
+    }
+
+    #[test]
+    fn uncertain_or_unknown_code_is_skipped() {
+        for (path, content) in [
+            ("src/lib.rs", "let s = r#\"{\"#;"),
+            ("src/app.ts", "/* unclosed comment {"),
+            ("src/app.ts", "const t = `unclosed {"),
+            ("notes.md", "(unbalanced"),
+            ("tool.py", "f(("),
+        ] {
+            let w = write("Write", json!({"file_path": path, "content": content}));
+            assert!(check_unbalanced(&w).is_empty(), "flagged {}: {}", path, content);
+        }
+    }
+
+    // -- Check 7: Hardcoded localhost ------------------------------------------
+
+    fn dev_globs() -> Vec<String> {
+        Config::default().dev_file_globs()
+    }
+
+    #[test]
+    fn warns_on_localhost_added_to_source_config() {
+        let w = test_edit(
+            "src/config.rs",
+            "pub const API_URL: &str = API;",
+            "pub const API_URL: &str = \"http://localhost:8080/api\";",
+        );
+        let findings = check_hardcoded_localhost(&w, &dev_globs());
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, "hardcoded-localhost");
+        assert!(findings[0].message.contains("`src/config.rs`"));
+        assert!(findings[0].message.contains("http://localhost:8080/api"));
+
+        let w = write("Write", json!({"file_path": "app/db.py", "content": "conn = connect(host='127.0.0.1')\n"}));
+        assert_eq!(check_hardcoded_localhost(&w, &dev_globs()).len(), 1);
+    }
+
+    #[test]
+    fn dev_files_are_allowed() {
+        let compose = "services:\n  api:\n    environment:\n      DB_HOST: localhost\n";
+        for path in ["docker-compose.dev.yml", "config/.env.local", "tests/api.rs", "README.md"] {
+            let w = write("Write", json!({"file_path": path, "content": compose}));
+            assert!(check_hardcoded_localhost(&w, &dev_globs()).is_empty(), "flagged {}", path);
+        }
+
+        // The exemptions are configurable.
+        let cfg: Config = toml::from_str("extra_dev_file_globs = [\"deploy/local/**\"]").unwrap();
+        let w = write("Write", json!({"file_path": "deploy/local/api.yml", "content": compose}));
+        assert!(check_hardcoded_localhost(&w, &cfg.dev_file_globs()).is_empty());
+        let cfg: Config = toml::from_str("dev_file_globs = []").unwrap();
+        let w = write("Write", json!({"file_path": "docker-compose.dev.yml", "content": compose}));
+        assert_eq!(check_hardcoded_localhost(&w, &cfg.dev_file_globs()).len(), 1);
+    }
+
+    #[test]
+    fn configured_defaults_comments_and_lookalikes_are_clean() {
+        assert!(!hardcodes_localhost("let host = env::var(\"HOST\").unwrap_or(\"localhost\".into());"));
+        assert!(!hardcodes_localhost("const url = process.env.API_URL || 'http://localhost:3000';"));
+        assert!(!hardcodes_localhost("// defaults to localhost"));
+        assert!(!hardcodes_localhost("let name = localhostname();"));
+        assert!(!hardcodes_localhost("let ip = \"127.0.0.10\";"));
+        assert!(hardcodes_localhost("listen: 127.0.0.1:9000"));
+    }
+
+    // -- Check 8: Placeholder URL ---------------------------------------------
+
+    fn placeholder_hosts() -> Vec<String> {
+        Config::default().placeholder_hosts()
+    }
+
+    #[test]
+    fn warns_on_placeholder_endpoint_in_source() {
+        let w = test_edit(
+            "src/client.ts",
+            "const BASE = config.baseUrl;",
+            "const BASE = \"https://api.example.com/v1\";",
+        );
+        let findings = check_placeholder_url(&w, &placeholder_hosts(), &dev_globs());
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, "placeholder-url");
+        assert!(findings[0].message.contains("`src/client.ts`"), "got: {}", findings[0].message);
+        assert!(findings[0].message.contains("`https://api.example.com/v1`"), "got: {}", findings[0].message);
+
+        let w = write("Write", json!({"file_path": "app/hooks.py", "content": "WEBHOOK = 'http://TODO'\n"}));
+        assert_eq!(check_placeholder_url(&w, &placeholder_hosts(), &dev_globs()).len(), 1);
+    }
+
+    #[test]
+    fn placeholder_urls_in_docs_tests_and_real_hosts_pass() {
+        let content = "fetch(\"https://example.com/api\")\n";
+        for path in ["docs/api.md", "README.md", "tests/client.test.ts", "src/client.spec.ts"] {
+            let w = write("Write", json!({"file_path": path, "content": content}));
+            assert!(check_placeholder_url(&w, &placeholder_hosts(), &dev_globs()).is_empty(), "flagged {}", path);
+        }
+        assert!(placeholder_urls("fetch('https://examples.com.au/a')", &placeholder_hosts()).is_empty());
+        assert!(placeholder_urls("fetch('https://notexample.com/a')", &placeholder_hosts()).is_empty());
+        assert_eq!(placeholder_urls("// see https://example.com", &placeholder_hosts()).len(), 1);
+    }
+
+    #[test]
+    fn placeholder_hosts_are_configurable() {
+        let cfg: Config = toml::from_str("extra_placeholder_hosts = [\"acme.test\"]").unwrap();
+        assert_eq!(
+            placeholder_urls("url = \"https://svc.acme.test:8443/x\"", &cfg.placeholder_hosts()),
+            vec!["https://svc.acme.test:8443/x"]
+        );
+        let cfg: Config = toml::from_str("placeholder_hosts = []").unwrap();
+        assert!(placeholder_urls("url = \"https://example.com\"", &cfg.placeholder_hosts()).is_empty());
+    }
+
+    // -- Check 9: Hidden error log ----------------------------------------------
+
+    #[test]
+    fn warns_on_downgraded_error_log() {
+        let w = test_edit(
+            "src/sync.ts",
+            "} catch (e) {\n  logger.error('sync failed', e);\n}",
+            "} catch (e) {\n  logger.debug('sync failed', e);\n}",
+        );
+        let findings = check_hidden_error_log(&w);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, "hidden-error-log");
+        assert!(
+            findings[0].message.contains("`logger.error('sync failed', e);` → `logger.debug('sync failed', e);`"),
+            "got: {}",
+            findings[0].message
+        );
+
+        assert!(downgrades_log("console.error(err);", "console.log(err);"));
+        assert!(downgrades_log("error!(\"sync failed: {}\", e);", "trace!(\"sync failed: {}\", e);"));
+        assert!(downgrades_log("log.setLevel(logging.ERROR)", "log.setLevel(logging.INFO)"));
+    }
+
+    #[test]
+    fn warns_on_commented_out_and_disabled_error_log() {
+        let w = test_edit("src/app.js", "console.error(err);", "// console.error(err);");
+        let findings = check_hidden_error_log(&w);
+        assert!(findings[0].message.contains("`console.error(err);` commented out"), "got: {}", findings[0].message);
+
+        let w = test_edit(
+            "app/jobs.py",
+            "logger.exception('job failed')",
+            "if False:\n    logger.exception('job failed')",
+        );
+        assert!(check_hidden_error_log(&w)[0].message.contains("behind `if False:`"));
+        let w = test_edit("src/app.js", "console.error(err);", "if (false) {\n  console.error(err);\n}");
+        assert_eq!(check_hidden_error_log(&w).len(), 1);
+    }
+
+    #[test]
+    fn unrelated_log_changes_are_clean() {
+        // Reworded message, raised level, and a non-error level lowered.
+        let w = test_edit(
+            "src/sync.ts",
+            "logger.error('sync failed');\nlogger.info('retrying');\nconsole.log(state);",
+            "logger.error('sync failed', { attempt });\nlogger.warn('retrying');\nconsole.debug(state);",
+        );
+        assert!(check_hidden_error_log(&w).is_empty());
+        assert!(!downgrades_log("let error = check(x);", "let debug = check(x);"));
+        assert!(!is_error_log("if err != nil {"));
+    }
+
+    // -- Check 10: Tautological assertion ---------------------------------------
+
+    #[test]
+    fn blocks_each_tautological_assertion_form() {
+        let cases = [
+            ("tests/cart.test.js", "assert(true);"),
+            ("tests/test_cart.py", "assert True"),
+            ("tests/test_cart.py", "self.assertTrue(True)"),
+            ("src/test/java/CartTest.java", "assertTrue(true);"),
+            ("src/cart.spec.ts", "expect(true).toBe(true);"),
+            ("src/cart.spec.ts", "expect(1).toBeTruthy();"),
+            ("src/cart.rs", "assert_eq!(1, 1);"),
+            ("src/cart.rs", "assert!(true, \"total matches\");"),
+            ("tests/test_cart.py", "self.assertEqual('a', 'a')"),
+            ("tests/test_cart.py", "assert 1 == 1"),
+        ];
+        for (path, line) in cases {
+            let w = test_edit(path, "assert_eq!(cart.total(), 30);", line);
+            let findings = check_tautological_assertion(&w);
+            assert_eq!(findings.len(), 1, "not blocked: {}", line);
+            assert_eq!(findings[0].rule, "tautological-assertion");
+            assert_eq!(findings[0].severity, hook_common::decision::Severity::Block);
+            assert!(findings[0].message.contains(&format!("`{}`", line)), "got: {}", findings[0].message);
+        }
+    }
+
+    #[test]
+    fn real_assertions_are_allowed() {
+        for line in [
+            "assert(cart.isEmpty());",
+            "assert total == 30",
+            "self.assertTrue(cart.is_empty())",
+            "expect(total).toBe(30);",
+            "expect(cart.items).toEqual([]);",
+            "assert_eq!(cart.total(), 30);",
+            "assert!(cart.is_empty());",
+            "assert_eq!(1, count);",
+            "assertEquals(\"a\", name);",
+            "// assert(true);",
+            "let asserted = assert_true_or(x);",
+        ] {
+            let w = test_edit("tests/cart.test.js", "", line);
+            assert!(check_tautological_assertion(&w).is_empty(), "flagged: {}", line);
+        }
+    }
+
+    #[test]
+    fn tautologies_outside_tests_or_already_present_are_not_reported() {
+        let w = test_edit("src/cart.js", "let a = 1;", "assert(true);");
+        assert!(check_tautological_assertion(&w).is_empty());
+        let w = test_edit("tests/cart.test.js", "assert(true);", "assert(true);\nlet a = 1;");
+        assert!(check_tautological_assertion(&w).is_empty());
+    }
+}